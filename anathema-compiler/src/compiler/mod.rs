@@ -18,6 +18,8 @@ pub enum Instruction {
     For {
         binding: StringId,
         data: ValueId,
+        sorted_by: Option<ValueId>,
+        filter: Option<ValueId>,
         size: usize,
     },
     View(ViewId),
@@ -79,11 +81,13 @@ impl Compiler {
                 Expression::Else { cond, size } => {
                     self.compile_control_flow(Branch::Else(*cond), *size)
                 }
-                Expression::For {
+                &Expression::For {
                     binding,
                     data,
+                    sorted_by,
+                    filter,
                     size,
-                } => self.compile_for(*binding, *data, *size),
+                } => self.compile_for(binding, data, sorted_by, filter, size),
             }?;
         }
         Ok(())
@@ -138,7 +142,14 @@ impl Compiler {
         Ok(())
     }
 
-    fn compile_for(&mut self, binding: StringId, data: ValueId, size: usize) -> Result<()> {
+    fn compile_for(
+        &mut self,
+        binding: StringId,
+        data: ValueId,
+        sorted_by: Option<ValueId>,
+        filter: Option<ValueId>,
+        size: usize,
+    ) -> Result<()> {
         let instruction_index = self.output.len();
 
         // Inner scope = body
@@ -147,6 +158,8 @@ impl Compiler {
         let instruction = Instruction::For {
             binding,
             data,
+            sorted_by,
+            filter,
             size,
         };
         self.output.insert(instruction_index, instruction);
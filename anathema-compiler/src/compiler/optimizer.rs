@@ -19,6 +19,8 @@ pub(crate) enum Expression {
     For {
         data: ValueId,
         binding: StringId,
+        sorted_by: Option<ValueId>,
+        filter: Option<ValueId>,
         size: usize,
     },
     View(ViewId),
@@ -78,8 +80,13 @@ impl Optimizer {
                 ParseExpr::ScopeStart => unreachable!(
                     "this should not happen as scopes are consumed by other expressions"
                 ),
-                &ParseExpr::For { data, binding } => {
-                    self.opt_for(data, binding);
+                &ParseExpr::For {
+                    data,
+                    binding,
+                    sorted_by,
+                    filter,
+                } => {
+                    self.opt_for(data, binding, sorted_by, filter);
                     continue;
                 }
                 &ParseExpr::View(ident) => {
@@ -181,7 +188,13 @@ impl Optimizer {
         }
     }
 
-    fn opt_for(&mut self, data: ValueId, binding: StringId) {
+    fn opt_for(
+        &mut self,
+        data: ValueId,
+        binding: StringId,
+        sorted_by: Option<ValueId>,
+        filter: Option<ValueId>,
+    ) {
         let start = self.output.len();
         self.opt_scope();
         let end = self.output.len();
@@ -190,6 +203,8 @@ impl Optimizer {
             Expression::For {
                 data,
                 binding,
+                sorted_by,
+                filter,
                 size: end - start,
             },
         );
@@ -335,6 +350,8 @@ mod test {
             Expression::For {
                 data: 0.into(),
                 binding: 1.into(),
+                sorted_by: None,
+                filter: None,
                 size: 2
             }
         );
@@ -1,6 +1,7 @@
 use std::error::Error as StdError;
 use std::fmt::{self, Display, Formatter};
 use std::ops::Range;
+use std::path::PathBuf;
 
 use crate::token::Operator;
 
@@ -17,62 +18,89 @@ pub(super) fn src_line_no(end: usize, src: &str) -> (usize, usize) {
 
     // Set the column to at least one, as zero makes no
     // sense to the end user
-    let col = 1 + end - pos;
+    let col = 1 + src[pos..end].chars().count();
 
     (line_no, col)
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Error {
-    pub kind: ErrorKind,
+/// The location of the offending token in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the start of the token
+    pub offset: usize,
+    /// Length of the token in bytes
+    pub len: usize,
+    /// Line number, starting at one
     pub line: usize,
+    /// Column (in chars), starting at one
     pub col: usize,
-    pub src: String,
 }
 
-impl StdError for Error {}
-
-impl Error {
-    pub(crate) fn unterminated_string(range: Range<usize>, src: &str) -> Self {
+impl Span {
+    pub(crate) fn new(range: Range<usize>, src: &str) -> Self {
         let (line, col) = src_line_no(range.start, src);
         Self {
+            offset: range.start,
+            len: range.len(),
             line,
             col,
-            src: src.to_string(),
-            kind: ErrorKind::UnterminatedString,
         }
     }
+}
 
-    pub(crate) fn invalid_number(range: Range<usize>, src: &str) -> Self {
-        let (line, col) = src_line_no(range.end, src);
+#[derive(Clone, PartialEq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub span: Span,
+    pub src: String,
+    /// The file the source was read from, if it's known
+    /// (e.g the source was included from another file).
+    pub path: Option<PathBuf>,
+}
+
+impl StdError for Error {}
+
+impl Error {
+    pub(crate) fn new(kind: ErrorKind, range: Range<usize>, src: &str) -> Self {
         Self {
-            line,
-            col,
+            kind,
+            span: Span::new(range, src),
             src: src.to_string(),
-            kind: ErrorKind::InvalidNumber,
+            path: None,
         }
     }
 
+    pub(crate) fn unterminated_string(range: Range<usize>, src: &str) -> Self {
+        Self::new(ErrorKind::UnterminatedString, range, src)
+    }
+
+    pub(crate) fn invalid_number(range: Range<usize>, src: &str) -> Self {
+        Self::new(ErrorKind::InvalidNumber, range, src)
+    }
+
     pub(crate) fn invalid_hex_value(range: Range<usize>, src: &str) -> Self {
-        let (line, col) = src_line_no(range.end, src);
-        Self {
-            line,
-            col,
-            src: src.to_string(),
-            kind: ErrorKind::InvalidHexValue,
-        }
+        Self::new(ErrorKind::InvalidHexValue, range, src)
     }
 }
 
+// -----------------------------------------------------------------------------
+//   - Display -
+//   Show the line of the error, with the token underlined:
+//
+//   error: invalid token (expected: "in")
+//    --> 1:7
+//     |
+//   1 | for x im y
+//     |       ^^
+// -----------------------------------------------------------------------------
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let start_line = self.line;
-        let lines = self
-            .src
-            .lines()
-            .enumerate()
-            .skip(start_line.saturating_sub(2))
-            .take(3);
+        let Span {
+            offset,
+            len,
+            line,
+            col,
+        } = self.span;
 
         let msg = match &self.kind {
             ErrorKind::UnterminatedString => "unterminated string".into(),
@@ -88,22 +116,45 @@ impl Display for Error {
             ErrorKind::UnexpectedEof => "unexpected end of file".into(),
             ErrorKind::TrailingPipe => "trailing pipe character".into(),
             ErrorKind::InvalidDedent => "dedent does not match previous indentation levels".into(),
-            ErrorKind::InvalidOperator(_op) => "invalid operator: {op}".into(),
-            ErrorKind::UnexpectedToken(_msg) => "unexpected token: {msg}".into(),
+            ErrorKind::InvalidOperator(op) => format!("invalid operator: {op}"),
+            ErrorKind::InvalidCall => "only named functions can be called".into(),
+            ErrorKind::DivisionByZero => "division by zero".into(),
+            ErrorKind::UnexpectedToken(msg) => format!("unexpected token: {msg}"),
         };
 
-        writeln!(f, "error on line {start_line}: {msg}")?;
+        let src_line = self.src.lines().nth(line - 1).unwrap_or("");
+        // Only underline the part of the token on the first line
+        let token_len = self
+            .src
+            .get(offset..offset + len)
+            .and_then(|token| token.lines().next())
+            .map_or(0, |token| token.chars().count());
+
+        let gutter = line.to_string().len();
 
-        for (no, line) in lines {
-            let no = no + 1;
-            let mark = if self.line == no { "-> " } else { "   " };
-            writeln!(f, "{mark}{no} {line}")?;
-            if self.line == no {
-                writeln!(f, "{:_<width$}|", "_", width = self.col)?;
-            }
+        writeln!(f, "error: {msg}")?;
+        match &self.path {
+            Some(path) => writeln!(f, "{:gutter$}--> {}:{line}:{col}", "", path.display())?,
+            None => writeln!(f, "{:gutter$}--> {line}:{col}", "")?,
         }
+        writeln!(f, "{:gutter$} |", "")?;
+        writeln!(f, "{line} | {src_line}")?;
+        writeln!(
+            f,
+            "{:gutter$} | {:col$}{}",
+            "",
+            "",
+            "^".repeat(token_len.max(1)),
+            col = col - 1
+        )
+    }
+}
 
-        Ok(())
+// The source is left out as it's rarely useful,
+// and `unwrap` shows the location of the error this way.
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
     }
 }
 
@@ -121,5 +172,55 @@ pub enum ErrorKind {
     InvalidDedent,
     InvalidPath,
     InvalidOperator(Operator),
+    InvalidCall,
+    DivisionByZero,
     UnexpectedToken(String),
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{compile, ViewIds};
+
+    fn compile_err(src: &str) -> String {
+        compile(src, &mut ViewIds::new()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn underline_token() {
+        let expected = "\
+error: invalid token (expected: \"in\")
+ --> 2:11
+  |
+2 |     for x im y
+  |           ^^
+";
+        assert_eq!(
+            compile_err("vstack\n    for x im y\n        text"),
+            expected
+        );
+    }
+
+    #[test]
+    fn underline_unterminated_string() {
+        let expected = "\
+error: unterminated string
+ --> 1:6
+  |
+1 | text 'hællo
+  |      ^^^^^^
+";
+        assert_eq!(compile_err("text 'hællo\ntext"), expected);
+    }
+
+    #[test]
+    fn underline_invalid_call() {
+        let expected = "\
+error: only named functions can be called
+ --> 1:6
+  |
+1 | text a.b(1)
+  |      ^^^^^^
+";
+        assert_eq!(compile_err("text a.b(1)"), expected);
+    }
+}
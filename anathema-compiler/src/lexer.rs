@@ -12,8 +12,9 @@ impl<'src, 'consts> Iterator for Lexer<'src, 'consts> {
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.next_token() {
-            Ok(Token(Kind::Eof, _)) => None,
-            val => Some(val),
+            Ok(Token(Kind::Eof, ..)) => None,
+            Ok(Token(kind, start, _)) => Some(Ok(Token(kind, start, self.position()))),
+            err => Some(err),
         }
     }
 }
@@ -144,7 +145,14 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
     }
 
     fn eof(&self) -> Result<Token> {
-        Ok(Token(Kind::Eof, self.src.len()))
+        Ok(Kind::Eof.to_token(self.src.len()))
+    }
+
+    // The byte offset of the next char
+    fn position(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.src.len(), |(index, _)| *index)
     }
 
     fn take_string(&mut self, start_char: char, start_index: usize) -> Result<Token> {
@@ -202,7 +210,7 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
             },
         }?;
 
-        Ok(Token(kind, index))
+        Ok(kind.to_token(index))
     }
 
     fn take_ident_or_keyword(&mut self, index: usize) -> Kind {
@@ -244,8 +252,8 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
         Kind::Indent(count)
     }
 
-    fn take_hex_values(&mut self, index: usize) -> Result<Token> {
-        let index = index + 1; // consume #
+    fn take_hex_values(&mut self, start: usize) -> Result<Token> {
+        let index = start + 1; // consume #
         const SHORT: usize = 3;
         const LONG: usize = 6;
 
@@ -258,7 +266,7 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
         let hex = &self.src[index..end];
         let len = hex.len();
         if len != 3 && len != 6 {
-            return Err(Error::invalid_hex_value(index..end, self.src));
+            return Err(Error::invalid_hex_value(start..end, self.src));
         }
 
         let kind = match len {
@@ -280,7 +288,7 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
             _ => unreachable!(),
         };
 
-        Ok(Token(kind, index))
+        Ok(kind.to_token(start))
     }
 }
 
@@ -1,17 +1,25 @@
 use anathema_values::ValueExpr;
 
 use super::pratt::{eval, expr};
-use crate::error::{src_line_no, Error, ErrorKind, Result};
-use crate::token::{Kind, Operator, Tokens, Value};
+use crate::error::{Error, ErrorKind, Result};
+use crate::token::{Kind, Operator, Token, Tokens, Value};
 use crate::{Constants, StringId, ValueId, ViewId, ViewIds};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Expression {
     LoadValue(ValueId),
-    LoadAttribute { key: StringId, value: ValueId },
+    LoadAttribute {
+        key: StringId,
+        value: ValueId,
+    },
     View(ViewId),
     Node(StringId),
-    For { data: ValueId, binding: StringId },
+    For {
+        data: ValueId,
+        binding: StringId,
+        sorted_by: Option<ValueId>,
+        filter: Option<ValueId>,
+    },
     If(ValueId),
     Else(Option<ValueId>),
     ScopeStart,
@@ -74,14 +82,25 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
         }
     }
 
+    // An error at the last consumed token
     fn error(&self, kind: ErrorKind) -> Error {
-        let (line, col) = src_line_no(self.tokens.previous().1, self.src);
-        Error {
-            line,
-            col,
-            src: self.src.to_string(),
-            kind,
-        }
+        let Token(_, start, end) = self.tokens.previous();
+        Error::new(kind, start..end, self.src)
+    }
+
+    // An error at the next token
+    fn peek_error(&self, kind: ErrorKind) -> Error {
+        let Token(_, start, end) = self.tokens.peek_token();
+        Error::new(kind, start..end, self.src)
+    }
+
+    // Parse the next expression, and if it is invalid
+    // the error spans the entire expression
+    fn parse_expr(&mut self) -> Result<ValueExpr> {
+        let Token(_, start, _) = self.tokens.peek_token();
+        let expr = expr(&mut self.tokens);
+        let Token(_, _, end) = self.tokens.previous();
+        eval(expr, self.consts).map_err(|kind| Error::new(kind, start..end, self.src))
     }
 
     fn read_ident(&mut self) -> Result<StringId> {
@@ -253,20 +272,39 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
         let binding = self.read_ident()?;
 
         if Kind::In != self.tokens.peek_skip_indent() {
-            return Err(self.error(ErrorKind::InvalidToken { expected: "in" }));
+            return Err(self.peek_error(ErrorKind::InvalidToken { expected: "in" }));
         }
 
         // Consume `In`
         self.tokens.consume();
 
-        let expr = expr(&mut self.tokens);
-        let value_expr = eval(expr, self.consts);
+        let value_expr = self.parse_expr()?;
 
         // let data = ValueParser::new(&mut self.lexer).parse()?;
         let data = self.consts.store_value(value_expr);
 
+        // Modifiers: `sorted_by <key>` and `where <predicate>`, in any order
+        let mut sorted_by = None;
+        let mut filter = None;
+        while let Kind::Value(Value::Ident(ident)) = self.tokens.peek_skip_indent() {
+            let modifier = match self.consts.lookup_string(ident) {
+                "sorted_by" if sorted_by.is_none() => &mut sorted_by,
+                "where" if filter.is_none() => &mut filter,
+                _ => break,
+            };
+
+            self.tokens.consume();
+            let value_expr = self.parse_expr()?;
+            *modifier = Some(self.consts.store_value(value_expr));
+        }
+
         self.next_state();
-        Ok(Some(Expression::For { data, binding }))
+        Ok(Some(Expression::For {
+            data,
+            binding,
+            sorted_by,
+            filter,
+        }))
     }
 
     fn parse_if(&mut self) -> Result<Option<Expression>> {
@@ -280,8 +318,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
             Ok(Some(Expression::Else(cond)))
         } else if Kind::If == self.tokens.peek_skip_indent() {
             self.tokens.consume();
-            let expr = expr(&mut self.tokens);
-            let value_expr = eval(expr, self.consts);
+            let value_expr = self.parse_expr()?;
             let value_id = self.consts.store_value(value_expr);
 
             self.next_state();
@@ -340,14 +377,13 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
         self.tokens.consume_all_whitespace();
 
         if Kind::Op(Operator::Colon) != self.tokens.peek_skip_indent() {
-            return Err(self.error(ErrorKind::InvalidToken { expected: ":" }));
+            return Err(self.peek_error(ErrorKind::InvalidToken { expected: ":" }));
         }
 
         self.tokens.consume();
         self.tokens.consume_all_whitespace();
 
-        let expr = expr(&mut self.tokens);
-        let value_expr = eval(expr, self.consts);
+        let value_expr = self.parse_expr()?;
         let value = self.consts.store_value(value_expr);
 
         self.tokens.consume_all_whitespace();
@@ -360,7 +396,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
             self.tokens.consume();
             self.next_state();
         } else {
-            return Err(self.error(ErrorKind::UnterminatedAttributes));
+            return Err(self.peek_error(ErrorKind::UnterminatedAttributes));
         }
 
         Ok(Some(Expression::LoadAttribute { key, value }))
@@ -405,8 +441,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
             if matches!(self.tokens.peek(), Kind::Newline | Kind::Eof) {
                 break;
             }
-            let expression = expr(&mut self.tokens);
-            let value_expr = eval(expression, self.consts);
+            let value_expr = self.parse_expr()?;
             values.push(value_expr);
         }
 
@@ -476,6 +511,7 @@ impl Iterator for Parser<'_, '_, '_> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::error::Span;
     use crate::lexer::Lexer;
 
     fn parse(src: &str) -> Vec<Result<Expression>> {
@@ -492,6 +528,10 @@ mod test {
         parse(src).into_iter().map(Result::unwrap).collect()
     }
 
+    fn parse_err(src: &str) -> Error {
+        parse(src).into_iter().find_map(Result::err).unwrap()
+    }
+
     #[test]
     fn parse_single_instruction() {
         let src = "a";
@@ -590,7 +630,9 @@ mod test {
             instructions.remove(0),
             Expression::For {
                 data: 0.into(),
-                binding: 0.into()
+                binding: 0.into(),
+                sorted_by: None,
+                filter: None,
             }
         );
         assert_eq!(instructions.remove(0), Expression::ScopeStart);
@@ -598,7 +640,9 @@ mod test {
             instructions.remove(0),
             Expression::For {
                 data: 0.into(),
-                binding: 2.into()
+                binding: 2.into(),
+                sorted_by: None,
+                filter: None,
             }
         );
         assert_eq!(instructions.remove(0), Expression::ScopeStart);
@@ -625,7 +669,9 @@ mod test {
             instructions.remove(0),
             Expression::For {
                 data: 0.into(),
-                binding: 0.into()
+                binding: 0.into(),
+                sorted_by: None,
+                filter: None,
             }
         );
         assert_eq!(instructions.remove(0), Expression::ScopeStart);
@@ -633,6 +679,25 @@ mod test {
         assert_eq!(instructions.remove(0), Expression::ScopeEnd);
     }
 
+    #[test]
+    fn parse_for_modifiers() {
+        let src = "
+        for x in data where x.active sorted_by x.name
+            y
+        ";
+        let mut instructions = parse_ok(src);
+        assert_eq!(
+            instructions.remove(0),
+            Expression::For {
+                data: 0.into(),
+                binding: 0.into(),
+                sorted_by: Some(2.into()),
+                filter: Some(1.into()),
+            }
+        );
+        assert_eq!(instructions.remove(0), Expression::ScopeStart);
+    }
+
     #[test]
     fn parse_if() {
         let src = "
@@ -737,4 +802,41 @@ mod test {
         assert_eq!(expressions.remove(0), Expression::Node(0.into()));
         assert_eq!(expressions.remove(0), Expression::LoadValue(0.into()));
     }
+
+    #[test]
+    fn error_span() {
+        let err = parse_err("vstack\n    for x im y");
+        assert_eq!(err.kind, ErrorKind::InvalidToken { expected: "in" });
+        assert_eq!(
+            err.span,
+            Span {
+                offset: 17,
+                len: 2,
+                line: 2,
+                col: 11
+            }
+        );
+
+        let err = parse_err("a [b: 1 c: 2]");
+        assert_eq!(err.kind, ErrorKind::UnterminatedAttributes);
+        assert_eq!((err.span.line, err.span.col), (1, 9));
+    }
+
+    #[test]
+    fn call_non_identifier() {
+        let err = parse_err("text [a: x.y(1)]");
+        assert_eq!(err.kind, ErrorKind::InvalidCall);
+        assert_eq!(
+            err.span,
+            Span {
+                offset: 9,
+                len: 6,
+                line: 1,
+                col: 10
+            }
+        );
+
+        let err = parse_err("for x in f()()\n    text");
+        assert_eq!(err.kind, ErrorKind::InvalidCall);
+    }
 }
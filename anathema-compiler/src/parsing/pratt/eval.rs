@@ -4,11 +4,12 @@ use anathema_values::hashmap::HashMap;
 use anathema_values::{Num, Owned, ValueExpr};
 
 use super::Expr;
+use crate::error::ErrorKind;
 use crate::token::Operator;
 use crate::Constants;
 
-pub fn eval(expr: Expr, consts: &Constants) -> ValueExpr {
-    match expr {
+pub fn eval(expr: Expr, consts: &Constants) -> Result<ValueExpr, ErrorKind> {
+    let value_expr = match expr {
         Expr::Bool(b) => ValueExpr::from(b),
         Expr::Color(color) => ValueExpr::from(color),
         Expr::Ident(string_id) => {
@@ -21,64 +22,60 @@ pub fn eval(expr: Expr, consts: &Constants) -> ValueExpr {
         }
         Expr::Num(num) => ValueExpr::Owned(Owned::Num(num.into())),
         Expr::Array { lhs, index } => {
-            let lhs = eval(*lhs, consts);
-            let index = eval(*index, consts);
+            let lhs = eval(*lhs, consts)?;
+            let index = eval(*index, consts)?;
             ValueExpr::Index(lhs.into(), index.into())
         }
-        Expr::Binary { op, lhs, rhs } => match op {
-            Operator::Dot => ValueExpr::Dot(eval(*lhs, consts).into(), eval(*rhs, consts).into()),
-            Operator::Mul | Operator::Plus | Operator::Minus | Operator::Div | Operator::Mod => {
-                let (lhs, rhs) = match (eval(*lhs, consts), eval(*rhs, consts)) {
-                    (ValueExpr::Owned(Owned::Num(lhs)), ValueExpr::Owned(Owned::Num(rhs))) => {
-                        match op {
-                            Operator::Mul => return ValueExpr::Owned(Owned::Num(lhs * rhs)),
-                            Operator::Plus => return ValueExpr::Owned(Owned::Num(lhs + rhs)),
-                            Operator::Minus => return ValueExpr::Owned(Owned::Num(lhs - rhs)),
-                            Operator::Div => return ValueExpr::Owned(Owned::Num(lhs / rhs)),
-                            Operator::Mod => return ValueExpr::Owned(Owned::Num(lhs % rhs)),
-                            _ => unreachable!(),
+        Expr::Binary { op, lhs, rhs } => {
+            let lhs = eval(*lhs, consts)?;
+            let rhs = eval(*rhs, consts)?;
+            match op {
+                Operator::Dot => ValueExpr::Dot(lhs.into(), rhs.into()),
+                Operator::Mul
+                | Operator::Plus
+                | Operator::Minus
+                | Operator::Div
+                | Operator::Mod => {
+                    let (lhs, rhs) = match (lhs, rhs) {
+                        (ValueExpr::Owned(Owned::Num(lhs)), ValueExpr::Owned(Owned::Num(rhs))) => {
+                            let num = match op {
+                                Operator::Mul => lhs * rhs,
+                                Operator::Plus => lhs + rhs,
+                                Operator::Minus => lhs - rhs,
+                                Operator::Div => {
+                                    lhs.checked_div(rhs).ok_or(ErrorKind::DivisionByZero)?
+                                }
+                                Operator::Mod => {
+                                    lhs.checked_rem(rhs).ok_or(ErrorKind::DivisionByZero)?
+                                }
+                                _ => unreachable!(),
+                            };
+                            return Ok(ValueExpr::Owned(Owned::Num(num)));
                         }
+                        (lhs, rhs) => (lhs.into(), rhs.into()),
+                    };
+
+                    match op {
+                        Operator::Mul => ValueExpr::Mul(lhs, rhs),
+                        Operator::Plus => ValueExpr::Add(lhs, rhs),
+                        Operator::Minus => ValueExpr::Sub(lhs, rhs),
+                        Operator::Div => ValueExpr::Div(lhs, rhs),
+                        Operator::Mod => ValueExpr::Mod(lhs, rhs),
+                        _ => unreachable!(),
                     }
-                    (lhs, rhs) => (lhs.into(), rhs.into()),
-                };
-
-                match op {
-                    Operator::Mul => ValueExpr::Mul(lhs, rhs),
-                    Operator::Plus => ValueExpr::Add(lhs, rhs),
-                    Operator::Minus => ValueExpr::Sub(lhs, rhs),
-                    Operator::Div => ValueExpr::Div(lhs, rhs),
-                    Operator::Mod => ValueExpr::Mod(lhs, rhs),
-                    _ => unreachable!(),
-                }
-            }
-            Operator::EqualEqual => {
-                ValueExpr::Equality(eval(*lhs, consts).into(), eval(*rhs, consts).into())
-            }
-            Operator::GreaterThan => {
-                ValueExpr::Greater(eval(*lhs, consts).into(), eval(*rhs, consts).into())
-            }
-            Operator::GreaterThanOrEqual => {
-                ValueExpr::GreaterEqual(eval(*lhs, consts).into(), eval(*rhs, consts).into())
-            }
-            Operator::LessThan => {
-                ValueExpr::Less(eval(*lhs, consts).into(), eval(*rhs, consts).into())
-            }
-            Operator::LessThanOrEqual => {
-                ValueExpr::LessEqual(eval(*lhs, consts).into(), eval(*rhs, consts).into())
-            }
-            Operator::Or | Operator::And => {
-                let lhs = eval(*lhs, consts);
-                let rhs = eval(*rhs, consts);
-                match op {
-                    Operator::Or => ValueExpr::Or(lhs.into(), rhs.into()),
-                    Operator::And => ValueExpr::And(lhs.into(), rhs.into()),
-                    _ => unreachable!(),
                 }
+                Operator::EqualEqual => ValueExpr::Equality(lhs.into(), rhs.into()),
+                Operator::GreaterThan => ValueExpr::Greater(lhs.into(), rhs.into()),
+                Operator::GreaterThanOrEqual => ValueExpr::GreaterEqual(lhs.into(), rhs.into()),
+                Operator::LessThan => ValueExpr::Less(lhs.into(), rhs.into()),
+                Operator::LessThanOrEqual => ValueExpr::LessEqual(lhs.into(), rhs.into()),
+                Operator::Or => ValueExpr::Or(lhs.into(), rhs.into()),
+                Operator::And => ValueExpr::And(lhs.into(), rhs.into()),
+                op => return Err(ErrorKind::InvalidOperator(op)),
             }
-            e => panic!("here is a panic: {e:#?}"),
-        },
+        }
         Expr::Unary { op, expr } => {
-            let expr = eval(*expr, consts);
+            let expr = eval(*expr, consts)?;
 
             match op {
                 Operator::Not => match expr {
@@ -91,20 +88,35 @@ pub fn eval(expr: Expr, consts: &Constants) -> ValueExpr {
                     }
                     _ => ValueExpr::Negative(expr.into()),
                 },
-                _ => panic!("operator: {op:#?}"),
+                op => return Err(ErrorKind::InvalidOperator(op)),
             }
         }
-        Expr::List(list) => {
-            ValueExpr::List(list.into_iter().map(|expr| eval(expr, consts)).collect())
-        }
+        Expr::List(list) => ValueExpr::List(
+            list.into_iter()
+                .map(|expr| eval(expr, consts))
+                .collect::<Result<_, _>>()?,
+        ),
         Expr::Map(map) => ValueExpr::Map(
             map.into_iter()
-                .map(|(key, value)| (eval(key, consts).to_string(), eval(value, consts)))
-                .collect::<HashMap<_, _>>()
+                .map(|(key, value)| Ok((eval(key, consts)?.to_string(), eval(value, consts)?)))
+                .collect::<Result<HashMap<_, _>, _>>()?
                 .into(),
         ),
-        Expr::Call { .. } => unimplemented!(),
-    }
+        Expr::Call { fun, args } => match *fun {
+            Expr::Ident(string_id) => {
+                let fun = consts.lookup_string(string_id);
+                let args = args
+                    .into_iter()
+                    .map(|arg| eval(arg, consts))
+                    .collect::<Result<_, _>>()?;
+                ValueExpr::Call(fun.into(), args)
+            }
+            // Only named functions can be called, e.g `a.b()` or `f()()` are invalid
+            _ => return Err(ErrorKind::InvalidCall),
+        },
+    };
+
+    Ok(value_expr)
 }
 
 #[cfg(test)]
@@ -122,7 +134,7 @@ mod test {
         let mut tokens = Tokens::new(tokens, input.len());
 
         let expression = expr(&mut tokens);
-        eval(expression, &consts)
+        eval(expression, &consts).unwrap()
     }
 
     #[test]
@@ -245,6 +257,12 @@ mod test {
         assert_eq!(expr.to_string(), "-a / b");
     }
 
+    #[test]
+    fn call() {
+        let expr = eval_str("format_currency(a.b, 1 + 2)");
+        assert_eq!(expr.to_string(), "format_currency(a.b, 3)");
+    }
+
     #[test]
     fn modulo() {
         let expr = eval_str("5 % 4");
@@ -253,4 +271,21 @@ mod test {
         let expr = eval_str("a % 4");
         assert_eq!(expr.to_string(), "a % 4");
     }
+
+    #[test]
+    fn division_by_zero() {
+        let mut consts = Constants::new();
+        for input in ["5 / 0", "5 % 0", "1 + 5 / (2 - 2)"] {
+            let lexer = Lexer::new(input, &mut consts);
+            let tokens = lexer.collect::<Result<_, _>>().unwrap();
+            let mut tokens = Tokens::new(tokens, input.len());
+
+            let expression = expr(&mut tokens);
+            assert_eq!(
+                eval(expression, &consts),
+                Err(ErrorKind::DivisionByZero),
+                "{input}"
+            );
+        }
+    }
 }
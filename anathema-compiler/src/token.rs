@@ -115,8 +115,9 @@ pub(crate) enum Kind {
 }
 
 impl Kind {
+    // The end of the token is set by the lexer once the token is consumed
     pub(crate) fn to_token(self, pos: usize) -> Token {
-        Token(self, pos)
+        Token(self, pos, pos)
     }
 }
 
@@ -137,8 +138,9 @@ impl Display for Kind {
     }
 }
 
+/// A token, and the start and end (byte offsets) of the token in the source.
 #[derive(Debug, Copy, Clone)]
-pub struct Token(pub(crate) Kind, pub(crate) usize);
+pub struct Token(pub(crate) Kind, pub(crate) usize, pub(crate) usize);
 
 pub(crate) struct Tokens {
     inner: Vec<Token>,
@@ -183,7 +185,7 @@ impl Tokens {
 
     pub fn consume_indent(&mut self) {
         loop {
-            if matches!(self.inner.get(self.index), Some(Token(Kind::Indent(_), _))) {
+            if matches!(self.inner.get(self.index), Some(Token(Kind::Indent(_), ..))) {
                 self.index += 1;
                 continue;
             }
@@ -193,7 +195,7 @@ impl Tokens {
 
     pub fn consume_newlines(&mut self) {
         loop {
-            if matches!(self.inner.get(self.index), Some(Token(Kind::Newline, _))) {
+            if matches!(self.inner.get(self.index), Some(Token(Kind::Newline, ..))) {
                 self.index += 1;
                 continue;
             }
@@ -203,12 +205,12 @@ impl Tokens {
 
     pub fn consume_all_whitespace(&mut self) {
         loop {
-            if matches!(self.inner.get(self.index), Some(Token(Kind::Indent(_), _))) {
+            if matches!(self.inner.get(self.index), Some(Token(Kind::Indent(_), ..))) {
                 self.index += 1;
                 continue;
             }
 
-            if matches!(self.inner.get(self.index), Some(Token(Kind::Newline, _))) {
+            if matches!(self.inner.get(self.index), Some(Token(Kind::Newline, ..))) {
                 self.index += 1;
                 continue;
             }
@@ -218,11 +220,14 @@ impl Tokens {
     }
 
     pub fn peek(&self) -> Kind {
+        self.peek_token().0
+    }
+
+    pub fn peek_token(&self) -> Token {
         self.inner
             .get(self.index)
             .copied()
-            .unwrap_or(Token(Kind::Eof, self.eof))
-            .0
+            .unwrap_or(Token(Kind::Eof, self.eof, self.eof))
     }
 
     pub fn previous(&self) -> Token {
@@ -230,7 +235,7 @@ impl Tokens {
         self.inner
             .get(self.index - 1)
             .copied()
-            .unwrap_or(Token(Kind::Eof, self.eof))
+            .unwrap_or(Token(Kind::Eof, self.eof, self.eof))
     }
 
     pub fn peek_skip_indent(&mut self) -> Kind {
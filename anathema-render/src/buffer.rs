@@ -1,11 +1,12 @@
 #![deny(missing_docs)]
+use std::fmt::{self, Display};
 use std::io::{Result, Write};
 
 use crossterm::style::Print;
 use crossterm::{cursor, QueueableCommand};
 use unicode_width::UnicodeWidthChar;
 
-use super::{ScreenPos, Size, Style};
+use super::{Link, ScreenPos, Size, Style};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub(crate) struct Cell {
@@ -58,12 +59,72 @@ pub(crate) enum CellState {
 ///
 /// The [`crate::Screen`] writes all the chars and their styles to the buffer, which works like a
 /// grid.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Buffer {
     size: Size,
     pub(crate) inner: Vec<Cell>,
 }
 
+// `clone_from` reuses the allocation of the cells,
+// as the buffer is copied on every render.
+impl Clone for Buffer {
+    fn clone(&self) -> Self {
+        Self {
+            size: self.size,
+            inner: self.inner.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.size = source.size;
+        self.inner.clone_from(&source.inner);
+    }
+}
+
+/// The characters in the buffer, without any styles, as lines separated by `\n`.
+/// Empty cells are spaces, but trailing empty cells and lines are skipped,
+/// the same as [`crate::Screen::write_frame`].
+///
+/// ```
+/// use anathema_render::{Buffer, ScreenPos, Size, Style};
+///
+/// let mut buffer = Buffer::new(Size::new(4, 3));
+/// buffer.put_char('a', Style::new(), ScreenPos::new(0, 0));
+/// buffer.put_char('b', Style::new(), ScreenPos::new(2, 1));
+/// assert_eq!(buffer.to_string(), "a\n  b");
+/// ```
+impl Display for Buffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let is_empty = |cell: &Cell| matches!(cell.inner, CellState::Empty);
+        let lines = self.cell_lines().collect::<Vec<_>>();
+        let height = lines
+            .iter()
+            .rposition(|line| !line.iter().all(is_empty))
+            .map_or(0, |i| i + 1);
+
+        for (y, line) in lines[..height].iter().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            let width = line
+                .iter()
+                .rposition(|cell| !is_empty(cell))
+                .map_or(0, |i| i + 1);
+
+            for cell in &line[..width] {
+                match cell.inner {
+                    CellState::Occupied(c) => write!(f, "{c}")?,
+                    CellState::Empty => write!(f, " ")?,
+                    CellState::Continuation => continue,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl Buffer {
     /// Crate a new `Buffer` with a given size.
     pub fn new(size: impl Into<Size>) -> Self {
@@ -117,6 +178,12 @@ impl Buffer {
         }
     }
 
+    // The style of the cell at the given position, including empty cells
+    pub(crate) fn style_mut(&mut self, pos: ScreenPos) -> Option<&mut Style> {
+        let index = self.index(pos);
+        self.inner.get_mut(index).map(|cell| &mut cell.style)
+    }
+
     /// Empty a cell at a given position
     pub fn empty(&mut self, pos: ScreenPos) {
         let index = self.index(pos);
@@ -139,12 +206,15 @@ impl Buffer {
 
     fn put(&mut self, mut cell: Cell, pos: ScreenPos) {
         let index = self.index(pos);
+        let is_wide =
+            |state: CellState| matches!(state, CellState::Occupied(c) if c.width() >= Some(2));
+        let has_next = (pos.x as usize) + 1 < self.size.width;
 
         if let CellState::Occupied(c) = cell.inner {
             // If this is a unicode char that is wider than one cell,
             // add a continuation cell if it fits, this way if we overwrite it
             // we can set the continuation cell to `Empty`.
-            if pos.x < self.size.width as u16 {
+            if has_next {
                 if let Some(2..) = c.width() {
                     self.put(
                         Cell::continuation(cell.style),
@@ -154,6 +224,19 @@ impl Buffer {
             }
         }
 
+        // The terminal removes a wide char if either half of it is overwritten,
+        // so the buffer has to do the same to draw the same thing.
+        let current = self.inner[index].inner;
+        if cell.inner != CellState::Continuation {
+            if current == CellState::Continuation && pos.x > 0 {
+                self.inner[index - 1] = Cell::empty();
+            }
+
+            if is_wide(current) && !is_wide(cell.inner) && has_next {
+                self.inner[index + 1] = Cell::empty();
+            }
+        }
+
         let current = &mut self.inner[index];
         cell.style.merge(current.style);
 
@@ -170,6 +253,10 @@ impl Buffer {
                 if let Some(col) = cell.style.bg {
                     current.style.bg = Some(col);
                 }
+
+                if let Some(link) = cell.style.link {
+                    current.style.link = Some(link);
+                }
             }
             _ => *current = cell,
         }
@@ -187,6 +274,8 @@ impl Buffer {
         self.inner[index]
     }
 
+    /// The character at a given position.
+    /// Panics if the cell is not occupied
     pub fn char_at(&self, x: usize, y: usize) -> char {
         let cell = self.cell_at(x, y);
         match cell.inner {
@@ -248,12 +337,18 @@ pub(crate) fn diff(old: &Buffer, new: &Buffer) -> Result<Vec<(ScreenPos, Option<
 // -----------------------------------------------------------------------------
 //     - Draw changes -
 // -----------------------------------------------------------------------------
+// If `hyperlinks` is false any links in the styles are ignored
+// and the text is drawn as plain text.
 pub(crate) fn draw_changes(
     mut w: impl Write,
     changes: Vec<(ScreenPos, Option<Style>, Change)>,
+    hyperlinks: bool,
+    origin: u16,
 ) -> Result<()> {
     let mut last_y = None;
     let mut next_cell_x = None;
+    let mut link = None;
+    let mut open_link: Option<Link> = None;
 
     for (screen_pos, style, change) in changes {
         // Cursor movement
@@ -263,7 +358,7 @@ pub(crate) fn draw_changes(
         };
 
         if should_move {
-            w.queue(cursor::MoveTo(screen_pos.x, screen_pos.y))?;
+            w.queue(cursor::MoveTo(screen_pos.x, screen_pos.y + origin))?;
         }
 
         last_y = Some(screen_pos.y);
@@ -272,6 +367,20 @@ pub(crate) fn draw_changes(
         // Apply style
         if let Some(style) = style {
             style.write(&mut w)?;
+            link = style.link;
+        }
+
+        // Apply link
+        if hyperlinks && open_link != link {
+            if open_link.is_some() {
+                Link::write_close(&mut w)?;
+            }
+
+            if let Some(link) = link {
+                link.write_open(&mut w)?;
+            }
+
+            open_link = link;
         }
 
         // Draw changes
@@ -281,6 +390,70 @@ pub(crate) fn draw_changes(
         };
     }
 
+    if open_link.is_some() {
+        Link::write_close(&mut w)?;
+    }
+
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+//     - Draw lines -
+// -----------------------------------------------------------------------------
+// Draw the entire buffer as lines of text, starting at the current cursor position.
+// Trailing empty cells and lines are skipped, and every line ends with a newline
+// so the cursor is placed below the output.
+pub(crate) fn draw_lines(mut w: impl Write, buffer: &Buffer, hyperlinks: bool) -> Result<()> {
+    let is_empty = |cell: &Cell| matches!(cell.inner, CellState::Empty);
+
+    let lines = buffer.cell_lines().collect::<Vec<_>>();
+    let height = lines
+        .iter()
+        .rposition(|line| !line.iter().all(is_empty))
+        .map_or(0, |i| i + 1);
+
+    for line in &lines[..height] {
+        let width = line
+            .iter()
+            .rposition(|cell| !is_empty(cell))
+            .map_or(0, |i| i + 1);
+
+        let mut previous_style = None;
+        let mut open_link: Option<Link> = None;
+
+        for cell in &line[..width] {
+            if previous_style != Some(cell.style) {
+                cell.style.write(&mut w)?;
+                previous_style = Some(cell.style);
+            }
+
+            if hyperlinks && open_link != cell.style.link {
+                if open_link.is_some() {
+                    Link::write_close(&mut w)?;
+                }
+
+                if let Some(link) = cell.style.link {
+                    link.write_open(&mut w)?;
+                }
+
+                open_link = cell.style.link;
+            }
+
+            match cell.inner {
+                CellState::Occupied(c) => w.queue(Print(c))?,
+                CellState::Empty => w.queue(Print(' '))?,
+                CellState::Continuation => continue,
+            };
+        }
+
+        if open_link.is_some() {
+            Link::write_close(&mut w)?;
+        }
+
+        Style::reset().write(&mut w)?;
+        w.queue(Print("\r\n"))?;
+    }
+
     Ok(())
 }
 
@@ -313,6 +486,93 @@ mod test {
         assert_eq!(Change::Insert('N'), change_3);
     }
 
+    #[test]
+    fn diff_style_only() {
+        let old_buffer = Buffer::new((2u16, 1));
+        let mut new_buffer = old_buffer.clone();
+        let mut style = Style::reset();
+        style.set_fg(crate::Color::Red);
+        new_buffer.put(Cell::new(' ', style), ScreenPos::new(1, 0));
+
+        // The glyph is the same (a space looks like an empty cell) but the colour is not
+        let changes = diff(&old_buffer, &new_buffer).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, ScreenPos::new(1, 0));
+        assert_eq!(changes[0].1.unwrap().fg, Some(crate::Color::Red));
+    }
+
+    #[test]
+    fn diff_only_changed_cells() {
+        let mut old_buffer = Buffer::new((4u16, 2));
+        for x in 0..4 {
+            old_buffer.put_char('a', Style::reset(), ScreenPos::new(x, 0));
+        }
+
+        let mut new_buffer = old_buffer.clone();
+        new_buffer.put_char('b', Style::reset(), ScreenPos::new(1, 0));
+        new_buffer.put_char('c', Style::reset(), ScreenPos::new(2, 0));
+        new_buffer.put_char('d', Style::reset(), ScreenPos::new(0, 1));
+
+        let changes = diff(&old_buffer, &new_buffer).unwrap();
+        let positions = changes.iter().map(|(pos, ..)| *pos).collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![
+                ScreenPos::new(1, 0),
+                ScreenPos::new(2, 0),
+                ScreenPos::new(0, 1)
+            ]
+        );
+
+        // The style is only written once, and the cursor is only moved
+        // at the start of each run of changes.
+        assert!(changes[0].1.is_some());
+        assert!(changes[1].1.is_none());
+
+        let mut output = vec![];
+        draw_changes(&mut output, changes, false, 0).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches('H').count(), 2);
+    }
+
+    #[test]
+    fn wide_char_in_last_column() {
+        let mut buffer = Buffer::new((2u16, 2));
+        buffer.put_char('💖', Style::reset(), ScreenPos::new(1, 0));
+        assert_eq!(buffer.cell_at(1, 0).inner, CellState::Occupied('💖'));
+        // The continuation doesn't wrap onto the next line
+        assert_eq!(buffer.cell_at(0, 1).inner, CellState::Empty);
+    }
+
+    #[test]
+    fn overwrite_wide_char() {
+        // Overwriting the first half
+        let mut buffer = Buffer::new((3u16, 1));
+        buffer.put_char('💖', Style::reset(), ScreenPos::new(0, 0));
+        buffer.put_char('a', Style::reset(), ScreenPos::new(0, 0));
+        assert_eq!(buffer.cell_at(0, 0).inner, CellState::Occupied('a'));
+        assert_eq!(buffer.cell_at(1, 0).inner, CellState::Empty);
+
+        // Overwriting the second half
+        let mut buffer = Buffer::new((3u16, 1));
+        buffer.put_char('💖', Style::reset(), ScreenPos::new(0, 0));
+        buffer.put_char('b', Style::reset(), ScreenPos::new(1, 0));
+        assert_eq!(buffer.cell_at(0, 0).inner, CellState::Empty);
+        assert_eq!(buffer.cell_at(1, 0).inner, CellState::Occupied('b'));
+
+        // Overlapping wide chars
+        let mut buffer = Buffer::new((3u16, 1));
+        buffer.put_char('💖', Style::reset(), ScreenPos::new(0, 0));
+        buffer.put_char('💖', Style::reset(), ScreenPos::new(1, 0));
+        assert_eq!(buffer.cell_at(0, 0).inner, CellState::Empty);
+        assert_eq!(buffer.cell_at(1, 0).inner, CellState::Occupied('💖'));
+        assert_eq!(buffer.cell_at(2, 0).inner, CellState::Continuation);
+
+        let changes = diff(&Buffer::new((3u16, 1)), &buffer).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].2, Change::Insert('💖'));
+    }
+
     #[test]
     fn resize() {
         let mut buffer = Buffer::new((2u16, 2));
@@ -325,4 +585,71 @@ mod test {
         assert_eq!(buffer.inner[0], Cell::new('1', Style::reset()));
         assert_eq!(buffer.inner[1], Cell::new('3', Style::reset()));
     }
+
+    fn link_changes() -> Vec<(ScreenPos, Option<Style>, Change)> {
+        let mut style = Style::reset();
+        style.set_link(Link::new("https://example.com"));
+
+        let old_buffer = Buffer::new((2u16, 2));
+        let mut new_buffer = Buffer::new((2u16, 2));
+        new_buffer.inner[0] = Cell::new('a', style);
+        new_buffer.inner[1] = Cell::new('b', style);
+        // Wrapped onto the next line
+        new_buffer.inner[2] = Cell::new('c', style);
+
+        diff(&old_buffer, &new_buffer).unwrap()
+    }
+
+    #[test]
+    fn draw_hyperlink() {
+        let mut output = vec![];
+        draw_changes(&mut output, link_changes(), true, 0).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let open = "\x1b]8;id=";
+        let close = "\x1b]8;;\x1b\\";
+        assert_eq!(output.matches(open).count(), 1);
+        assert!(output.contains(";https://example.com\x1b\\"));
+        assert!(output.ends_with(close));
+    }
+
+    #[test]
+    fn draw_hyperlink_unsupported() {
+        let mut output = vec![];
+        draw_changes(&mut output, link_changes(), false, 0).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(!output.contains("\x1b]8;"));
+        assert!(output.contains('a'));
+    }
+
+    // Remove any escape sequences (`ESC [ ... letter`) from the output
+    fn strip_escapes(output: &[u8]) -> String {
+        let mut s = String::new();
+        let mut chars = std::str::from_utf8(output).unwrap().chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1b' => {
+                    for c in chars.by_ref() {
+                        if c.is_ascii_alphabetic() {
+                            break;
+                        }
+                    }
+                }
+                c => s.push(c),
+            }
+        }
+        s
+    }
+
+    #[test]
+    fn draw_lines_skips_trailing_space() {
+        let mut buffer = Buffer::new(Size::new(5, 4));
+        buffer.put_char('a', Style::reset(), ScreenPos::new(0, 0));
+        buffer.put_char('💖', Style::reset(), ScreenPos::new(2, 1));
+
+        let mut output = vec![];
+        draw_lines(&mut output, &buffer, false).unwrap();
+
+        assert_eq!(strip_escapes(&output), "a\r\n  💖\r\n");
+    }
 }
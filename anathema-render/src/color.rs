@@ -0,0 +1,542 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::fmt::{self, Display};
+use std::sync::OnceLock;
+
+use crossterm::style::Color;
+
+// The CSS named colours, sorted by name so they can be binary searched.
+const CSS_COLORS: &[(&str, (u8, u8, u8))] = &[
+    ("aliceblue", (0xf0, 0xf8, 0xff)),
+    ("antiquewhite", (0xfa, 0xeb, 0xd7)),
+    ("aqua", (0x00, 0xff, 0xff)),
+    ("aquamarine", (0x7f, 0xff, 0xd4)),
+    ("azure", (0xf0, 0xff, 0xff)),
+    ("beige", (0xf5, 0xf5, 0xdc)),
+    ("bisque", (0xff, 0xe4, 0xc4)),
+    ("black", (0x00, 0x00, 0x00)),
+    ("blanchedalmond", (0xff, 0xeb, 0xcd)),
+    ("blue", (0x00, 0x00, 0xff)),
+    ("blueviolet", (0x8a, 0x2b, 0xe2)),
+    ("brown", (0xa5, 0x2a, 0x2a)),
+    ("burlywood", (0xde, 0xb8, 0x87)),
+    ("cadetblue", (0x5f, 0x9e, 0xa0)),
+    ("chartreuse", (0x7f, 0xff, 0x00)),
+    ("chocolate", (0xd2, 0x69, 0x1e)),
+    ("coral", (0xff, 0x7f, 0x50)),
+    ("cornflowerblue", (0x64, 0x95, 0xed)),
+    ("cornsilk", (0xff, 0xf8, 0xdc)),
+    ("crimson", (0xdc, 0x14, 0x3c)),
+    ("cyan", (0x00, 0xff, 0xff)),
+    ("darkblue", (0x00, 0x00, 0x8b)),
+    ("darkcyan", (0x00, 0x8b, 0x8b)),
+    ("darkgoldenrod", (0xb8, 0x86, 0x0b)),
+    ("darkgray", (0xa9, 0xa9, 0xa9)),
+    ("darkgreen", (0x00, 0x64, 0x00)),
+    ("darkgrey", (0xa9, 0xa9, 0xa9)),
+    ("darkkhaki", (0xbd, 0xb7, 0x6b)),
+    ("darkmagenta", (0x8b, 0x00, 0x8b)),
+    ("darkolivegreen", (0x55, 0x6b, 0x2f)),
+    ("darkorange", (0xff, 0x8c, 0x00)),
+    ("darkorchid", (0x99, 0x32, 0xcc)),
+    ("darkred", (0x8b, 0x00, 0x00)),
+    ("darksalmon", (0xe9, 0x96, 0x7a)),
+    ("darkseagreen", (0x8f, 0xbc, 0x8f)),
+    ("darkslateblue", (0x48, 0x3d, 0x8b)),
+    ("darkslategray", (0x2f, 0x4f, 0x4f)),
+    ("darkslategrey", (0x2f, 0x4f, 0x4f)),
+    ("darkturquoise", (0x00, 0xce, 0xd1)),
+    ("darkviolet", (0x94, 0x00, 0xd3)),
+    ("deeppink", (0xff, 0x14, 0x93)),
+    ("deepskyblue", (0x00, 0xbf, 0xff)),
+    ("dimgray", (0x69, 0x69, 0x69)),
+    ("dimgrey", (0x69, 0x69, 0x69)),
+    ("dodgerblue", (0x1e, 0x90, 0xff)),
+    ("firebrick", (0xb2, 0x22, 0x22)),
+    ("floralwhite", (0xff, 0xfa, 0xf0)),
+    ("forestgreen", (0x22, 0x8b, 0x22)),
+    ("fuchsia", (0xff, 0x00, 0xff)),
+    ("gainsboro", (0xdc, 0xdc, 0xdc)),
+    ("ghostwhite", (0xf8, 0xf8, 0xff)),
+    ("gold", (0xff, 0xd7, 0x00)),
+    ("goldenrod", (0xda, 0xa5, 0x20)),
+    ("gray", (0x80, 0x80, 0x80)),
+    ("green", (0x00, 0x80, 0x00)),
+    ("greenyellow", (0xad, 0xff, 0x2f)),
+    ("grey", (0x80, 0x80, 0x80)),
+    ("honeydew", (0xf0, 0xff, 0xf0)),
+    ("hotpink", (0xff, 0x69, 0xb4)),
+    ("indianred", (0xcd, 0x5c, 0x5c)),
+    ("indigo", (0x4b, 0x00, 0x82)),
+    ("ivory", (0xff, 0xff, 0xf0)),
+    ("khaki", (0xf0, 0xe6, 0x8c)),
+    ("lavender", (0xe6, 0xe6, 0xfa)),
+    ("lavenderblush", (0xff, 0xf0, 0xf5)),
+    ("lawngreen", (0x7c, 0xfc, 0x00)),
+    ("lemonchiffon", (0xff, 0xfa, 0xcd)),
+    ("lightblue", (0xad, 0xd8, 0xe6)),
+    ("lightcoral", (0xf0, 0x80, 0x80)),
+    ("lightcyan", (0xe0, 0xff, 0xff)),
+    ("lightgoldenrodyellow", (0xfa, 0xfa, 0xd2)),
+    ("lightgray", (0xd3, 0xd3, 0xd3)),
+    ("lightgreen", (0x90, 0xee, 0x90)),
+    ("lightgrey", (0xd3, 0xd3, 0xd3)),
+    ("lightpink", (0xff, 0xb6, 0xc1)),
+    ("lightsalmon", (0xff, 0xa0, 0x7a)),
+    ("lightseagreen", (0x20, 0xb2, 0xaa)),
+    ("lightskyblue", (0x87, 0xce, 0xfa)),
+    ("lightslategray", (0x77, 0x88, 0x99)),
+    ("lightslategrey", (0x77, 0x88, 0x99)),
+    ("lightsteelblue", (0xb0, 0xc4, 0xde)),
+    ("lightyellow", (0xff, 0xff, 0xe0)),
+    ("lime", (0x00, 0xff, 0x00)),
+    ("limegreen", (0x32, 0xcd, 0x32)),
+    ("linen", (0xfa, 0xf0, 0xe6)),
+    ("magenta", (0xff, 0x00, 0xff)),
+    ("maroon", (0x80, 0x00, 0x00)),
+    ("mediumaquamarine", (0x66, 0xcd, 0xaa)),
+    ("mediumblue", (0x00, 0x00, 0xcd)),
+    ("mediumorchid", (0xba, 0x55, 0xd3)),
+    ("mediumpurple", (0x93, 0x70, 0xdb)),
+    ("mediumseagreen", (0x3c, 0xb3, 0x71)),
+    ("mediumslateblue", (0x7b, 0x68, 0xee)),
+    ("mediumspringgreen", (0x00, 0xfa, 0x9a)),
+    ("mediumturquoise", (0x48, 0xd1, 0xcc)),
+    ("mediumvioletred", (0xc7, 0x15, 0x85)),
+    ("midnightblue", (0x19, 0x19, 0x70)),
+    ("mintcream", (0xf5, 0xff, 0xfa)),
+    ("mistyrose", (0xff, 0xe4, 0xe1)),
+    ("moccasin", (0xff, 0xe4, 0xb5)),
+    ("navajowhite", (0xff, 0xde, 0xad)),
+    ("navy", (0x00, 0x00, 0x80)),
+    ("oldlace", (0xfd, 0xf5, 0xe6)),
+    ("olive", (0x80, 0x80, 0x00)),
+    ("olivedrab", (0x6b, 0x8e, 0x23)),
+    ("orange", (0xff, 0xa5, 0x00)),
+    ("orangered", (0xff, 0x45, 0x00)),
+    ("orchid", (0xda, 0x70, 0xd6)),
+    ("palegoldenrod", (0xee, 0xe8, 0xaa)),
+    ("palegreen", (0x98, 0xfb, 0x98)),
+    ("paleturquoise", (0xaf, 0xee, 0xee)),
+    ("palevioletred", (0xdb, 0x70, 0x93)),
+    ("papayawhip", (0xff, 0xef, 0xd5)),
+    ("peachpuff", (0xff, 0xda, 0xb9)),
+    ("peru", (0xcd, 0x85, 0x3f)),
+    ("pink", (0xff, 0xc0, 0xcb)),
+    ("plum", (0xdd, 0xa0, 0xdd)),
+    ("powderblue", (0xb0, 0xe0, 0xe6)),
+    ("purple", (0x80, 0x00, 0x80)),
+    ("rebeccapurple", (0x66, 0x33, 0x99)),
+    ("red", (0xff, 0x00, 0x00)),
+    ("rosybrown", (0xbc, 0x8f, 0x8f)),
+    ("royalblue", (0x41, 0x69, 0xe1)),
+    ("saddlebrown", (0x8b, 0x45, 0x13)),
+    ("salmon", (0xfa, 0x80, 0x72)),
+    ("sandybrown", (0xf4, 0xa4, 0x60)),
+    ("seagreen", (0x2e, 0x8b, 0x57)),
+    ("seashell", (0xff, 0xf5, 0xee)),
+    ("sienna", (0xa0, 0x52, 0x2d)),
+    ("silver", (0xc0, 0xc0, 0xc0)),
+    ("skyblue", (0x87, 0xce, 0xeb)),
+    ("slateblue", (0x6a, 0x5a, 0xcd)),
+    ("slategray", (0x70, 0x80, 0x90)),
+    ("slategrey", (0x70, 0x80, 0x90)),
+    ("snow", (0xff, 0xfa, 0xfa)),
+    ("springgreen", (0x00, 0xff, 0x7f)),
+    ("steelblue", (0x46, 0x82, 0xb4)),
+    ("tan", (0xd2, 0xb4, 0x8c)),
+    ("teal", (0x00, 0x80, 0x80)),
+    ("thistle", (0xd8, 0xbf, 0xd8)),
+    ("tomato", (0xff, 0x63, 0x47)),
+    ("turquoise", (0x40, 0xe0, 0xd0)),
+    ("violet", (0xee, 0x82, 0xee)),
+    ("wheat", (0xf5, 0xde, 0xb3)),
+    ("white", (0xff, 0xff, 0xff)),
+    ("whitesmoke", (0xf5, 0xf5, 0xf5)),
+    ("yellow", (0xff, 0xff, 0x00)),
+    ("yellowgreen", (0x9a, 0xcd, 0x32)),
+];
+
+// The 16 colour palette, in ANSI order, with the xterm default values.
+const ANSI_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0x00, 0x00, 0x00)),
+    (Color::DarkRed, (0xcd, 0x00, 0x00)),
+    (Color::DarkGreen, (0x00, 0xcd, 0x00)),
+    (Color::DarkYellow, (0xcd, 0xcd, 0x00)),
+    (Color::DarkBlue, (0x00, 0x00, 0xee)),
+    (Color::DarkMagenta, (0xcd, 0x00, 0xcd)),
+    (Color::DarkCyan, (0x00, 0xcd, 0xcd)),
+    (Color::Grey, (0xe5, 0xe5, 0xe5)),
+    (Color::DarkGrey, (0x7f, 0x7f, 0x7f)),
+    (Color::Red, (0xff, 0x00, 0x00)),
+    (Color::Green, (0x00, 0xff, 0x00)),
+    (Color::Yellow, (0xff, 0xff, 0x00)),
+    (Color::Blue, (0x5c, 0x5c, 0xff)),
+    (Color::Magenta, (0xff, 0x00, 0xff)),
+    (Color::Cyan, (0x00, 0xff, 0xff)),
+    (Color::White, (0xff, 0xff, 0xff)),
+];
+
+// The value of an entry in the 256 colour palette:
+// the 16 colours, followed by a 6x6x6 colour cube and 24 shades of grey.
+fn ansi_value_rgb(value: u8) -> (u8, u8, u8) {
+    const CUBE: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+    match value {
+        0..=15 => ANSI_COLORS[value as usize].1,
+        16..=231 => {
+            let value = value as usize - 16;
+            (CUBE[value / 36], CUBE[value / 6 % 6], CUBE[value % 6])
+        }
+        232..=255 => {
+            let grey = 8 + (value - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+thread_local! {
+    // The closest palette entries of the colours looked up so far,
+    // as finding the closest entry means comparing against the entire palette.
+    static NEAREST_ANSI_VALUES: RefCell<HashMap<(u8, u8, u8), u8>> = RefCell::new(HashMap::new());
+}
+
+static TRUECOLOR: OnceLock<bool> = OnceLock::new();
+
+// The closest entry in the 256 colour palette
+pub(crate) fn nearest_ansi_value(rgb: (u8, u8, u8)) -> Color {
+    let value = NEAREST_ANSI_VALUES.with(|values| {
+        *values.borrow_mut().entry(rgb).or_insert_with(|| {
+            (0..=255)
+                .min_by_key(|&value| distance(ansi_value_rgb(value), rgb))
+                .expect("the palette is not empty")
+        })
+    });
+    Color::AnsiValue(value)
+}
+
+/// Best effort detection of truecolor (24-bit RGB) support in the current terminal.
+/// The environment is only read the first time this is called.
+///
+/// Set `ANATHEMA_TRUECOLOR` to `0` or `1` to override the detection.
+pub fn truecolor_supported() -> bool {
+    *TRUECOLOR.get_or_init(|| {
+        if let Ok(value) = env::var("ANATHEMA_TRUECOLOR") {
+            return value != "0";
+        }
+
+        match env::var("COLORTERM") {
+            Ok(value) => value == "truecolor" || value == "24bit",
+            Err(_) => false,
+        }
+    })
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// Error returned when a string can not be parsed as a [`Color`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorParseError {
+    /// A hex colour has to be three, six or eight digits long (excluding the `#`)
+    InvalidLength(usize),
+    /// The string contains a character that isn't a hex digit
+    InvalidDigit(char),
+}
+
+impl Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(f, "expected 3, 6 or 8 hex digits, found {len}")
+            }
+            Self::InvalidDigit(c) => write!(f, "`{c}` is not a hex digit"),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+/// Additional ways to create a [`Color`].
+pub trait ColorExt: Sized {
+    /// Parse a hex colour: `#rgb`, `#rrggbb` or `#rrggbbaa`.
+    ///
+    /// The leading `#` is optional and the digits are case-insensitive.
+    /// Terminals have no notion of transparency, so the alpha channel is ignored.
+    ///
+    /// ```
+    /// use anathema_render::{Color, ColorExt};
+    ///
+    /// let color = Color::from_hex("#ff8800").unwrap();
+    /// assert_eq!(
+    ///     color,
+    ///     Color::Rgb {
+    ///         r: 255,
+    ///         g: 136,
+    ///         b: 0
+    ///     }
+    /// );
+    /// assert_eq!(Color::from_hex("F80").unwrap(), color);
+    /// ```
+    fn from_hex(hex: &str) -> Result<Self, ColorParseError>;
+
+    /// Look up one of the CSS named colours, e.g `rebeccapurple`.
+    ///
+    /// The name is case-insensitive and any surrounding whitespace is ignored.
+    ///
+    /// ```
+    /// use anathema_render::{Color, ColorExt};
+    ///
+    /// let color = Color::from_name(" RebeccaPurple ").unwrap();
+    /// assert_eq!(
+    ///     color,
+    ///     Color::Rgb {
+    ///         r: 0x66,
+    ///         g: 0x33,
+    ///         b: 0x99
+    ///     }
+    /// );
+    /// assert!(Color::from_name("not a colour").is_none());
+    /// ```
+    fn from_name(name: &str) -> Option<Self>;
+
+    /// The red, green and blue values of the colour.
+    ///
+    /// The 16 and 256 colour palettes use the xterm default values,
+    /// as the actual values depend on the terminal.
+    /// `Color::Reset` is the default colour of the terminal, which isn't known, so it has no value.
+    fn to_rgb(&self) -> Option<(u8, u8, u8)>;
+
+    /// Blend `over` on top of this colour, where an `alpha` of zero is this colour
+    /// and an `alpha` of one is `over`.
+    /// The alpha is clamped to `[0.0, 1.0]`.
+    ///
+    /// Terminal colours are opaque, so the blending is done when drawing,
+    /// against the colour that is already there. E.g to dim a cell by half:
+    /// `bg.blend(Color::Black, 0.5)`, or use [`Screen::blend_region`](crate::Screen::blend_region).
+    ///
+    /// * If either colour is an RGB colour the result is an RGB colour
+    /// * If either colour is from the 256 colour palette the result is the closest entry in that palette
+    /// * Otherwise the result is the closest of the 16 colours
+    /// * `Color::Reset` can't be blended, so the result is whichever colour has
+    ///   the most weight
+    ///
+    /// ```
+    /// use anathema_render::{Color, ColorExt};
+    ///
+    /// let white = Color::Rgb {
+    ///     r: 255,
+    ///     g: 255,
+    ///     b: 255,
+    /// };
+    /// assert_eq!(
+    ///     white.blend(Color::Black, 0.5),
+    ///     Color::Rgb {
+    ///         r: 128,
+    ///         g: 128,
+    ///         b: 128
+    ///     }
+    /// );
+    /// assert_eq!(Color::Red.blend(Color::Black, 0.4), Color::DarkRed);
+    /// ```
+    fn blend(self, over: Self, alpha: f32) -> Self;
+}
+
+impl ColorExt for Color {
+    fn from_hex(hex: &str) -> Result<Self, ColorParseError> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+
+        if let Some(c) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(ColorParseError::InvalidDigit(c));
+        }
+
+        // All the characters are ascii at this point, so indexing by byte is fine
+        let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).expect("validated hex digit");
+        let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).expect("validated hex digits");
+
+        let (r, g, b) = match hex.len() {
+            3 => (digit(0) * 17, digit(1) * 17, digit(2) * 17),
+            6 | 8 => (pair(0), pair(2), pair(4)),
+            len => return Err(ColorParseError::InvalidLength(len)),
+        };
+
+        Ok(Color::Rgb { r, g, b })
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        let index = CSS_COLORS
+            .binary_search_by_key(&name.as_str(), |(name, _)| name)
+            .ok()?;
+        let (r, g, b) = CSS_COLORS[index].1;
+        Some(Color::Rgb { r, g, b })
+    }
+
+    fn to_rgb(&self) -> Option<(u8, u8, u8)> {
+        match *self {
+            Color::Reset => None,
+            Color::Rgb { r, g, b } => Some((r, g, b)),
+            Color::AnsiValue(value) => Some(ansi_value_rgb(value)),
+            color => ANSI_COLORS
+                .iter()
+                .find_map(|&(c, rgb)| (c == color).then_some(rgb)),
+        }
+    }
+
+    fn blend(self, over: Self, alpha: f32) -> Self {
+        let alpha = match alpha.is_nan() {
+            true => 0.0,
+            false => alpha.clamp(0.0, 1.0),
+        };
+
+        let (Some(base), Some(top)) = (self.to_rgb(), over.to_rgb()) else {
+            return match alpha < 0.5 {
+                true => self,
+                false => over,
+            };
+        };
+
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * alpha).round() as u8;
+        let rgb = (mix(base.0, top.0), mix(base.1, top.1), mix(base.2, top.2));
+
+        match (self, over) {
+            (Color::Rgb { .. }, _) | (_, Color::Rgb { .. }) => Color::Rgb {
+                r: rgb.0,
+                g: rgb.1,
+                b: rgb.2,
+            },
+            (Color::AnsiValue(_), _) | (_, Color::AnsiValue(_)) => nearest_ansi_value(rgb),
+            _ => {
+                ANSI_COLORS
+                    .iter()
+                    .min_by_key(|(_, value)| distance(*value, rgb))
+                    .expect("the palette is not empty")
+                    .0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_hex() {
+        let expected = Color::Rgb {
+            r: 0xaa,
+            g: 0xbb,
+            b: 0xcc,
+        };
+        assert_eq!(Color::from_hex("#abc").unwrap(), expected);
+        assert_eq!(Color::from_hex("#AABBCC").unwrap(), expected);
+        assert_eq!(Color::from_hex("aabbcc").unwrap(), expected);
+        assert_eq!(Color::from_hex("#aabbcc80").unwrap(), expected);
+    }
+
+    #[test]
+    fn css_names() {
+        assert_eq!(
+            Color::from_name("green").unwrap(),
+            Color::Rgb { r: 0, g: 128, b: 0 }
+        );
+        assert_eq!(
+            Color::from_name("GhostWhite"),
+            Color::from_hex("#f8f8ff").ok()
+        );
+        assert_eq!(
+            Color::from_name("aliceblue"),
+            Color::from_hex("#f0f8ff").ok()
+        );
+        assert_eq!(
+            Color::from_name("yellowgreen"),
+            Color::from_hex("#9acd32").ok()
+        );
+        assert!(Color::from_name("").is_none());
+        assert!(Color::from_name("greenish").is_none());
+    }
+
+    #[test]
+    fn invalid_hex() {
+        assert_eq!(
+            Color::from_hex("#abcd"),
+            Err(ColorParseError::InvalidLength(4))
+        );
+        assert_eq!(Color::from_hex("#"), Err(ColorParseError::InvalidLength(0)));
+        assert_eq!(
+            Color::from_hex("#ff88zz"),
+            Err(ColorParseError::InvalidDigit('z'))
+        );
+        assert_eq!(
+            Color::from_hex("#+f8"),
+            Err(ColorParseError::InvalidDigit('+'))
+        );
+    }
+
+    #[test]
+    fn blend_rgb() {
+        let base = Color::Rgb {
+            r: 200,
+            g: 100,
+            b: 0,
+        };
+        let over = Color::Rgb { r: 0, g: 0, b: 100 };
+
+        assert_eq!(base.blend(over, 0.0), base);
+        assert_eq!(base.blend(over, 1.0), over);
+        assert_eq!(
+            base.blend(over, 0.25),
+            Color::Rgb {
+                r: 150,
+                g: 75,
+                b: 25
+            }
+        );
+
+        // The alpha is clamped
+        assert_eq!(base.blend(over, -1.0), base);
+        assert_eq!(base.blend(over, 2.0), over);
+        assert_eq!(base.blend(over, f32::NAN), base);
+    }
+
+    #[test]
+    fn blend_palette() {
+        assert_eq!(Color::White.blend(Color::Black, 0.5), Color::DarkGrey);
+        assert_eq!(Color::Green.blend(Color::Black, 0.2), Color::DarkGreen);
+        assert_eq!(Color::Blue.blend(Color::Blue, 0.5), Color::Blue);
+
+        // The 256 colour palette
+        let grey = Color::AnsiValue(255).blend(Color::Black, 0.5);
+        assert_eq!(grey, Color::AnsiValue(243));
+
+        // Mixed with RGB
+        assert_eq!(
+            Color::White.blend(Color::Rgb { r: 0, g: 0, b: 0 }, 0.5),
+            Color::Rgb {
+                r: 128,
+                g: 128,
+                b: 128
+            }
+        );
+    }
+
+    #[test]
+    fn blend_reset() {
+        assert_eq!(Color::Reset.blend(Color::Black, 0.4), Color::Reset);
+        assert_eq!(Color::Reset.blend(Color::Black, 0.5), Color::Black);
+        assert_eq!(Color::Red.blend(Color::Reset, 0.9), Color::Reset);
+    }
+
+    #[test]
+    fn palette_values() {
+        assert_eq!(Color::DarkRed.to_rgb(), Some((0xcd, 0, 0)));
+        assert_eq!(Color::AnsiValue(9).to_rgb(), Color::Red.to_rgb());
+        assert_eq!(Color::AnsiValue(16).to_rgb(), Some((0, 0, 0)));
+        assert_eq!(Color::AnsiValue(196).to_rgb(), Some((0xff, 0, 0)));
+        assert_eq!(Color::AnsiValue(232).to_rgb(), Some((8, 8, 8)));
+        assert_eq!(Color::Reset.to_rgb(), None);
+    }
+}
@@ -0,0 +1,246 @@
+use crossterm::style::Color;
+
+use crate::color::nearest_ansi_value;
+use crate::{truecolor_supported, ColorExt, Size};
+
+/// The direction of a [`Gradient`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum GradientDirection {
+    /// From left to right
+    #[default]
+    Horizontal,
+    /// From top to bottom
+    Vertical,
+    /// From the top left corner to the bottom right corner
+    Diagonal,
+}
+
+/// A gradient between a number of colours (stops), spaced evenly
+/// from one edge to the other.
+///
+/// The colours between the stops are blended with [`ColorExt::blend`].
+/// If the terminal doesn't support truecolor (see [`truecolor_supported`]) the
+/// sampled colours are snapped to the closest colour in the 256 colour palette.
+///
+/// A gradient with a single stop is the same as a solid colour.
+///
+/// ```
+/// use anathema_render::{Color, Gradient, GradientDirection};
+///
+/// let stops = [
+///     Color::Rgb { r: 0, g: 0, b: 255 },
+///     Color::Rgb {
+///         r: 128,
+///         g: 0,
+///         b: 128,
+///     },
+/// ];
+/// let gradient = Gradient::new(stops, GradientDirection::Horizontal).with_truecolor(true);
+///
+/// assert_eq!(gradient.sample(0.0, 0.0), stops[0]);
+/// assert_eq!(
+///     gradient.sample(0.5, 0.0),
+///     Color::Rgb {
+///         r: 64,
+///         g: 0,
+///         b: 192
+///     }
+/// );
+/// assert_eq!(gradient.sample(1.0, 1.0), stops[1]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    // The stops, and the stops snapped to the 256 colour palette
+    // (only used without truecolor support)
+    stops: Vec<(Color, Color)>,
+    /// The direction of the gradient
+    pub direction: GradientDirection,
+    truecolor: bool,
+}
+
+impl Gradient {
+    /// Create a new gradient.
+    /// Truecolor support is detected with [`truecolor_supported`].
+    pub fn new(stops: impl IntoIterator<Item = Color>, direction: GradientDirection) -> Self {
+        Self {
+            stops: stops
+                .into_iter()
+                .map(|stop| (stop, snap_to_palette(stop)))
+                .collect(),
+            direction,
+            truecolor: truecolor_supported(),
+        }
+    }
+
+    /// Override the detected truecolor support.
+    /// Without truecolor the sampled colours are snapped to the 256 colour palette.
+    pub fn with_truecolor(mut self, truecolor: bool) -> Self {
+        self.truecolor = truecolor;
+        self
+    }
+
+    /// The colour stops
+    pub fn stops(&self) -> impl Iterator<Item = Color> + '_ {
+        self.stops.iter().map(|(stop, _)| *stop)
+    }
+
+    fn stop(&self, index: usize) -> Color {
+        let (stop, palette) = self.stops[index];
+        match self.truecolor {
+            true => stop,
+            false => palette,
+        }
+    }
+
+    /// The colour at a normalized position, where `(0.0, 0.0)` is the top left corner
+    /// and `(1.0, 1.0)` is the bottom right corner.
+    /// The position is clamped to `[0.0, 1.0]`.
+    ///
+    /// A gradient without any stops is `Color::Reset`.
+    pub fn sample(&self, x: f32, y: f32) -> Color {
+        let offset = match self.direction {
+            GradientDirection::Horizontal => x,
+            GradientDirection::Vertical => y,
+            GradientDirection::Diagonal => (x + y) / 2.0,
+        };
+
+        let offset = match offset.is_nan() {
+            true => 0.0,
+            false => offset.clamp(0.0, 1.0),
+        };
+
+        match self.stops.len() {
+            0 => Color::Reset,
+            1 => self.stop(0),
+            len => {
+                let segments = len - 1;
+                let pos = offset * segments as f32;
+                // The last stop is the end of the last segment
+                let index = (pos as usize).min(segments - 1);
+                let alpha = pos - index as f32;
+                match alpha {
+                    0.0 => self.stop(index),
+                    1.0 => self.stop(index + 1),
+                    _ => {
+                        let color = self.stops[index].0.blend(self.stops[index + 1].0, alpha);
+                        match self.truecolor {
+                            true => color,
+                            false => snap_to_palette(color),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// The colour of the cell at `(x, y)` in a region of the given size,
+    /// where the first and last cells are the first and last stops.
+    pub fn sample_cell(&self, x: usize, y: usize, size: Size) -> Color {
+        let normalize = |pos: usize, len: usize| match len {
+            0 | 1 => 0.0,
+            len => pos as f32 / (len - 1) as f32,
+        };
+
+        self.sample(normalize(x, size.width), normalize(y, size.height))
+    }
+}
+
+fn snap_to_palette(color: Color) -> Color {
+    match color {
+        Color::Rgb { r, g, b } => nearest_ansi_value((r, g, b)),
+        _ => color,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BLACK: Color = Color::Rgb { r: 0, g: 0, b: 0 };
+    const WHITE: Color = Color::Rgb {
+        r: 255,
+        g: 255,
+        b: 255,
+    };
+    const GREY: Color = Color::Rgb {
+        r: 128,
+        g: 128,
+        b: 128,
+    };
+
+    fn gradient(stops: impl IntoIterator<Item = Color>, direction: GradientDirection) -> Gradient {
+        Gradient::new(stops, direction).with_truecolor(true)
+    }
+
+    #[test]
+    fn horizontal() {
+        let gradient = gradient([BLACK, WHITE], GradientDirection::Horizontal);
+        assert_eq!(gradient.sample(0.0, 0.7), BLACK);
+        assert_eq!(gradient.sample(0.5, 0.0), GREY);
+        assert_eq!(gradient.sample(1.0, 0.0), WHITE);
+
+        // The position is clamped
+        assert_eq!(gradient.sample(-1.0, 0.0), BLACK);
+        assert_eq!(gradient.sample(2.0, 0.0), WHITE);
+        assert_eq!(gradient.sample(f32::NAN, 0.0), BLACK);
+    }
+
+    #[test]
+    fn vertical_and_diagonal() {
+        let vertical = gradient([BLACK, WHITE], GradientDirection::Vertical);
+        assert_eq!(vertical.sample(1.0, 0.0), BLACK);
+        assert_eq!(vertical.sample(0.0, 1.0), WHITE);
+
+        let diagonal = gradient([BLACK, WHITE], GradientDirection::Diagonal);
+        assert_eq!(diagonal.sample(1.0, 0.0), GREY);
+        assert_eq!(diagonal.sample(0.0, 1.0), GREY);
+        assert_eq!(diagonal.sample(1.0, 1.0), WHITE);
+    }
+
+    #[test]
+    fn multiple_stops() {
+        let red = Color::Rgb { r: 255, g: 0, b: 0 };
+        let gradient = gradient([BLACK, red, WHITE], GradientDirection::Horizontal);
+        assert_eq!(gradient.sample(0.5, 0.0), red);
+        assert_eq!(
+            gradient.sample(0.75, 0.0),
+            Color::Rgb {
+                r: 255,
+                g: 128,
+                b: 128
+            }
+        );
+        assert_eq!(gradient.sample(1.0, 0.0), WHITE);
+    }
+
+    #[test]
+    fn single_stop_is_solid() {
+        let gradient = gradient([Color::Red], GradientDirection::Diagonal);
+        assert_eq!(gradient.sample(0.0, 0.0), Color::Red);
+        assert_eq!(gradient.sample(0.3, 1.0), Color::Red);
+
+        let empty = Gradient::new([], GradientDirection::Horizontal);
+        assert_eq!(empty.sample(0.5, 0.5), Color::Reset);
+    }
+
+    #[test]
+    fn snap_to_palette() {
+        let gradient = Gradient::new([BLACK, WHITE], GradientDirection::Horizontal);
+        let gradient = gradient.with_truecolor(false);
+        assert_eq!(gradient.sample(0.0, 0.0), Color::AnsiValue(0));
+        assert_eq!(gradient.sample(0.5, 0.0), Color::AnsiValue(244));
+        assert_eq!(gradient.sample(1.0, 0.0), Color::AnsiValue(15));
+    }
+
+    #[test]
+    fn sample_cells() {
+        let gradient = gradient([BLACK, WHITE], GradientDirection::Horizontal);
+        let size = Size::new(3, 1);
+        assert_eq!(gradient.sample_cell(0, 0, size), BLACK);
+        assert_eq!(gradient.sample_cell(1, 0, size), GREY);
+        assert_eq!(gradient.sample_cell(2, 0, size), WHITE);
+
+        // A single cell is the first stop
+        assert_eq!(gradient.sample_cell(0, 0, Size::new(1, 1)), BLACK);
+    }
+}
@@ -31,6 +31,9 @@
 use std::ops::{Add, Sub};
 
 mod buffer;
+mod color;
+mod gradient;
+mod link;
 mod screen;
 mod style;
 
@@ -42,6 +45,9 @@ pub use crossterm::terminal::size;
 pub use screen::Screen;
 
 pub use crate::buffer::Buffer;
+pub use crate::color::{truecolor_supported, ColorExt, ColorParseError};
+pub use crate::gradient::{Gradient, GradientDirection};
+pub use crate::link::{hyperlinks_supported, Link};
 pub use crate::style::{Attributes, Style};
 
 /// Size
@@ -61,6 +67,57 @@ impl Size {
     pub const fn new(width: usize, height: usize) -> Self {
         Self { width, height }
     }
+
+    /// Subtract a size from another size.
+    /// Returns `None` if either the width or the height would be less than zero.
+    /// ```
+    /// # use anathema_render::Size;
+    /// let size = Size::new(5, 5);
+    /// assert_eq!(size.checked_sub(Size::new(2, 5)), Some(Size::new(3, 0)));
+    /// assert_eq!(size.checked_sub(Size::new(6, 1)), None);
+    /// ```
+    pub const fn checked_sub(self, other: Self) -> Option<Self> {
+        let Some(width) = self.width.checked_sub(other.width) else {
+            return None;
+        };
+        let Some(height) = self.height.checked_sub(other.height) else {
+            return None;
+        };
+        Some(Self::new(width, height))
+    }
+
+    /// Subtract a size from another size, stopping at zero.
+    /// ```
+    /// # use anathema_render::Size;
+    /// let size = Size::new(5, 5);
+    /// assert_eq!(size.saturating_sub(Size::new(6, 1)), Size::new(0, 4));
+    /// ```
+    pub const fn saturating_sub(self, other: Self) -> Self {
+        Self::new(
+            self.width.saturating_sub(other.width),
+            self.height.saturating_sub(other.height),
+        )
+    }
+
+    /// Add two sizes together.
+    /// Returns `None` if either the width or the height would overflow.
+    pub const fn checked_add(self, other: Self) -> Option<Self> {
+        let Some(width) = self.width.checked_add(other.width) else {
+            return None;
+        };
+        let Some(height) = self.height.checked_add(other.height) else {
+            return None;
+        };
+        Some(Self::new(width, height))
+    }
+
+    /// Add two sizes together, stopping at `usize::MAX`.
+    pub const fn saturating_add(self, other: Self) -> Self {
+        Self::new(
+            self.width.saturating_add(other.width),
+            self.height.saturating_add(other.height),
+        )
+    }
 }
 
 impl From<(usize, usize)> for Size {
@@ -75,25 +132,22 @@ impl From<(u16, u16)> for Size {
     }
 }
 
+/// Saturating addition, see [`Size::saturating_add`].
+/// An unbounded size (`usize::MAX`) stays unbounded.
 impl Add for Size {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Self {
-            width: self.width + other.width,
-            height: self.height + other.height,
-        }
+        self.saturating_add(other)
     }
 }
 
+/// Saturating subtraction, see [`Size::saturating_sub`].
 impl Sub for Size {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        Self {
-            width: self.width - other.width,
-            height: self.height - other.height,
-        }
+        self.saturating_sub(other)
     }
 }
 
@@ -121,3 +175,28 @@ impl ScreenPos {
         Self { x, y }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn size_sub() {
+        let size = Size::new(3, 4);
+        assert_eq!(size - Size::new(1, 1), Size::new(2, 3));
+        assert_eq!(size - Size::new(5, 1), Size::new(0, 3));
+        assert_eq!(size.checked_sub(size), Some(Size::ZERO));
+        assert_eq!(size.checked_sub(Size::new(3, 5)), None);
+    }
+
+    #[test]
+    fn size_add_overflow() {
+        let unbounded = Size::new(usize::MAX, 1);
+        assert_eq!(unbounded + Size::new(2, 2), Size::new(usize::MAX, 3));
+        assert_eq!(unbounded.checked_add(Size::new(1, 0)), None);
+        assert_eq!(
+            unbounded.checked_add(Size::new(0, 1)),
+            Some(Size::new(usize::MAX, 2))
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::env;
+use std::io::{Result, Write};
+use std::sync::{Arc, OnceLock, RwLock};
+
+// Every url gets an id the first time a link to it is created,
+// shared by all threads. The urls are kept for as long as the process runs,
+// so a link is only the size of the id.
+#[derive(Default)]
+struct Links {
+    ids: HashMap<Arc<str>, u32>,
+    urls: Vec<Arc<str>>,
+}
+
+static LINKS: OnceLock<RwLock<Links>> = OnceLock::new();
+
+fn links() -> &'static RwLock<Links> {
+    LINKS.get_or_init(Default::default)
+}
+
+/// A hyperlink, emitted as an OSC 8 escape sequence around the cells it covers.
+///
+/// The url is interned, so the link can be stored in a [`crate::Style`]
+/// which has to remain `Copy`. Links to the same url are equal,
+/// on any thread.
+///
+/// ```
+/// use anathema_render::Link;
+/// let a = Link::new("https://example.com");
+/// let b = Link::new("https://example.com");
+/// assert_eq!(a, b);
+/// assert_eq!(&*a.url(), "https://example.com");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Link(u32);
+
+impl Link {
+    /// Create a new link (or reuse an existing one with the same url)
+    pub fn new(url: &str) -> Self {
+        if let Some(id) = links().read().expect("poisoned lock").ids.get(url) {
+            return Self(*id);
+        }
+
+        let mut links = links().write().expect("poisoned lock");
+        // Another thread could have added the url in the meantime
+        if let Some(id) = links.ids.get(url) {
+            return Self(*id);
+        }
+
+        let id = links.urls.len() as u32;
+        let url: Arc<str> = url.into();
+        links.urls.push(url.clone());
+        links.ids.insert(url, id);
+        Self(id)
+    }
+
+    /// The url of the link
+    pub fn url(&self) -> Arc<str> {
+        links().read().expect("poisoned lock").urls[self.0 as usize].clone()
+    }
+
+    // The id is written as part of the sequence so terminals can
+    // join segments of the same link that span multiple lines.
+    pub(crate) fn write_open(&self, w: &mut impl Write) -> Result<()> {
+        write!(w, "\x1b]8;id={};{}\x1b\\", self.0, self.url())
+    }
+
+    pub(crate) fn write_close(w: &mut impl Write) -> Result<()> {
+        write!(w, "\x1b]8;;\x1b\\")
+    }
+}
+
+/// Best effort detection of OSC 8 support in the current terminal.
+///
+/// Set `ANATHEMA_HYPERLINKS` to `0` or `1` to override the detection.
+pub fn hyperlinks_supported() -> bool {
+    if let Ok(value) = env::var("ANATHEMA_HYPERLINKS") {
+        return value != "0";
+    }
+
+    if env::var_os("WT_SESSION").is_some() || env::var_os("VTE_VERSION").is_some() {
+        return true;
+    }
+
+    if let Ok(program) = env::var("TERM_PROGRAM") {
+        if matches!(
+            program.as_str(),
+            "iTerm.app" | "WezTerm" | "vscode" | "Hyper" | "ghostty"
+        ) {
+            return true;
+        }
+    }
+
+    match env::var("TERM") {
+        Ok(term) => term.contains("kitty") || term.contains("foot") || term.contains("alacritty"),
+        Err(_) => false,
+    }
+}
@@ -7,14 +7,16 @@ use crossterm::terminal::{
 };
 use crossterm::{cursor, ExecutableCommand, QueueableCommand};
 
-use super::buffer::{diff, draw_changes, Buffer};
-use super::{ScreenPos, Size, Style};
+use super::buffer::{diff, draw_changes, draw_lines, Buffer};
+use super::{hyperlinks_supported, ColorExt, ScreenPos, Size, Style};
 
 /// The `Screen` is used to draw to some `std::io::Write`able output (generally `stdout`);
 pub struct Screen {
     // This is pub(crate) for testing purposes
     pub(crate) new_buffer: Buffer,
     old_buffer: Buffer,
+    hyperlinks: bool,
+    origin: u16,
 }
 
 impl Screen {
@@ -51,9 +53,39 @@ impl Screen {
         Self {
             old_buffer: Buffer::new(size),
             new_buffer: Buffer::new(size),
+            hyperlinks: hyperlinks_supported(),
+            origin: 0,
         }
     }
 
+    /// Set the terminal row where the screen starts (default: 0).
+    ///
+    /// This makes it possible to draw below existing output in the terminal,
+    /// rather than from the top of the terminal. The size of the screen should
+    /// not exceed the rows below the origin.
+    pub fn set_origin(&mut self, row: u16) {
+        self.origin = row;
+    }
+
+    /// The terminal row where the screen starts
+    pub fn origin(&self) -> u16 {
+        self.origin
+    }
+
+    /// Enable or disable hyperlinks (OSC 8).
+    /// When disabled, text with a link is drawn as plain text.
+    ///
+    /// This is enabled by default if the terminal is detected to support it
+    /// (see [`hyperlinks_supported`](crate::hyperlinks_supported)).
+    pub fn set_hyperlinks(&mut self, enabled: bool) {
+        self.hyperlinks = enabled;
+    }
+
+    /// Returns true if hyperlinks are written to the output
+    pub fn hyperlinks(&self) -> bool {
+        self.hyperlinks
+    }
+
     /// Access to the current buffer
     pub fn buffer(&self) -> &Buffer {
         &self.new_buffer
@@ -80,13 +112,19 @@ impl Screen {
     /// when initialising a blank screen.
     ///
     /// To clear the screen between draw calls, use [`Screen::erase`]
+    ///
+    /// If the screen has an [origin](Self::set_origin) only the rows from the origin
+    /// and down are cleared.
     pub fn clear_all(&mut self, mut output: impl Write) -> Result<()> {
         self.erase();
         output.flush()?;
-        output.queue(cursor::MoveTo(0, 0))?;
+        output.queue(cursor::MoveTo(0, self.origin))?;
         output.queue(SetForegroundColor(Color::Reset))?;
         output.queue(SetBackgroundColor(Color::Reset))?;
-        output.queue(Clear(ClearType::All))?;
+        match self.origin {
+            0 => output.queue(Clear(ClearType::All))?,
+            _ => output.queue(Clear(ClearType::FromCursorDown))?,
+        };
         output.flush()?;
         Ok(())
     }
@@ -99,8 +137,7 @@ impl Screen {
     /// Erase a specific region.
     /// Will reset the styles for all the cells as well.
     pub fn erase_region(&mut self, pos: ScreenPos, size: Size) {
-        let to_x = (size.width as u16 + pos.x).min(self.size().width as u16);
-        let to_y = (size.height as u16 + pos.y).min(self.size().height as u16);
+        let (to_x, to_y) = self.region_end(pos, size);
 
         for x in pos.x.min(to_x)..to_x {
             for y in pos.y.min(to_y)..to_y {
@@ -109,6 +146,44 @@ impl Screen {
         }
     }
 
+    // The end of a region (exclusive), clamped to the size of the screen.
+    // The size of the region can be larger than the screen (e.g unbounded).
+    fn region_end(&self, pos: ScreenPos, size: Size) -> (u16, u16) {
+        let to_u16 = |len: usize| u16::try_from(len).unwrap_or(u16::MAX);
+        let screen = self.size();
+        let to_x = to_u16(size.width).saturating_add(pos.x);
+        let to_y = to_u16(size.height).saturating_add(pos.y);
+        (
+            to_x.min(to_u16(screen.width)),
+            to_y.min(to_u16(screen.height)),
+        )
+    }
+
+    /// Blend a colour on top of everything drawn in a region, e.g to dim the
+    /// content behind a modal: `screen.blend_region(pos, size, Color::Black, 0.5)`.
+    ///
+    /// Both the foreground and the background of the cells are blended (see [`ColorExt::blend`]).
+    /// Cells without a background are blended against the default background of the terminal,
+    /// while cells with the default foreground keep it.
+    pub fn blend_region(&mut self, pos: ScreenPos, size: Size, over: Color, alpha: f32) {
+        let (to_x, to_y) = self.region_end(pos, size);
+
+        for x in pos.x.min(to_x)..to_x {
+            for y in pos.y.min(to_y)..to_y {
+                let Some(style) = self.new_buffer.style_mut(ScreenPos::new(x, y)) else {
+                    continue;
+                };
+                let bg = style.bg.unwrap_or(Color::Reset);
+                style.bg = Some(bg.blend(over, alpha));
+                // The default foreground is left as is, as it could end up
+                // the same colour as the background
+                if let Some(fg) = style.fg.filter(|fg| *fg != Color::Reset) {
+                    style.fg = Some(fg.blend(over, alpha));
+                }
+            }
+        }
+    }
+
     /// Put a char at the given screen position, with a given style.
     /// If the screen position is outside the [`Buffer`]s size then this is
     /// out of bounds and will panic.
@@ -129,14 +204,38 @@ impl Screen {
             return Ok(());
         }
 
-        draw_changes(&mut output, changes)?;
+        draw_changes(&mut output, changes, self.hyperlinks, self.origin)?;
         output.flush()?;
 
-        self.old_buffer = self.new_buffer.clone();
+        // Reuse the allocation of the old buffer
+        self.old_buffer.clone_from(&self.new_buffer);
+
+        Ok(())
+    }
 
+    /// Write the current frame as lines of text, starting at the current cursor position.
+    ///
+    /// Unlike [`Screen::render`] this doesn't move the cursor, and ends with a newline
+    /// so the cursor is placed below the output. Trailing empty lines are skipped.
+    ///
+    /// This is useful to leave the last frame in the main screen
+    /// after leaving the alternative screen.
+    pub fn write_frame(&self, mut output: impl Write) -> Result<()> {
+        draw_lines(&mut output, &self.new_buffer, self.hyperlinks)?;
+        output.flush()?;
         Ok(())
     }
 
+    /// The current frame as text, including the escape sequences for the styles
+    /// (see [`Screen::write_frame`]).
+    /// Use the [`Display`](std::fmt::Display) implementation of the [`Buffer`] for the text without styles.
+    pub fn to_ansi_string(&self) -> String {
+        let mut output = vec![];
+        // Writing to a `Vec` doesn't fail
+        let _ = draw_lines(&mut output, &self.new_buffer, self.hyperlinks);
+        String::from_utf8_lossy(&output).into_owned()
+    }
+
     /// Enter an alternative screen.
     /// When using this with stdout it means the output will not persist once the program exits.
     pub fn enter_alt_screen(&self, mut output: impl Write) -> Result<()> {
@@ -199,6 +298,56 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn blend_region() {
+        let mut screen = Screen::new(Size::new(2, 1));
+        let mut style = Style::new();
+        style.set_fg(Color::Rgb {
+            r: 200,
+            g: 200,
+            b: 200,
+        });
+        style.set_bg(Color::White);
+        screen.put('x', style, ScreenPos::ZERO);
+
+        screen.blend_region(ScreenPos::ZERO, Size::new(10, 10), Color::Black, 0.5);
+
+        let (c, style) = screen.get(ScreenPos::ZERO).unwrap();
+        assert_eq!(c, 'x');
+        assert_eq!(
+            style.fg,
+            Some(Color::Rgb {
+                r: 100,
+                g: 100,
+                b: 100
+            })
+        );
+        assert_eq!(style.bg, Some(Color::DarkGrey));
+
+        // Empty cells have the default background
+        let empty = screen.new_buffer.inner[1];
+        assert_eq!(empty.style.bg, Some(Color::Black));
+    }
+
+    #[test]
+    fn blend_unbounded_region() {
+        let mut screen = make_screen(Size::new(2, 1));
+        screen.blend_region(
+            ScreenPos::new(1, 0),
+            Size::new(usize::MAX, 1),
+            Color::Black,
+            1.0,
+        );
+        assert_eq!(
+            screen.get(ScreenPos::ZERO).unwrap().1.bg,
+            Some(Color::Reset)
+        );
+        assert_eq!(
+            screen.get(ScreenPos::new(1, 0)).unwrap().1.bg,
+            Some(Color::Black)
+        );
+    }
+
     #[test]
     fn erase_region() {
         // Erase a whole region, leaving all cells `empty`
@@ -215,6 +364,15 @@ mod test {
         assert_eq!(Cell::empty(), bottom_right);
     }
 
+    #[test]
+    fn erase_unbounded_region() {
+        let mut screen = make_screen(Size::new(2, 2));
+        screen.erase_region(ScreenPos::new(1, 0), Size::new(usize::MAX, 70_000));
+        assert_eq!(Cell::new('0', Style::reset()), screen.new_buffer.inner[0]);
+        assert_eq!(Cell::empty(), screen.new_buffer.inner[1]);
+        assert_eq!(Cell::empty(), screen.new_buffer.inner[3]);
+    }
+
     #[test]
     fn clear_all() {
         // Clear the entire screen, as well as the buffers
@@ -225,6 +383,25 @@ mod test {
         assert_eq!(Cell::empty(), actual);
     }
 
+    #[test]
+    fn render_below_origin() {
+        let mut render_output = vec![];
+        let mut screen = Screen::new(Size::new(2, 1));
+        screen.set_origin(3);
+        screen.put('x', Style::reset(), ScreenPos::new(1, 0));
+        screen.render(&mut render_output).unwrap();
+
+        let output = String::from_utf8(render_output).unwrap();
+        // Row 4, column 2 (one-based)
+        assert!(output.starts_with("\x1b[4;2H"));
+
+        let mut clear_output = vec![];
+        screen.clear_all(&mut clear_output).unwrap();
+        let output = String::from_utf8(clear_output).unwrap();
+        assert!(output.starts_with("\x1b[4;1H"));
+        assert!(output.ends_with("\x1b[J"));
+    }
+
     #[test]
     #[should_panic(expected = "index out of bounds: the len is 1 but the index is 4")]
     fn put_outside_of_screen() {
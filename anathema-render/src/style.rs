@@ -4,6 +4,8 @@ pub use crossterm::style::Attribute as CrossAttrib;
 use crossterm::style::{Color, SetAttribute, SetBackgroundColor, SetForegroundColor};
 use crossterm::QueueableCommand;
 
+use crate::Link;
+
 /// The style for a cell in a [`crate::Buffer`]
 /// A style is applied to ever single cell in a [`crate::Buffer`].
 ///
@@ -36,6 +38,9 @@ pub struct Style {
     pub bg: Option<Color>,
     /// Attributes.
     pub attributes: Attributes,
+    /// Hyperlink.
+    /// This is only written to the output if the [`crate::Screen`] has hyperlinks enabled.
+    pub link: Option<Link>,
 }
 
 impl Style {
@@ -45,6 +50,7 @@ impl Style {
             fg: None,
             bg: None,
             attributes: Attributes::empty(),
+            link: None,
         }
     }
 
@@ -116,6 +122,11 @@ impl Style {
         self.bg = Some(bg);
     }
 
+    /// Set the hyperlink
+    pub fn set_link(&mut self, link: Link) {
+        self.link = Some(link);
+    }
+
     /// Set the style to bold
     pub fn set_bold(&mut self, bold: bool) {
         if bold {
@@ -190,6 +201,7 @@ impl Style {
     /// Merge two styles:
     /// if `self` has no foreground the foreground from the other style is copied to self.
     /// if `self` has no background the background from the other style is copied to self.
+    /// if `self` has no link the link from the other style is copied to self.
     pub fn merge(&mut self, other: Style) {
         if let (None, Some(fg)) = (self.fg, other.fg) {
             self.fg = Some(fg);
@@ -199,6 +211,10 @@ impl Style {
             self.bg = Some(bg);
         }
 
+        if let (None, Some(link)) = (self.link, other.link) {
+            self.link = Some(link);
+        }
+
         self.attributes |= other.attributes;
     }
 }
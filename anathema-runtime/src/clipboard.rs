@@ -0,0 +1,24 @@
+use anathema_widget_core::clipboard::Clipboard;
+
+/// The system clipboard (requires the `clipboard` feature).
+///
+/// The runtime uses the system clipboard by default when the feature is enabled.
+pub struct SystemClipboard(arboard::Clipboard);
+
+impl SystemClipboard {
+    /// Access the system clipboard, or `None` if there is no clipboard
+    /// (e.g over ssh, or without a display server).
+    pub fn new() -> Option<Self> {
+        arboard::Clipboard::new().ok().map(Self)
+    }
+}
+
+impl Clipboard for SystemClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.0.get_text().ok()
+    }
+
+    fn set_text(&mut self, text: &str) -> bool {
+        self.0.set_text(text).is_ok()
+    }
+}
@@ -0,0 +1,134 @@
+use anathema_render::{ScreenPos, Size};
+use anathema_values::NodeId;
+use anathema_widget_core::{Nodes, Region};
+
+// -----------------------------------------------------------------------------
+//   - Damage -
+//   Tracks the regions of the widgets that changed since the last frame.
+//
+//   If a changed node is not a widget (e.g a loop or a view) or a widget
+//   changed its size or position, the entire screen has to be repainted, as
+//   this can affect the layout of other widgets.
+// -----------------------------------------------------------------------------
+#[derive(Debug, Default)]
+pub(super) enum Damage {
+    #[default]
+    Full,
+    Regions(Vec<(NodeId, Region)>),
+}
+
+impl Damage {
+    pub(super) fn full(&mut self) {
+        *self = Self::Full;
+    }
+
+    // Add a changed node, along with the region it covered before the change.
+    pub(super) fn add(&mut self, node_id: NodeId, region: Option<Region>) {
+        match (&mut *self, region) {
+            (Self::Regions(regions), Some(region)) => regions.push((node_id, region)),
+            _ => self.full(),
+        }
+    }
+
+    // Returns the region to repaint, or `None` if the entire screen should be repainted.
+    // This should be called after the layout and position of the nodes.
+    pub(super) fn take(&mut self, nodes: &mut Nodes<'_>) -> Option<Region> {
+        let regions = match std::mem::replace(self, Self::Regions(vec![])) {
+            Self::Full => return None,
+            Self::Regions(regions) => regions,
+        };
+
+        clip_region(&regions, |node_id| nodes.region(node_id))
+    }
+}
+
+fn clip_region(
+    regions: &[(NodeId, Region)],
+    mut current: impl FnMut(&NodeId) -> Option<Region>,
+) -> Option<Region> {
+    let mut clip: Option<Region> = None;
+
+    for (node_id, previous) in regions {
+        if current(node_id) != Some(*previous) {
+            return None;
+        }
+
+        clip = Some(match clip {
+            Some(clip) => clip.union(previous),
+            None => *previous,
+        });
+    }
+
+    clip
+}
+
+// Convert a region to a screen position and size, for erasing.
+// Returns `None` if the region is outside of the screen
+pub(super) fn screen_region(region: &Region) -> Option<(ScreenPos, Size)> {
+    let x = u16::try_from(region.from.x).ok()?;
+    let y = u16::try_from(region.from.y).ok()?;
+    let pos = ScreenPos::new(x, y);
+    let size = Size::new(
+        (region.to.x - region.from.x + 1) as usize,
+        (region.to.y - region.from.y + 1) as usize,
+    );
+    Some((pos, size))
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::Pos;
+
+    use super::*;
+
+    fn region(x: i32, y: i32, width: i32, height: i32) -> Region {
+        Region::new(Pos::new(x, y), Pos::new(x + width - 1, y + height - 1))
+    }
+
+    #[test]
+    fn union_of_unchanged_widgets() {
+        let regions = vec![
+            (NodeId::new(0), region(0, 0, 2, 2)),
+            (NodeId::new(1), region(5, 5, 1, 1)),
+            // Overlapping the first region
+            (NodeId::new(2), region(1, 1, 2, 1)),
+        ];
+
+        let clip = clip_region(&regions, |id| {
+            regions.iter().find(|(i, _)| i == id).map(|(_, r)| *r)
+        });
+
+        assert_eq!(clip, Some(Region::new(Pos::ZERO, Pos::new(5, 5))));
+    }
+
+    #[test]
+    fn resized_widget_repaints_everything() {
+        let regions = vec![
+            (NodeId::new(0), region(0, 0, 2, 2)),
+            (NodeId::new(1), region(5, 5, 1, 1)),
+        ];
+
+        let clip = clip_region(&regions, |id| match id == &NodeId::new(1) {
+            true => Some(region(5, 5, 2, 1)),
+            false => Some(region(0, 0, 2, 2)),
+        });
+
+        assert!(clip.is_none());
+    }
+
+    #[test]
+    fn non_widget_repaints_everything() {
+        let mut damage = Damage::Regions(vec![]);
+        damage.add(NodeId::new(0), Some(region(0, 0, 1, 1)));
+        damage.add(NodeId::new(1), None);
+        assert!(matches!(damage, Damage::Full));
+    }
+
+    #[test]
+    fn screen_pos_and_size() {
+        let (pos, size) = screen_region(&region(2, 3, 4, 5)).unwrap();
+        assert_eq!(pos, ScreenPos::new(2, 3));
+        assert_eq!(size, Size::new(4, 5));
+        assert!(screen_region(&region(-1, 0, 2, 2)).is_none());
+    }
+}
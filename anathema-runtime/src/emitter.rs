@@ -0,0 +1,66 @@
+use std::sync::mpsc::{self, Receiver, SendError, Sender};
+
+use anathema_widget_core::Event;
+
+/// Send synthetic events to a running [`Runtime`](crate::Runtime),
+/// as if they came from the terminal.
+///
+/// This is useful for tests and for replaying macros.
+///
+/// Synthetic events are handled at the start of the next frame, in the order they
+/// were emitted, before any pending terminal events.
+///
+/// ```
+/// # use anathema_runtime::Runtime;
+/// # use anathema_vm::CompiledTemplates;
+/// # use anathema_widget_core::{Event, KeyCode, KeyEventState, KeyModifiers};
+/// # fn run(templates: &CompiledTemplates) {
+/// let runtime = Runtime::new(templates).unwrap();
+/// let emitter = runtime.emitter();
+///
+/// std::thread::spawn(move || {
+///     let key = Event::KeyPress(KeyCode::Enter, KeyModifiers::NONE, KeyEventState::NONE);
+///     emitter.emit(key).unwrap();
+///     emitter.emit(Event::Quit).unwrap();
+/// });
+///
+/// runtime.run().unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Emitter(Sender<Event>);
+
+impl Emitter {
+    pub(crate) fn new() -> (Self, Receiver<Event>) {
+        let (tx, rx) = mpsc::channel();
+        (Self(tx), rx)
+    }
+
+    /// Emit an event.
+    /// This fails if the runtime is no longer running.
+    pub fn emit(&self, event: Event) -> Result<(), SendError<Event>> {
+        self.0.send(event)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn events_in_order() {
+        let (emitter, rx) = Emitter::new();
+        emitter.emit(Event::Focus).unwrap();
+        emitter.clone().emit(Event::Quit).unwrap();
+
+        let events = rx.try_iter().collect::<Vec<_>>();
+        assert!(matches!(events[..], [Event::Focus, Event::Quit]));
+    }
+
+    #[test]
+    fn emit_after_shutdown() {
+        let (emitter, rx) = Emitter::new();
+        drop(rx);
+        assert!(emitter.emit(Event::Quit).is_err());
+    }
+}
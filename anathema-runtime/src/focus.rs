@@ -0,0 +1,226 @@
+use anathema_values::NodeId;
+use anathema_widget_core::{Event, Nodes};
+
+use crate::tabindex::Direction;
+
+// -----------------------------------------------------------------------------
+//   - Focus manager -
+//   Keeps track of the focused widget.
+//
+//   The focus moves between the widgets that accept focus in the order
+//   they appear in the node tree, wrapping around at either end.
+//   If the focused widget is removed (e.g by a loop or an if statement)
+//   the focus starts over from the first (or last) widget.
+// -----------------------------------------------------------------------------
+pub(crate) struct FocusManager {
+    current: Option<NodeId>,
+}
+
+impl FocusManager {
+    pub(crate) fn new() -> Self {
+        Self { current: None }
+    }
+
+    pub(crate) fn focus_next(&mut self, nodes: &mut Nodes<'_>) {
+        self.cycle(nodes, Direction::Forwards)
+    }
+
+    pub(crate) fn focus_prev(&mut self, nodes: &mut Nodes<'_>) {
+        self.cycle(nodes, Direction::Backwards)
+    }
+
+    fn cycle(&mut self, nodes: &mut Nodes<'_>, direction: Direction) {
+        let focusable = nodes.focusable();
+
+        let next = match focusable.len() {
+            0 => None,
+            len => {
+                let index = self
+                    .current
+                    .as_ref()
+                    .and_then(|current| focusable.iter().position(|id| id == current));
+
+                let index = match index {
+                    Some(index) => direction.next(index, len - 1),
+                    None => direction.default(len - 1),
+                };
+
+                Some(focusable[index].clone())
+            }
+        };
+
+        if next == self.current {
+            return;
+        }
+
+        if let Some(old) = self.current.take() {
+            nodes.with_widget(&old, |widget| widget.blur());
+        }
+
+        if let Some(next) = &next {
+            nodes.with_widget(next, |widget| widget.focus());
+        }
+
+        self.current = next;
+    }
+
+    // Pass the event to the focused widget.
+    // Returns true if the widget handled the event.
+    pub(crate) fn on_event(&mut self, nodes: &mut Nodes<'_>, event: Event) -> bool {
+        let Some(current) = &self.current else {
+            return false;
+        };
+
+        nodes
+            .with_widget(current, |widget| widget.on_event(event))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::Size;
+    use anathema_values::{Context, Value};
+    use anathema_vm::Templates;
+    use anathema_widget_core::contexts::PositionCtx;
+    use anathema_widget_core::error::Result;
+    use anathema_widget_core::layout::Constraints;
+    use anathema_widget_core::nodes::make_it_so;
+    use anathema_widget_core::{
+        AnyWidget, Factory, FactoryContext, KeyCode, KeyEventState, KeyModifiers, LayoutNodes,
+        Widget,
+    };
+    use anathema_widgets::register_default_widgets;
+
+    use super::*;
+    use crate::layout_root;
+
+    #[derive(Debug)]
+    struct Button {
+        label: Value<String>,
+        focused: bool,
+        presses: usize,
+    }
+
+    impl Widget for Button {
+        fn layout(&mut self, _nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+            Ok(Size::new(1, 1))
+        }
+
+        fn position(&mut self, _children: &mut Nodes<'_>, _ctx: PositionCtx) {}
+
+        fn accepts_focus(&self) -> bool {
+            true
+        }
+
+        fn focus(&mut self) {
+            self.focused = true;
+        }
+
+        fn blur(&mut self) {
+            self.focused = false;
+        }
+
+        fn on_event(&mut self, event: Event) -> bool {
+            match event {
+                Event::KeyPress(KeyCode::Enter, ..) => {
+                    self.presses += 1;
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    fn register_button() {
+        // The widgets are registered once for all the tests
+        let _ = register_default_widgets();
+        let _ = Factory::register("button", |ctx: FactoryContext<'_>| {
+            let button = Button {
+                label: ctx.text,
+                focused: false,
+                presses: 0,
+            };
+            Ok(Box::new(button) as Box<dyn AnyWidget>)
+        });
+    }
+
+    // The label of every button, if it's focused, and the number of times it was pressed
+    fn buttons(nodes: &mut Nodes<'_>) -> Vec<(String, bool, usize)> {
+        nodes
+            .focusable()
+            .iter()
+            .filter_map(|node_id| {
+                nodes.with_widget(node_id, |widget| {
+                    let button = widget.to_ref::<Button>();
+                    let label = button.label.value_ref().cloned().unwrap_or_default();
+                    (label, button.focused, button.presses)
+                })
+            })
+            .collect()
+    }
+
+    fn focused(nodes: &mut Nodes<'_>) -> Vec<String> {
+        buttons(nodes)
+            .into_iter()
+            .filter_map(|(label, focused, _)| focused.then_some(label))
+            .collect()
+    }
+
+    #[test]
+    fn focus_in_document_order() {
+        register_button();
+        let src = "
+vstack
+    button 'a'
+    text 'not focusable'
+    hstack
+        button 'b'
+    button 'c'
+";
+        let mut templates = Templates::new(src.into(), ());
+        let templates = templates.compile().unwrap();
+        let mut nodes = make_it_so(templates.expressions());
+        layout_root(&mut nodes, Constraints::new(20, 20), &Context::root(&())).unwrap();
+
+        let mut focus = FocusManager::new();
+        assert!(focused(&mut nodes).is_empty());
+
+        focus.focus_next(&mut nodes);
+        assert_eq!(focused(&mut nodes), vec!["a"]);
+        focus.focus_next(&mut nodes);
+        assert_eq!(focused(&mut nodes), vec!["b"]);
+        focus.focus_next(&mut nodes);
+        assert_eq!(focused(&mut nodes), vec!["c"]);
+
+        // Wrap around
+        focus.focus_next(&mut nodes);
+        assert_eq!(focused(&mut nodes), vec!["a"]);
+        focus.focus_prev(&mut nodes);
+        assert_eq!(focused(&mut nodes), vec!["c"]);
+    }
+
+    #[test]
+    fn route_events_to_focused_widget() {
+        register_button();
+        let mut templates = Templates::new("vstack\n    button 'a'\n    button 'b'".into(), ());
+        let templates = templates.compile().unwrap();
+        let mut nodes = make_it_so(templates.expressions());
+        layout_root(&mut nodes, Constraints::new(20, 20), &Context::root(&())).unwrap();
+
+        let enter = Event::KeyPress(KeyCode::Enter, KeyModifiers::NONE, KeyEventState::NONE);
+        let mut focus = FocusManager::new();
+
+        // Nothing is focused
+        assert!(!focus.on_event(&mut nodes, enter));
+
+        focus.focus_prev(&mut nodes);
+        assert!(focus.on_event(&mut nodes, enter));
+        let unhandled = Event::KeyPress(KeyCode::Esc, KeyModifiers::NONE, KeyEventState::NONE);
+        assert!(!focus.on_event(&mut nodes, unhandled));
+
+        let buttons = buttons(&mut nodes);
+        assert_eq!(buttons[0], ("a".to_string(), false, 0));
+        assert_eq!(buttons[1], ("b".to_string(), true, 1));
+    }
+}
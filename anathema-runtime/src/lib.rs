@@ -1,30 +1,58 @@
-use std::io::{stdout, Stdout};
+use std::io::{self, stdout, Stdout, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::Receiver;
 use std::time::{Duration, Instant};
 
 use anathema_render::{size, Screen, Size};
-use anathema_values::{drain_dirty_nodes, Context};
-use anathema_vm::CompiledTemplates;
-use anathema_widget_core::contexts::PaintCtx;
-use anathema_widget_core::error::Result;
+use anathema_values::{
+    drain_dirty_nodes, drain_resolve_errors, Context, FunctionResult, Functions, ResolveError,
+    ValueRef,
+};
+use anathema_vm::{CompiledTemplates, Templates};
+use anathema_widget_core::clipboard::Clipboard;
+use anathema_widget_core::contexts::{PaintCtx, RuntimeContext};
+use anathema_widget_core::error::{Error, Result};
 use anathema_widget_core::layout::Constraints;
 use anathema_widget_core::nodes::{make_it_so, Nodes};
 use anathema_widget_core::views::Views;
-use anathema_widget_core::{Event, Events, KeyCode, LayoutNodes, Pos};
+use anathema_widget_core::{
+    Event, Events, Factory, KeyCode, LayoutNodes, Pos, Region, WidgetFactory,
+};
 use anathema_widgets::register_default_widgets;
-use crossterm::terminal::enable_raw_mode;
+use crossterm::cursor::{self, MoveTo};
+use crossterm::QueueableCommand;
 use tabindex::Direction;
 
+#[cfg(feature = "clipboard")]
+pub use crate::clipboard::SystemClipboard;
+use crate::damage::{screen_region, Damage};
+pub use crate::emitter::Emitter;
+use crate::focus::FocusManager;
+use crate::overlay::DebugOverlay;
+use crate::reload::HotReload;
+use crate::resize::PendingResize;
 use crate::tabindex::TabIndexing;
+use crate::terminal::TerminalGuard;
 
 #[allow(unused_extern_crates)]
 extern crate anathema_values as anathema;
 
+#[cfg(feature = "clipboard")]
+mod clipboard;
+mod damage;
+mod emitter;
+mod focus;
 mod meta;
+mod overlay;
+mod reload;
+mod resize;
 mod tabindex;
+mod terminal;
 
 /// The runtime handles events, tab indices and configuration of the display
 ///
 /// ```
+/// # use std::time::Duration;
 /// # use anathema_runtime::Runtime;
 /// # use anathema_vm::CompiledTemplates;
 /// # fn run(templates: &CompiledTemplates) {
@@ -32,7 +60,7 @@ mod tabindex;
 /// let mut runtime = Runtime::new(templates).unwrap();
 /// runtime.enable_mouse = true;
 /// runtime.enable_alt_screen = false;
-/// runtime.fps = 120;
+/// runtime.tick_rate = Duration::from_secs(1) / 120;
 /// runtime.run().unwrap();
 /// # }
 /// ```
@@ -51,7 +79,10 @@ pub struct Runtime<'e> {
     /// _count
     /// ```
     pub enable_meta: bool,
-    /// Enable mouse support on terminals that supports it
+    /// Enable mouse support on terminals that supports it.
+    /// Mouse events (clicks, scrolling and motion) are passed to the views
+    /// with terminal coordinates, and mouse capture is disabled again
+    /// when the runtime shuts down.
     pub enable_mouse: bool,
     /// This captures the ctrl+c command and terminates the runtime.
     pub enable_ctrlc: bool,
@@ -62,72 +93,375 @@ pub struct Runtime<'e> {
     ///
     /// The root view will never have a tab index
     pub enable_tabindex: bool,
+    /// Enable focus for widgets that accept focus (see [`Widget::accepts_focus`](anathema_widget_core::Widget::accepts_focus)).
+    ///
+    /// Tab and shift+tab move the focus to the next and previous widget,
+    /// in the order the widgets appear in the template. This replaces tabbing between views.
+    ///
+    /// Key events are passed to the focused widget first, and only
+    /// passed on to the views if the widget doesn't handle them.
+    pub enable_focus: bool,
     /// This will create an alternate screen and render to this screen.
     /// This retains the old content of the terminal and restores it once the
     /// runtime terminates.
+    ///
+    /// If this is disabled the runtime renders in place, using the rows from the cursor
+    /// to the bottom of the terminal, and those rows are cleared once the runtime
+    /// terminates, leaving the old content of the terminal as it was.
     pub enable_alt_screen: bool,
-    /// Set the target number of frames to render per second.
-    pub fps: u8,
+    /// Enable raw mode (default: true).
+    ///
+    /// In raw mode every key press is passed to the runtime as it happens,
+    /// rather than the terminal handling the input (e.g echoing the keys and
+    /// sending ctrl+c as a signal).
+    ///
+    /// Raw mode is disabled again once the runtime terminates, even if it panics.
+    pub enable_raw_mode: bool,
+    /// Leave the last frame in the terminal once the runtime exits without an error,
+    /// with the cursor placed below it.
+    ///
+    /// When using the alternate screen, the frame is written to the main screen
+    /// after leaving the alternate screen.
+    pub leave_output_on_exit: bool,
+    /// The time between two ticks (default: 30 ticks per second).
+    ///
+    /// Events are handled as they arrive, and once per tick every view is ticked
+    /// (see [`View::tick`](anathema_widget_core::views::View::tick)) and any changes are drawn.
+    /// Nothing is drawn if nothing changed.
+    ///
+    /// `Duration::ZERO` means the runtime ticks (and draws any changes) as fast as possible.
+    pub tick_rate: Duration,
+    /// Enable the debug overlay, showing the current fps, layout time, paint time and
+    /// the number of widgets in the top right corner.
+    ///
+    /// The overlay can be toggled with [`Runtime::debug_overlay_key`].
+    /// While the overlay is visible the screen is redrawn every frame.
+    pub debug_overlay: bool,
+    /// The key used to toggle the debug overlay (default: `F12`).
+    pub debug_overlay_key: KeyCode,
+    /// Only repaint the regions of the widgets that changed.
+    ///
+    /// If a change affects anything other than a widget (e.g a loop or a view),
+    /// or a widget changes its size or position, the entire screen is repainted.
+    pub enable_damage_regions: bool,
+    /// How long the size of the terminal has to stay the same after a resize
+    /// before the layout is updated (default: zero).
+    ///
+    /// Every resize event is passed to the views as it arrives, but the layout is
+    /// only updated for the last size. With a zero duration the layout is updated
+    /// at the end of the tick the resize arrived in.
+    ///
+    /// If the terminal is too small to fit the widgets, they are clipped.
+    pub resize_debounce: Duration,
     screen: Screen,
-    output: Stdout,
+    output: Box<dyn Write>,
     constraints: Constraints,
     nodes: Nodes<'e>,
     events: Events,
+    emitter: Emitter,
+    injected: Receiver<Event>,
     needs_layout: bool,
     meta: meta::Meta,
     tabindex: TabIndexing,
-}
-
-impl<'e> Drop for Runtime<'e> {
-    fn drop(&mut self) {
-        let _ = self.screen.restore(&mut self.output);
-    }
+    focus: FocusManager,
+    overlay: DebugOverlay,
+    damage: Damage,
+    hot_reload: Option<HotReload>,
+    pending_resize: PendingResize,
+    clipboard: Option<Box<dyn Clipboard>>,
+    functions: Functions,
+    // The first call to `frame` has to do the initial layout
+    started: bool,
+    // Restores the terminal when the runtime is dropped
+    terminal: TerminalGuard<Stdout>,
 }
 
 impl<'e> Runtime<'e> {
     /// Create a new runtime.
+    ///
+    /// With the `clipboard` feature the runtime uses the system clipboard,
+    /// if there is one (see [`SystemClipboard`](crate::SystemClipboard)).
     pub fn new(templates: &'e CompiledTemplates) -> Result<Self> {
-        let expressions = templates.expressions();
         register_default_widgets()?;
+        let size: Size = size()?.into();
+        #[allow(unused_mut)]
+        let mut runtime = Self::with_output(templates, size, Box::new(stdout()));
+
+        #[cfg(feature = "clipboard")]
+        if let Some(clipboard) = SystemClipboard::new() {
+            runtime.set_clipboard(clipboard);
+        }
 
-        let nodes = make_it_so(expressions);
+        Ok(runtime)
+    }
 
-        let size: Size = size()?.into();
+    /// Create a runtime without a terminal, with a screen of the given size.
+    /// This is useful for testing templates and views.
+    ///
+    /// The frames are laid out and painted the same way as with a terminal,
+    /// but they are only drawn to the [`Screen`].
+    /// Call [`Runtime::frame`] instead of [`Runtime::run`] to draw a frame,
+    /// and use [`Runtime::screen`] to inspect it.
+    ///
+    /// ```
+    /// # use anathema_render::Size;
+    /// # use anathema_runtime::Runtime;
+    /// # use anathema_vm::Templates;
+    /// let mut templates = Templates::new("border\n    text 'hi'".into(), ());
+    /// let templates = templates.compile().unwrap();
+    ///
+    /// let mut runtime = Runtime::headless(&templates, Size::new(10, 5));
+    /// runtime.frame().unwrap();
+    ///
+    /// let expected = "┌──┐\n│hi│\n└──┘";
+    /// assert_eq!(runtime.screen().buffer().to_string(), expected);
+    /// ```
+    pub fn headless(templates: &'e CompiledTemplates, size: Size) -> Self {
+        // A test can create any number of runtimes, but the widgets
+        // can only be registered once
+        let _ = register_default_widgets();
+        Self::with_output(templates, size, Box::new(io::sink()))
+    }
+
+    fn with_output(templates: &'e CompiledTemplates, size: Size, output: Box<dyn Write>) -> Self {
+        let nodes = make_it_so(templates.expressions());
         let constraints = Constraints::new(Some(size.width), Some(size.height));
         let screen = Screen::new(size);
+        let (emitter, injected) = Emitter::new();
 
-        let inst = Self {
-            output: stdout(),
+        Self {
+            output,
             screen,
             constraints,
             nodes,
             enable_meta: false,
             enable_mouse: false,
             enable_alt_screen: true,
+            enable_raw_mode: true,
+            leave_output_on_exit: false,
             events: Events,
-            fps: 30,
+            emitter,
+            injected,
+            tick_rate: Duration::from_secs(1) / 30,
             needs_layout: true,
             meta: meta::Meta::new(size.width, size.height),
             tabindex: TabIndexing::new(),
             enable_ctrlc: true,
             enable_tabindex: false,
+            enable_focus: false,
+            focus: FocusManager::new(),
+            debug_overlay: false,
+            debug_overlay_key: KeyCode::F(12),
+            overlay: DebugOverlay::new(false),
+            enable_damage_regions: false,
+            damage: Damage::Full,
+            hot_reload: None,
+            resize_debounce: Duration::ZERO,
+            pending_resize: PendingResize::new(),
+            clipboard: None,
+            functions: templates.functions().clone(),
+            started: false,
+            terminal: TerminalGuard::new(stdout()),
+        }
+    }
+
+    /// Set the [`tick_rate`](Self::tick_rate) to a number of ticks per second.
+    /// Zero is treated as one tick per second.
+    #[deprecated(note = "set `tick_rate` instead")]
+    pub fn fps(&mut self, fps: u8) {
+        self.tick_rate = Duration::from_secs(1) / u32::from(fps.max(1));
+    }
+
+    /// The screen the frames are drawn to.
+    pub fn screen(&self) -> &Screen {
+        &self.screen
+    }
+
+    /// Register a function that can be called from the templates.
+    /// See [`anathema_vm::Templates::register_fn`].
+    pub fn register_fn<F>(&mut self, name: impl Into<String>, f: F)
+    where
+        F: Fn(&[ValueRef<'_>]) -> FunctionResult + Send + Sync + 'static,
+    {
+        self.functions.register_fn(name, f)
+    }
+
+    /// The most recent errors returned by functions called from the templates
+    /// (see [`Runtime::register_fn`]), oldest first.
+    ///
+    /// The errors are also shown by the debug overlay.
+    pub fn resolve_errors(&self) -> impl Iterator<Item = &ResolveError> {
+        self.overlay.errors()
+    }
+
+    /// Set the clipboard, replacing any previous clipboard, so views can copy and paste text
+    /// using [`RuntimeContext::clipboard`] (see [`View::on_event_with_context`](anathema_widget_core::views::View::on_event_with_context)).
+    ///
+    /// There is no clipboard by default, unless the `clipboard` feature is enabled.
+    pub fn set_clipboard(&mut self, clipboard: impl Clipboard + 'static) {
+        self.clipboard = Some(Box::new(clipboard));
+    }
+
+    /// Register a custom widget, so `ident` can be used in the templates.
+    /// See [`Factory::register`].
+    ///
+    /// Unknown widgets in a template fail with [`Error::UnregisteredWidget`](anathema_widget_core::error::Error::UnregisteredWidget),
+    /// naming the widget.
+    pub fn register_widget(
+        &mut self,
+        ident: impl Into<String>,
+        factory: impl WidgetFactory + 'static,
+    ) -> Result<()> {
+        Factory::register(ident, factory)
+    }
+
+    /// Reload the root template from `path` whenever the file changes,
+    /// while keeping the state of the views.
+    ///
+    /// Views created with [`Templates::add_prototype`] are recreated, and lose their state.
+    /// Only the root template is reloaded, but it's also reloaded when a file it includes
+    /// (see [`Templates::included_files`]) changes.
+    ///
+    /// If the template fails to compile the previous template is kept
+    /// and the error is displayed on the bottom line of the screen until the
+    /// template compiles again.
+    ///
+    /// Note: this is meant for development, and the templates are only reloaded
+    /// by [`Runtime::run`].
+    ///
+    /// ```
+    /// # use anathema_runtime::Runtime;
+    /// # use anathema_vm::Templates;
+    /// # fn run() {
+    /// let path = "templates/index.tiny";
+    /// let template = std::fs::read_to_string(path).unwrap();
+    /// let mut templates = Templates::new(template, ());
+    /// let compiled = templates.compile().unwrap();
+    ///
+    /// let mut runtime = Runtime::new(&compiled).unwrap();
+    /// runtime.enable_hot_reload(templates, path);
+    /// runtime.run().unwrap();
+    /// # }
+    /// ```
+    pub fn enable_hot_reload(&mut self, templates: Templates, path: impl Into<PathBuf>) {
+        self.hot_reload = Some(HotReload::new(templates, path.into()));
+    }
+
+    // Returns the new templates if the root template changed
+    fn poll_reload(&mut self) -> Option<CompiledTemplates> {
+        let hot_reload = self.hot_reload.as_mut()?;
+
+        let previous_error = hot_reload.error.clone();
+        let templates = hot_reload.poll();
+
+        if previous_error != hot_reload.error {
+            self.needs_layout = true;
+            self.damage.full();
+        }
+
+        templates
+    }
+
+    // Replace the nodes with nodes borrowing different expressions,
+    // returning the runtime with the new nodes along with the old nodes.
+    fn replace_nodes<'a>(self, nodes: Nodes<'a>) -> (Runtime<'a>, Nodes<'e>) {
+        let Self {
+            enable_meta,
+            enable_mouse,
+            enable_ctrlc,
+            enable_tabindex,
+            enable_focus,
+            enable_alt_screen,
+            enable_raw_mode,
+            leave_output_on_exit,
+            tick_rate,
+            debug_overlay,
+            debug_overlay_key,
+            enable_damage_regions,
+            resize_debounce,
+            screen,
+            output,
+            constraints,
+            nodes: old_nodes,
+            events,
+            emitter,
+            injected,
+            needs_layout,
+            meta,
+            tabindex,
+            focus,
+            overlay,
+            damage,
+            hot_reload,
+            pending_resize,
+            clipboard,
+            functions,
+            started,
+            terminal,
+        } = self;
+
+        let runtime = Runtime {
+            enable_meta,
+            enable_mouse,
+            enable_ctrlc,
+            enable_tabindex,
+            enable_focus,
+            enable_alt_screen,
+            enable_raw_mode,
+            leave_output_on_exit,
+            tick_rate,
+            debug_overlay,
+            debug_overlay_key,
+            enable_damage_regions,
+            resize_debounce,
+            screen,
+            output,
+            constraints,
+            nodes,
+            events,
+            emitter,
+            injected,
+            needs_layout,
+            meta,
+            tabindex,
+            focus,
+            overlay,
+            damage,
+            hot_reload,
+            pending_resize,
+            clipboard,
+            functions,
+            started,
+            terminal,
         };
 
-        Ok(inst)
+        (runtime, old_nodes)
     }
 
-    fn layout(&mut self) -> Result<()> {
-        self.nodes.reset_cache();
-        let context = Context::root(&self.meta);
+    /// Get an [`Emitter`] to send synthetic events to the runtime.
+    pub fn emitter(&self) -> Emitter {
+        self.emitter.clone()
+    }
 
-        let mut nodes = LayoutNodes::new(&mut self.nodes, self.constraints, &context);
+    /// Inject a synthetic event, as if it came from the terminal.
+    /// The event is handled at the start of the first frame once the runtime is running.
+    pub fn inject_event(&self, event: Event) {
+        // The runtime holds the receiving end, so this can't fail
+        let _ = self.emitter.emit(event);
+    }
 
-        nodes.for_each(|mut node| {
-            node.layout(self.constraints)?;
-            Ok(())
-        })?;
+    // Synthetic events are handled before any terminal events.
+    fn next_event(&self, timeout: Duration) -> Option<Event> {
+        self.injected
+            .try_recv()
+            .ok()
+            .or_else(|| self.events.poll(timeout))
+    }
 
-        Ok(())
+    fn layout(&mut self) -> Result<()> {
+        self.nodes.reset_cache();
+        let context = Context::root(&self.meta);
+        layout_root(&mut self.nodes, self.constraints, &context)
     }
 
     fn position(&mut self) {
@@ -137,12 +471,37 @@ impl<'e> Runtime<'e> {
     }
 
     fn paint(&mut self) {
+        let clip = self.damage.take(&mut self.nodes);
+        let clip = clip.and_then(|region| screen_region(&region).map(|erase| (region, erase)));
+
+        match clip {
+            Some((region, (pos, size))) => {
+                self.screen.erase_region(pos, size);
+                self.paint_region(Some(&region));
+            }
+            None => {
+                self.screen.erase();
+                self.paint_region(None);
+            }
+        }
+
+        if !self.enable_damage_regions {
+            self.damage.full();
+        }
+    }
+
+    fn paint_region(&mut self, clip: Option<&Region>) {
         for (widget, children) in self.nodes.iter_mut() {
-            widget.paint(children, PaintCtx::new(&mut self.screen, None));
+            widget.paint(children, PaintCtx::new(&mut self.screen, clip));
         }
     }
 
     fn changes(&mut self) {
+        // Receive the values sent to any channel collections since the last frame
+        Views::for_each(|node_id, _| {
+            self.nodes.with_view(node_id, |view| view.poll_state());
+        });
+
         let dirty_nodes = drain_dirty_nodes();
         if dirty_nodes.is_empty() {
             return;
@@ -150,6 +509,13 @@ impl<'e> Runtime<'e> {
 
         self.needs_layout = true;
 
+        if self.enable_damage_regions {
+            for (node_id, _) in &dirty_nodes {
+                let region = self.nodes.region(node_id);
+                self.damage.add(node_id.clone(), region);
+            }
+        }
+
         let state = &self.meta;
         let context = Context::root(state);
 
@@ -158,10 +524,54 @@ impl<'e> Runtime<'e> {
         }
     }
 
-    fn tick_views(&mut self) {
+    fn tick_views(&mut self, delta: Duration) {
+        Views::for_each(|node_id, _| {
+            self.nodes.with_view(node_id, |view| {
+                view.animate(delta);
+                view.tick();
+            });
+        });
+    }
+
+    // The size of the screen, given the size of the terminal.
+    // When rendering in place the screen starts at the origin,
+    // which is moved up if the terminal is too small to fit it.
+    fn screen_size(&mut self, size: Size) -> Size {
+        if self.enable_alt_screen {
+            return size;
+        }
+
+        let origin = self
+            .screen
+            .origin()
+            .min(size.height.saturating_sub(1) as u16);
+        self.screen.set_origin(origin);
+        self.terminal.set_inline(origin);
+        Size::new(size.width, size.height - origin as usize)
+    }
+
+    fn resize(&mut self, size: Size) -> Result<()> {
+        self.screen.erase();
+        self.screen.render(&mut self.output)?;
+        let size = self.screen_size(size);
+        self.screen.resize(size);
+        self.screen.clear_all(&mut self.output)?;
+
+        self.constraints.max_width = size.width;
+        self.constraints.max_height = size.height;
+
+        *self.meta._size.width = size.width;
+        *self.meta._size.height = size.height;
+
+        self.damage.full();
+
         Views::for_each(|node_id, _| {
-            self.nodes.with_view(node_id, |view| view.tick());
+            self.nodes.with_view(node_id, |view| view.resize(size));
         });
+
+        self.needs_layout = true;
+
+        Ok(())
     }
 
     fn global_event(&mut self, event: Event) -> Event {
@@ -179,7 +589,17 @@ impl<'e> Runtime<'e> {
         // -----------------------------------------------------------------------------
         //   - Handle tabbing between widgets -
         // -----------------------------------------------------------------------------
-        if self.enable_tabindex {
+        if self.enable_focus {
+            if let Event::KeyPress(code @ (KeyCode::Tab | KeyCode::BackTab), ..) = event {
+                match code {
+                    KeyCode::Tab => self.focus.focus_next(&mut self.nodes),
+                    _ => self.focus.focus_prev(&mut self.nodes),
+                }
+
+                self.needs_layout = true;
+                self.damage.full();
+            }
+        } else if self.enable_tabindex {
             if let Event::KeyPress(code @ (KeyCode::Tab | KeyCode::BackTab), ..) = event {
                 let dir = match code {
                     KeyCode::Tab => Direction::Forwards,
@@ -197,113 +617,607 @@ impl<'e> Runtime<'e> {
             }
         }
 
+        // -----------------------------------------------------------------------------
+        //   - Toggle the debug overlay -
+        // -----------------------------------------------------------------------------
+        if self.debug_overlay {
+            if let Event::KeyPress(code, ..) = event {
+                if code == self.debug_overlay_key {
+                    self.overlay.toggle();
+                    self.needs_layout = true;
+                    self.damage.full();
+                }
+            }
+        }
+
         event
     }
 
-    /// Consumes the runtime and loops until
-    /// either the runtime receives an error or the `Quit` event is triggered.
-    pub fn run(mut self) -> Result<()> {
-        if self.enable_alt_screen {
-            self.screen.enter_alt_screen(&mut self.output)?;
+    // Handle a single event.
+    // Returns true if the runtime should quit.
+    fn handle_event(&mut self, event: Event) -> bool {
+        let event = self.global_event(event);
+
+        match event {
+            // Resize events are coalesced and applied once all
+            // the events for this frame are processed.
+            Event::Resize(width, height) => {
+                let size = Size::from((width, height));
+                self.pending_resize.push(size, Instant::now());
+            }
+            Event::Blur => *self.meta._focus = false,
+            Event::Focus => *self.meta._focus = true,
+            Event::Quit => return true,
+            _ => {}
         }
 
-        enable_raw_mode()?;
-        Screen::hide_cursor(&mut self.output)?;
+        // The focused widget gets the first go at key events
+        let handled = self.enable_focus
+            && matches!(
+                event,
+                Event::KeyPress(..) | Event::KeyRelease(..) | Event::KeyRepeat(..)
+            )
+            && self.focus.on_event(&mut self.nodes, event);
 
-        self.layout()?;
+        let clipboard = self
+            .clipboard
+            .as_deref_mut()
+            .map(|clipboard| clipboard as _);
+        let mut ctx = RuntimeContext::new(clipboard);
 
-        if self.enable_mouse {
-            Screen::enable_mouse(&mut self.output)?;
+        if handled {
+            // The widget draws itself from its own state, which might have changed
+            self.needs_layout = true;
+            self.damage.full();
+        } else if self.enable_tabindex {
+            if let Some(view_id) = self.tabindex.current_node() {
+                self.nodes
+                    .with_view(view_id, |view| view.on_event(event, &mut ctx));
+            }
+        } else {
+            // TODO: this is a bit sketchy
+            let root = 0.into(); // TODO: this should be a `const`
+            self.nodes
+                .with_view(&root, |view| view.on_event(event, &mut ctx));
         }
 
-        if self.enable_tabindex {
+        ctx.should_quit()
+    }
+
+    // Apply any changes, and draw the frame if anything changed
+    fn draw(&mut self) -> Result<()> {
+        if let Some(size) = self
+            .pending_resize
+            .take(self.resize_debounce, Instant::now())
+        {
+            self.resize(size)?;
+        }
+
+        self.changes();
+
+        *self.meta._count = self.nodes.count();
+
+        if self.overlay.visible {
+            self.needs_layout = true;
+            self.damage.full();
+        }
+
+        // TODO: the meta info should only be updated if `self.enable_meta`
+        if self.needs_layout {
+            let meta_total = Instant::now();
+
+            self.layout()?;
+            self.overlay.layout_time = meta_total.elapsed();
+            *self.meta._timings.layout = format!("{:?}", self.overlay.layout_time);
+
+            let now = Instant::now();
+            self.position();
+            *self.meta._timings.position = format!("{:?}", now.elapsed());
+
+            let now = Instant::now();
+            self.paint();
+            self.overlay.paint_time = now.elapsed();
+            *self.meta._timings.paint = format!("{:?}", self.overlay.paint_time);
+
+            // Errors from the functions called while updating and laying out the widgets
+            self.overlay.push_errors(drain_resolve_errors());
+
+            self.overlay.widget_count = *self.meta._count;
+            self.overlay.frame();
+            self.overlay.paint(&mut self.screen);
+            if let Some(hot_reload) = &self.hot_reload {
+                hot_reload.paint(&mut self.screen);
+            }
+
+            let now = Instant::now();
+            self.screen.render(&mut self.output)?;
+            *self.meta._timings.render = format!("{:?}", now.elapsed());
+            *self.meta._timings.total = format!("{:?}", meta_total.elapsed());
+
+            self.needs_layout = false;
+        }
+
+        Ok(())
+    }
+
+    fn start_focus(&mut self) {
+        if self.enable_focus {
+            self.focus.focus_next(&mut self.nodes);
+        } else if self.enable_tabindex {
             self.tabindex.next(Direction::Forwards);
             if let Some(next) = self.tabindex.current_node() {
                 self.nodes.with_view(next, |view| view.focus());
             }
         }
+    }
+
+    /// Draw a single frame without waiting for events or a tick.
+    /// This is meant for a runtime without a terminal (see [`Runtime::headless`]).
+    ///
+    /// Injected events (see [`Runtime::inject_event`]) are handled first, then the
+    /// frame is drawn, and finally the views are ticked, using the [`tick_rate`](Self::tick_rate)
+    /// as the time since the last frame.
+    pub fn frame(&mut self) -> Result<()> {
+        let _functions = self.functions.enter();
+
+        if !self.started {
+            self.layout()?;
+            self.start_focus();
+            self.overlay.visible = self.debug_overlay;
+            self.started = true;
+        }
+
+        while let Ok(event) = self.injected.try_recv() {
+            // There is no loop to quit
+            let _ = self.handle_event(event);
+        }
+
+        self.draw()?;
+        self.tick_views(self.tick_rate);
+        Ok(())
+    }
+
+    /// Consumes the runtime and loops until
+    /// either the runtime receives an error or the `Quit` event is triggered.
+    pub fn run(mut self) -> Result<()> {
+        let _functions = self.functions.enter();
+
+        if self.enable_raw_mode {
+            self.terminal.enable_raw_mode()?;
+        }
+
+        match self.enable_alt_screen {
+            true => self.terminal.enter_alt_screen()?,
+            false => {
+                let (_, row) = cursor::position()?;
+                self.screen.set_origin(row);
+                self.resize(self.screen.size())?;
+            }
+        }
+
+        self.terminal.hide_cursor()?;
+
+        self.layout()?;
+
+        if self.enable_mouse {
+            self.terminal.enable_mouse()?;
+        }
+
+        self.start_focus();
 
         self.screen.clear_all(&mut self.output)?;
+        self.overlay.visible = self.debug_overlay;
+
+        // The nodes borrow the expressions, so the reloaded templates are declared
+        // before the runtime, and replaced (freeing the previous templates) once the
+        // nodes borrowing them are gone.
+        let mut reloaded: Option<CompiledTemplates> = None;
+        let mut runtime = self;
+
+        let mut last_tick = Instant::now();
+        // The time between two frames, passed to the views to animate
+        let mut last_frame = Instant::now();
+
+        let result = 'run: loop {
+            if let Some(templates) = runtime.poll_reload() {
+                let (empty, nodes) = runtime.replace_nodes(make_it_so(&[]));
+                // Return the views (and their state) so they are used by the new nodes
+                nodes.recycle_views();
+                let templates = reloaded.insert(templates);
+                runtime = empty.replace_nodes(make_it_so(templates.expressions())).0;
+
+                let _ = drain_dirty_nodes();
+                runtime.needs_layout = true;
+                runtime.damage.full();
+            }
+
+            // Wait for events until the next tick
+            loop {
+                let timeout = runtime.tick_rate.saturating_sub(last_tick.elapsed());
+                let Some(event) = runtime.next_event(timeout) else {
+                    break;
+                };
 
-        let mut fps_now = Instant::now();
-        let sleep_micros = ((1.0 / self.fps as f64) * 1000.0 * 1000.0) as u128;
+                if runtime.handle_event(event) {
+                    break 'run Ok(());
+                }
 
-        'run: loop {
-            while let Some(event) = self.events.poll(Duration::from_millis(1)) {
-                let event = self.global_event(event);
+                // Handle the events that are already available without waiting,
+                // otherwise only one event is handled per tick if the tick rate is zero.
+                while let Some(event) = runtime.next_event(Duration::ZERO) {
+                    if runtime.handle_event(event) {
+                        break 'run Ok(());
+                    }
+                }
 
                 // Make sure event handling isn't holding up the rest of the event loop.
-                if fps_now.elapsed().as_micros() > sleep_micros {
+                if last_tick.elapsed() >= runtime.tick_rate {
                     break;
                 }
+            }
+
+            runtime.draw()?;
+
+            let now = Instant::now();
+            runtime.tick_views(now.duration_since(last_frame));
+            last_frame = now;
+
+            // Polling for events can return early (e.g if there is no terminal),
+            // so sleep for whatever is left of the tick.
+            let sleep = runtime.tick_rate.saturating_sub(last_tick.elapsed());
+            if !sleep.is_zero() {
+                std::thread::sleep(sleep);
+            }
+
+            last_tick = Instant::now();
+        };
+
+        if result.is_ok() && runtime.leave_output_on_exit {
+            runtime.leave_output()?;
+        }
+
+        result
+    }
+
+    // Write the last frame to the main screen.
+    fn leave_output(&mut self) -> Result<()> {
+        match self.enable_alt_screen {
+            true => self.terminal.leave_alt_screen()?,
+            // The frame is already on the main screen, and is drawn
+            // from the origin, so draw it again from there.
+            false => {
+                self.output.queue(MoveTo(0, self.screen.origin()))?;
+                self.terminal.keep_inline_output();
+            }
+        }
+
+        self.screen.write_frame(&mut self.output)?;
+        Ok(())
+    }
+}
+
+// Lay out the root nodes.
+// If there isn't enough space for a node (e.g the terminal is too small),
+// the node is limited to the constraints and clipped when painted,
+// rather than stopping the runtime.
+fn layout_root<'e>(
+    nodes: &mut Nodes<'e>,
+    constraints: Constraints,
+    context: &Context<'_, 'e>,
+) -> Result<()> {
+    let mut nodes = LayoutNodes::new(nodes, constraints, context);
+
+    nodes.for_each(|mut node| match node.layout(constraints) {
+        Ok(_) => Ok(()),
+        Err(Error::InsufficientSpaceAvailble) => {
+            node.size = constraints.clamp_size(node.size);
+            Ok(())
+        }
+        Err(e) => Err(e),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc;
+
+    use anathema_values::{ChannelCollection, Map, State, StateValue};
+    use anathema_widget_core::clipboard::paste;
+    use anathema_widget_core::{KeyEventState, KeyModifiers, View};
+
+    use super::*;
+
+    #[derive(State)]
+    struct Counter {
+        count: StateValue<usize>,
+    }
+
+    struct CounterView(Counter);
+
+    impl View for CounterView {
+        fn on_event(&mut self, event: Event, _: &mut Nodes<'_>) {
+            if let Event::KeyPress(KeyCode::Char('+'), ..) = event {
+                *self.0.count += 1;
+            }
+        }
+
+        fn state(&self) -> &dyn State {
+            &self.0
+        }
+    }
 
-                match event {
-                    Event::Resize(width, height) => {
-                        let size = Size::from((width, height));
-                        self.screen.erase();
-                        self.screen.render(&mut self.output)?;
-                        self.screen.resize(size);
-                        self.screen.clear_all(&mut self.output)?;
+    #[test]
+    fn headless_frame() {
+        let view = CounterView(Counter {
+            count: StateValue::new(0),
+        });
+        let mut templates = Templates::new("border\n    text 'count: ' count".into(), view);
+        let templates = templates.compile().unwrap();
+        let mut runtime = Runtime::headless(&templates, Size::new(20, 5));
+
+        runtime.frame().unwrap();
+        let expected = "┌────────┐\n│count: 0│\n└────────┘";
+        assert_eq!(runtime.screen().buffer().to_string(), expected);
+
+        let plus = Event::KeyPress(
+            KeyCode::Char('+'),
+            KeyModifiers::empty(),
+            KeyEventState::empty(),
+        );
+        runtime.inject_event(plus);
+        runtime.frame().unwrap();
+        let expected = "┌────────┐\n│count: 1│\n└────────┘";
+        assert_eq!(runtime.screen().buffer().to_string(), expected);
+        assert!(runtime.screen().to_ansi_string().contains("count: 1"));
+    }
 
-                        self.constraints.max_width = size.width;
-                        self.constraints.max_height = size.height;
+    #[derive(Default)]
+    struct TestClipboard(Option<String>);
 
-                        *self.meta._size.width = size.width;
-                        *self.meta._size.height = size.height;
+    impl Clipboard for TestClipboard {
+        fn get_text(&mut self) -> Option<String> {
+            self.0.clone()
+        }
+
+        fn set_text(&mut self, text: &str) -> bool {
+            self.0 = Some(text.to_string());
+            true
+        }
+    }
+
+    #[derive(State)]
+    struct Input {
+        text: StateValue<String>,
+    }
+
+    struct InputView(Input);
+
+    impl View for InputView {
+        fn on_event_with_context(
+            &mut self,
+            event: Event,
+            _: &mut Nodes<'_>,
+            ctx: &mut RuntimeContext<'_>,
+        ) {
+            match event {
+                Event::CtrlC => {
+                    if let Some(clipboard) = ctx.clipboard() {
+                        clipboard.set_text(&self.0.text);
                     }
-                    Event::Blur => *self.meta._focus = false,
-                    Event::Focus => *self.meta._focus = true,
-                    Event::Quit => break 'run Ok(()),
-                    _ => {}
                 }
-
-                if self.enable_tabindex {
-                    if let Some(view_id) = self.tabindex.current_node() {
-                        self.nodes.with_view(view_id, |view| view.on_event(event));
+                Event::KeyPress(KeyCode::Char('v'), KeyModifiers::CONTROL, _) => {
+                    if let Some(text) = ctx.clipboard().and_then(|c| c.get_text()) {
+                        let cursor = self.0.text.chars().count();
+                        paste(&mut self.0.text, cursor, &text, Some(5));
                     }
-                } else {
-                    // TODO: this is a bit sketchy
-                    let root = 0.into(); // TODO: this should be a `const`
-                    self.nodes.with_view(&root, |view| view.on_event(event));
                 }
+                Event::KeyPress(KeyCode::Char(c), ..) => self.0.text.push(c),
+                Event::KeyPress(KeyCode::Esc, ..) => ctx.quit(),
+                _ => {}
             }
+        }
+
+        fn state(&self) -> &dyn State {
+            &self.0
+        }
+    }
 
-            self.changes();
+    #[test]
+    fn copy_and_paste() {
+        let view = InputView(Input {
+            text: StateValue::new(String::new()),
+        });
+        let mut templates = Templates::new("text text".into(), view);
+        let templates = templates.compile().unwrap();
+        let mut runtime = Runtime::headless(&templates, Size::new(10, 1));
+        runtime.enable_ctrlc = false;
+        runtime.frame().unwrap();
 
-            *self.meta._count = self.nodes.count();
+        let key =
+            |c, modifiers| Event::KeyPress(KeyCode::Char(c), modifiers, KeyEventState::empty());
+        let paste = key('v', KeyModifiers::CONTROL);
 
-            // TODO: the meta info should only be updated if `self.enable_meta`
-            if self.needs_layout {
-                let meta_total = Instant::now();
+        // There is no clipboard
+        runtime.inject_event(key('a', KeyModifiers::empty()));
+        runtime.inject_event(Event::CtrlC);
+        runtime.inject_event(paste);
+        runtime.frame().unwrap();
+        assert_eq!(runtime.screen().buffer().to_string(), "a");
 
-                self.layout()?;
-                *self.meta._timings.layout = format!("{:?}", meta_total.elapsed());
+        runtime.set_clipboard(TestClipboard::default());
+        runtime.inject_event(key('b', KeyModifiers::empty()));
+        runtime.inject_event(Event::CtrlC);
+        runtime.inject_event(paste);
+        runtime.inject_event(paste);
+        runtime.frame().unwrap();
+        // The second paste is limited by the max length
+        assert_eq!(runtime.screen().buffer().to_string(), "ababa");
 
-                let now = Instant::now();
-                self.position();
-                *self.meta._timings.position = format!("{:?}", now.elapsed());
+        let esc = Event::KeyPress(KeyCode::Esc, KeyModifiers::empty(), KeyEventState::empty());
+        assert!(runtime.handle_event(esc));
+    }
 
-                let now = Instant::now();
-                self.paint();
-                *self.meta._timings.paint = format!("{:?}", now.elapsed());
+    #[derive(State)]
+    struct Items {
+        items: Map<usize>,
+    }
 
-                let now = Instant::now();
-                self.screen.render(&mut self.output)?;
-                *self.meta._timings.render = format!("{:?}", now.elapsed());
-                *self.meta._timings.total = format!("{:?}", meta_total.elapsed());
-                self.screen.erase();
+    struct ItemsView(Items);
 
-                self.needs_layout = false;
+    impl View for ItemsView {
+        fn on_event(&mut self, event: Event, _: &mut Nodes<'_>) {
+            if let Event::KeyPress(KeyCode::Char('+'), ..) = event {
+                self.0.items.insert("d".into(), 4);
             }
+        }
 
-            self.tick_views();
+        fn state(&self) -> &dyn State {
+            &self.0
+        }
+    }
 
-            let sleep = sleep_micros.saturating_sub(fps_now.elapsed().as_micros()) as u64;
-            if sleep > 0 {
-                std::thread::sleep(Duration::from_micros(sleep));
-            }
+    #[test]
+    fn insert_into_loop_over_map() {
+        let view = ItemsView(Items {
+            items: Map::new([("c", 3), ("a", 1), ("b", 2)]),
+        });
+        let template = "vstack\n    for item in items\n        text item";
+        let mut templates = Templates::new(template.into(), view);
+        let templates = templates.compile().unwrap();
+        let mut runtime = Runtime::headless(&templates, Size::new(5, 5));
+
+        runtime.frame().unwrap();
+        assert_eq!(runtime.screen().buffer().to_string(), "3\n1\n2");
+
+        let plus = Event::KeyPress(
+            KeyCode::Char('+'),
+            KeyModifiers::empty(),
+            KeyEventState::empty(),
+        );
+        runtime.inject_event(plus);
+        runtime.frame().unwrap();
+        assert_eq!(runtime.screen().buffer().to_string(), "3\n1\n2\n4");
+    }
+
+    #[derive(State)]
+    struct Lines {
+        lines: ChannelCollection<usize>,
+    }
+
+    struct LinesView(Lines);
 
-            fps_now = Instant::now();
+    impl View for LinesView {
+        fn state(&self) -> &dyn State {
+            &self.0
         }
+
+        fn state_mut(&mut self) -> Option<&mut dyn State> {
+            Some(&mut self.0)
+        }
+    }
+
+    #[test]
+    fn poll_channel_collections() {
+        let (tx, rx) = mpsc::channel();
+        let view = LinesView(Lines {
+            lines: ChannelCollection::new(rx, 2),
+        });
+        let template = "vstack\n    for line in lines\n        text line";
+        let mut templates = Templates::new(template.into(), view);
+        let templates = templates.compile().unwrap();
+        let mut runtime = Runtime::headless(&templates, Size::new(5, 5));
+
+        tx.send(1).unwrap();
+        runtime.frame().unwrap();
+        assert_eq!(runtime.screen().buffer().to_string(), "1");
+
+        // The oldest value is evicted
+        tx.send(2).unwrap();
+        tx.send(3).unwrap();
+        runtime.frame().unwrap();
+        assert_eq!(runtime.screen().buffer().to_string(), "2\n3");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn fps_sets_tick_rate() {
+        let mut templates = Templates::new("text 'hello'".into(), ());
+        let templates = templates.compile().unwrap();
+        let mut runtime = Runtime::headless(&templates, Size::new(20, 5));
+
+        runtime.fps(50);
+        assert_eq!(runtime.tick_rate, Duration::from_millis(20));
+        runtime.fps(0);
+        assert_eq!(runtime.tick_rate, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn function_errors() {
+        let mut templates = Templates::new("text missing(1)".into(), ());
+        let templates = templates.compile().unwrap();
+        let mut runtime = Runtime::headless(&templates, Size::new(20, 5));
+        runtime.frame().unwrap();
+
+        let errors = runtime.resolve_errors().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "`missing`: function not found");
+    }
+
+    #[test]
+    fn functions_per_templates() {
+        let mut templates = Templates::new("text greet()".into(), ());
+        templates.register_fn("greet", |_: &[ValueRef<'_>]| Ok("hello".into()));
+        let templates = templates.compile().unwrap();
+        let mut runtime = Runtime::headless(&templates, Size::new(10, 1));
+        runtime.frame().unwrap();
+        assert_eq!(runtime.screen().buffer().to_string(), "hello");
+        drop(runtime);
+
+        // Functions registered with other templates aren't available
+        let mut templates = Templates::new("text greet()".into(), ());
+        let templates = templates.compile().unwrap();
+        let mut runtime = Runtime::headless(&templates, Size::new(10, 1));
+        runtime.frame().unwrap();
+        assert_eq!(runtime.screen().buffer().to_string(), "");
+        drop(runtime);
+
+        let mut templates = Templates::new("text greet()".into(), ());
+        let templates = templates.compile().unwrap();
+        let mut runtime = Runtime::headless(&templates, Size::new(10, 1));
+        runtime.register_fn("greet", |_: &[ValueRef<'_>]| Ok("hi".into()));
+        runtime.frame().unwrap();
+        assert_eq!(runtime.screen().buffer().to_string(), "hi");
+    }
+
+    #[test]
+    fn layout_smaller_than_min_size() {
+        // The widgets are registered once for all the tests
+        let _ = register_default_widgets();
+        let mut templates = Templates::new("border\n    text 'hello'".into(), ());
+        let templates = templates.compile().unwrap();
+        let mut nodes = make_it_so(templates.expressions());
+        let context = Context::root(&());
+
+        layout_root(&mut nodes, Constraints::new(1, 1), &context).unwrap();
+        let (widget, _) = nodes.first_mut().unwrap();
+        assert!(widget.size.width <= 1 && widget.size.height <= 1);
+
+        // Growing the terminal again
+        nodes.reset_cache();
+        layout_root(&mut nodes, Constraints::new(20, 5), &context).unwrap();
+        let (widget, _) = nodes.first_mut().unwrap();
+        assert_eq!(widget.size, Size::new(7, 3));
+    }
+
+    #[test]
+    fn padding_template() {
+        let template = "border\n    padding [padding: 1, left: 2]\n        text 'hi'";
+        let mut templates = Templates::new(template.into(), ());
+        let templates = templates.compile().unwrap();
+        let mut runtime = Runtime::headless(&templates, Size::new(10, 5));
+        runtime.frame().unwrap();
+
+        let expected = "┌─────┐\n│     │\n│  hi │\n│     │\n└─────┘";
+        assert_eq!(runtime.screen().buffer().to_string(), expected);
     }
 }
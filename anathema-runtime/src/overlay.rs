@@ -0,0 +1,161 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use anathema_render::{Screen, ScreenPos, Style};
+use anathema_values::ResolveError;
+
+// The number of function errors kept by the overlay
+const MAX_ERRORS: usize = 5;
+
+// -----------------------------------------------------------------------------
+//   - Debug overlay -
+//   Draws frame timings, and the most recent errors from template functions,
+//   in the top right corner of the screen, on top of everything else.
+// -----------------------------------------------------------------------------
+pub(super) struct DebugOverlay {
+    pub(super) visible: bool,
+    pub(super) layout_time: Duration,
+    pub(super) paint_time: Duration,
+    pub(super) widget_count: usize,
+    errors: VecDeque<ResolveError>,
+    frames: usize,
+    fps: usize,
+    last_sample: Instant,
+}
+
+impl DebugOverlay {
+    pub(super) fn new(visible: bool) -> Self {
+        Self {
+            visible,
+            layout_time: Duration::ZERO,
+            paint_time: Duration::ZERO,
+            widget_count: 0,
+            errors: VecDeque::new(),
+            frames: 0,
+            fps: 0,
+            last_sample: Instant::now(),
+        }
+    }
+
+    pub(super) fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    // Register a rendered frame. The fps is sampled once per second.
+    pub(super) fn frame(&mut self) {
+        self.frames += 1;
+        let elapsed = self.last_sample.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            self.fps = (self.frames as f64 / elapsed.as_secs_f64()).round() as usize;
+            self.frames = 0;
+            self.last_sample = Instant::now();
+        }
+    }
+
+    // Keep the most recent errors, dropping the oldest ones
+    pub(super) fn push_errors(&mut self, errors: Vec<ResolveError>) {
+        self.errors.extend(errors);
+        let excess = self.errors.len().saturating_sub(MAX_ERRORS);
+        self.errors.drain(..excess);
+    }
+
+    pub(super) fn errors(&self) -> impl Iterator<Item = &ResolveError> {
+        self.errors.iter()
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("fps: {}", self.fps),
+            format!("layout: {:?}", self.layout_time),
+            format!("paint: {:?}", self.paint_time),
+            format!("widgets: {}", self.widget_count),
+        ];
+        lines.extend(self.errors.iter().map(|err| format!("error: {err}")));
+        lines
+    }
+
+    pub(super) fn paint(&self, screen: &mut Screen) {
+        if !self.visible {
+            return;
+        }
+
+        let size = screen.size();
+        // Errors can be longer than the screen is wide
+        let lines = self
+            .lines()
+            .into_iter()
+            .map(|line| line.chars().take(size.width).collect::<String>())
+            .collect::<Vec<_>>();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+
+        let mut style = Style::reset();
+        style.set_inverse(true);
+
+        let x = (size.width - width) as u16;
+        for (y, line) in lines.iter().enumerate().take(size.height) {
+            for (i, c) in format!("{line:>width$}").chars().enumerate() {
+                screen.put(c, style, ScreenPos::new(x + i as u16, y as u16));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::Size;
+    use anathema_values::FunctionError;
+
+    use super::*;
+
+    #[test]
+    fn paint_top_right() {
+        let mut screen = Screen::new(Size::new(20, 5));
+        let mut overlay = DebugOverlay::new(true);
+        overlay.widget_count = 3;
+        overlay.paint(&mut screen);
+
+        let row = |y: u16| {
+            (0..20)
+                .filter_map(|x| screen.get(ScreenPos::new(x, y)).map(|(c, _)| c))
+                .collect::<String>()
+        };
+
+        assert!(row(0).ends_with("fps: 0"));
+        assert_eq!(row(3).trim(), "widgets: 3");
+        assert!(screen.get(ScreenPos::new(19, 0)).is_some());
+        assert!(screen.get(ScreenPos::new(0, 0)).is_none());
+    }
+
+    #[test]
+    fn hidden_overlay() {
+        let mut screen = Screen::new(Size::new(20, 5));
+        let overlay = DebugOverlay::new(false);
+        overlay.paint(&mut screen);
+        assert!(screen.get(ScreenPos::new(19, 0)).is_none());
+    }
+
+    #[test]
+    fn most_recent_errors() {
+        let error = |function: &str| ResolveError {
+            function: function.into(),
+            error: FunctionError::NotFound,
+        };
+
+        let mut overlay = DebugOverlay::new(true);
+        overlay.push_errors((0..MAX_ERRORS).map(|i| error(&i.to_string())).collect());
+        overlay.push_errors(vec![error("last")]);
+
+        let errors = overlay
+            .errors()
+            .map(|e| e.function.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(errors, ["1", "2", "3", "4", "last"]);
+
+        let mut screen = Screen::new(Size::new(20, 10));
+        overlay.paint(&mut screen);
+        let row = (0..20)
+            .filter_map(|x| screen.get(ScreenPos::new(x, 8)).map(|(c, _)| c))
+            .collect::<String>();
+        assert_eq!(row, "error: `last`: funct");
+    }
+}
@@ -0,0 +1,193 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use anathema_render::{Screen, ScreenPos, Style};
+use anathema_vm::{CompiledTemplates, Templates};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// -----------------------------------------------------------------------------
+//   - Hot reload -
+//   Watch the root template, and the files it includes, for changes
+//   and recompile it.
+//
+//   The file is polled for changes (by looking at the modified time), rather
+//   than using a file system watcher, so it works the same on every platform.
+// -----------------------------------------------------------------------------
+pub(super) struct HotReload {
+    templates: Templates,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    // The included files and when they were modified
+    includes: Vec<(PathBuf, Option<SystemTime>)>,
+    last_poll: Instant,
+    pub(super) error: Option<String>,
+}
+
+impl HotReload {
+    pub(super) fn new(templates: Templates, path: PathBuf) -> Self {
+        let mut reload = Self {
+            modified: modified(&path),
+            templates,
+            path,
+            includes: vec![],
+            last_poll: Instant::now(),
+            error: None,
+        };
+        reload.watch_includes();
+        reload
+    }
+
+    // Watch the files included by the last compile
+    fn watch_includes(&mut self) {
+        self.includes = self
+            .templates
+            .included_files()
+            .iter()
+            .map(|path| (path.clone(), modified(path)))
+            .collect();
+    }
+
+    // Returns true if the root template or any of the included files changed
+    fn changed(&mut self) -> bool {
+        let mut changed = false;
+
+        let modified = modified(&self.path);
+        if modified != self.modified {
+            self.modified = modified;
+            changed = true;
+        }
+
+        for (path, included_modified) in &mut self.includes {
+            let modified = self::modified(path);
+            if modified != *included_modified {
+                *included_modified = modified;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    // Returns the new templates if the root template (or an included file) changed and compiled.
+    // If the template fails to compile the error is stored, and will be displayed
+    // until the template compiles again.
+    pub(super) fn poll(&mut self) -> Option<CompiledTemplates> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+
+        if !self.changed() {
+            return None;
+        }
+
+        let result = fs::read_to_string(&self.path)
+            .map_err(|e| e.to_string())
+            .and_then(|root| {
+                self.templates.set_root(root);
+                self.templates.compile().map_err(|e| e.to_string())
+            });
+
+        // The includes can change with the template
+        self.watch_includes();
+
+        match result {
+            Ok(templates) => {
+                self.error = None;
+                Some(templates)
+            }
+            Err(e) => {
+                self.error = Some(format!("{}: {e}", self.path.display()));
+                None
+            }
+        }
+    }
+
+    // Draw the last error on the bottom line of the screen
+    pub(super) fn paint(&self, screen: &mut Screen) {
+        let Some(error) = &self.error else { return };
+        let size = screen.size();
+        if size.height == 0 {
+            return;
+        }
+
+        let mut style = Style::reset();
+        style.set_inverse(true);
+
+        let y = (size.height - 1) as u16;
+        let line = error.lines().next().unwrap_or_default();
+        for (x, c) in format!("{line:<width$}", width = size.width)
+            .chars()
+            .take(size.width)
+            .enumerate()
+        {
+            screen.put(c, style, ScreenPos::new(x as u16, y));
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keep_last_good_template() {
+        let path =
+            std::env::temp_dir().join(format!("anathema-reload-{}.tiny", std::process::id()));
+        fs::write(&path, "text 'a'").unwrap();
+
+        let templates = Templates::new("text 'a'".into(), ());
+        let mut reload = HotReload::new(templates, path.clone());
+
+        // Nothing changed
+        reload.last_poll -= POLL_INTERVAL;
+        assert!(reload.poll().is_none());
+
+        fs::write(&path, "text [").unwrap();
+        reload.modified = None;
+        reload.last_poll -= POLL_INTERVAL;
+        assert!(reload.poll().is_none());
+        assert!(reload.error.is_some());
+
+        fs::write(&path, "text 'b'").unwrap();
+        reload.modified = None;
+        reload.last_poll -= POLL_INTERVAL;
+        assert!(reload.poll().is_some());
+        assert!(reload.error.is_none());
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn reload_includes() {
+        let dir = std::env::temp_dir().join(format!("anathema-reload-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("root.tiny");
+        let include = dir.join("include.tiny");
+        fs::write(&path, "@include \"include.tiny\"").unwrap();
+        fs::write(&include, "text 'a'").unwrap();
+
+        let mut templates = Templates::new(fs::read_to_string(&path).unwrap(), ());
+        templates.set_include_dir(&dir);
+        templates.compile().unwrap();
+        let mut reload = HotReload::new(templates, path.clone());
+        assert_eq!(reload.includes.len(), 1);
+
+        // Nothing changed
+        reload.last_poll -= POLL_INTERVAL;
+        assert!(reload.poll().is_none());
+
+        fs::write(&include, "text 'b'").unwrap();
+        reload.includes[0].1 = None;
+        reload.last_poll -= POLL_INTERVAL;
+        assert!(reload.poll().is_some());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
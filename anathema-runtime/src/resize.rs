@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+
+use anathema_render::Size;
+
+// -----------------------------------------------------------------------------
+//   - Pending resize -
+//   Resize events are coalesced into the latest size, which is applied
+//   once the size hasn't changed for the debounce duration.
+//   This way a storm of resize events (e.g dragging the edge of a window)
+//   only causes a single layout.
+// -----------------------------------------------------------------------------
+pub(crate) struct PendingResize {
+    size: Option<Size>,
+    changed: Instant,
+}
+
+impl PendingResize {
+    pub(crate) fn new() -> Self {
+        Self {
+            size: None,
+            changed: Instant::now(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, size: Size, now: Instant) {
+        self.size = Some(size);
+        self.changed = now;
+    }
+
+    // The latest size, if the size hasn't changed for the duration of `debounce`
+    pub(crate) fn take(&mut self, debounce: Duration, now: Instant) -> Option<Size> {
+        match now.duration_since(self.changed) >= debounce {
+            true => self.size.take(),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn coalesce_to_latest_size() {
+        let now = Instant::now();
+        let mut pending = PendingResize::new();
+        pending.push(Size::new(10, 10), now);
+        pending.push(Size::new(5, 2), now);
+
+        assert_eq!(pending.take(Duration::ZERO, now), Some(Size::new(5, 2)));
+        assert_eq!(pending.take(Duration::ZERO, now), None);
+    }
+
+    #[test]
+    fn debounce() {
+        let debounce = Duration::from_millis(50);
+        let start = Instant::now();
+        let mut pending = PendingResize::new();
+
+        pending.push(Size::new(10, 10), start);
+        assert_eq!(
+            pending.take(debounce, start + Duration::from_millis(40)),
+            None
+        );
+
+        // A new size restarts the debounce
+        pending.push(Size::new(20, 10), start + Duration::from_millis(40));
+        assert_eq!(
+            pending.take(debounce, start + Duration::from_millis(60)),
+            None
+        );
+
+        let size = pending.take(debounce, start + Duration::from_millis(90));
+        assert_eq!(size, Some(Size::new(20, 10)));
+    }
+}
@@ -28,14 +28,14 @@ pub(super) enum Direction {
 }
 
 impl Direction {
-    fn default(&self, max: usize) -> usize {
+    pub(super) fn default(&self, max: usize) -> usize {
         match self {
             Self::Forwards => 0,
             Self::Backwards => max,
         }
     }
 
-    fn next(&self, old: usize, max: usize) -> usize {
+    pub(super) fn next(&self, old: usize, max: usize) -> usize {
         match self {
             Self::Forwards if old == max => 0,
             Self::Backwards if old == 0 => max,
@@ -0,0 +1,160 @@
+use std::io::Write;
+
+use anathema_render::Screen;
+use crossterm::cursor::MoveTo;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::{ExecutableCommand, QueueableCommand};
+
+// -----------------------------------------------------------------------------
+//   - Terminal guard -
+//   Keeps track of the changes made to the terminal, and undoes them
+//   when dropped.
+//
+//   As the guard is owned by the runtime this also happens if the runtime
+//   panics, so a crash doesn't leave the terminal in raw mode.
+// -----------------------------------------------------------------------------
+pub(crate) struct TerminalGuard<W: Write> {
+    output: W,
+    raw_mode: bool,
+    alt_screen: bool,
+    mouse: bool,
+    cursor_hidden: bool,
+    // The first row of the screen when drawing below the existing output
+    // of the terminal. The rows are cleared on drop.
+    inline: Option<u16>,
+}
+
+impl<W: Write> TerminalGuard<W> {
+    pub(crate) fn new(output: W) -> Self {
+        Self {
+            output,
+            raw_mode: false,
+            alt_screen: false,
+            mouse: false,
+            cursor_hidden: false,
+            inline: None,
+        }
+    }
+
+    pub(crate) fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+        enable_raw_mode()?;
+        self.raw_mode = true;
+        Ok(())
+    }
+
+    pub(crate) fn enter_alt_screen(&mut self) -> std::io::Result<()> {
+        self.output.execute(EnterAlternateScreen)?;
+        self.alt_screen = true;
+        Ok(())
+    }
+
+    pub(crate) fn leave_alt_screen(&mut self) -> std::io::Result<()> {
+        if self.alt_screen {
+            self.output.execute(LeaveAlternateScreen)?;
+            self.alt_screen = false;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn enable_mouse(&mut self) -> std::io::Result<()> {
+        Screen::enable_mouse(&mut self.output)?;
+        self.output.flush()?;
+        self.mouse = true;
+        Ok(())
+    }
+
+    pub(crate) fn hide_cursor(&mut self) -> std::io::Result<()> {
+        Screen::hide_cursor(&mut self.output)?;
+        self.output.flush()?;
+        self.cursor_hidden = true;
+        Ok(())
+    }
+
+    pub(crate) fn set_inline(&mut self, origin: u16) {
+        self.inline = Some(origin);
+    }
+
+    // Leave whatever was drawn inline in the terminal
+    pub(crate) fn keep_inline_output(&mut self) {
+        self.inline = None;
+    }
+
+    fn restore(&mut self) -> std::io::Result<()> {
+        if self.mouse {
+            Screen::disable_mouse(&mut self.output)?;
+        }
+
+        if let Some(origin) = self.inline {
+            self.output.queue(MoveTo(0, origin))?;
+            self.output.queue(Clear(ClearType::FromCursorDown))?;
+        }
+
+        self.leave_alt_screen()?;
+
+        if self.cursor_hidden {
+            Screen::show_cursor(&mut self.output)?;
+        }
+
+        self.output.flush()?;
+
+        if self.raw_mode {
+            disable_raw_mode()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for TerminalGuard<W> {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn restore_on_drop() {
+        let mut output = vec![];
+        {
+            let mut guard = TerminalGuard::new(&mut output);
+            guard.enter_alt_screen().unwrap();
+            guard.hide_cursor().unwrap();
+        }
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, "\x1b[?1049h\x1b[?25l\x1b[?1049l\x1b[?25h");
+    }
+
+    #[test]
+    fn restore_on_panic() {
+        let mut output = vec![];
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut guard = TerminalGuard::new(&mut output);
+            guard.hide_cursor().unwrap();
+            panic!("crash mid-frame");
+        }));
+
+        assert!(result.is_err());
+        assert!(String::from_utf8(output).unwrap().ends_with("\x1b[?25h"));
+    }
+
+    #[test]
+    fn clear_inline_output() {
+        let mut output = vec![];
+        TerminalGuard::new(&mut output).set_inline(4);
+        assert_eq!(String::from_utf8(output).unwrap(), "\x1b[5;1H\x1b[J");
+
+        let mut output = vec![];
+        {
+            let mut guard = TerminalGuard::new(&mut output);
+            guard.set_inline(4);
+            guard.keep_inline_output();
+        }
+        assert!(output.is_empty());
+    }
+}
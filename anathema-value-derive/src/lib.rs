@@ -1,12 +1,34 @@
 use manyhow::{ensure, manyhow, Result};
 use quote_use::quote_use as quote;
-use syn::{self, Fields};
+use syn::{self, Fields, Ident};
 
 #[manyhow]
-#[proc_macro_derive(State)]
+#[proc_macro_derive(State, attributes(state))]
 pub fn state_derive(strct: syn::ItemStruct) -> Result {
     let name = &strct.ident;
 
+    // Computed fields are methods on the state: `#[state(computed(full_name))]`
+    let mut computed = vec![];
+    for attr in strct
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("state"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("computed") {
+                return Err(meta.error("expected `computed`"));
+            }
+            meta.parse_nested_meta(|method| match method.path.get_ident() {
+                Some(ident) => {
+                    computed.push(ident.clone());
+                    Ok(())
+                }
+                None => Err(method.error("expected the name of a method")),
+            })
+        })?;
+    }
+    let computed_names = computed.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
     ensure!(
         let Fields::Named(struct_fields) = &strct.fields,
         strct.fields,
@@ -20,6 +42,8 @@ pub fn state_derive(strct: syn::ItemStruct) -> Result {
         .map(|f| (f, f.to_string()))
         .unzip();
 
+    check_computed(&field_idents, &computed)?;
+
     Ok(quote! {
         # use ::anathema::values::{self, ValueRef, Path, state};
         impl state::State for #name {
@@ -31,6 +55,11 @@ pub fn state_derive(strct: syn::ItemStruct) -> Result {
                                 self.#field_idents.get_value(node_id)
                             }
                         )*
+                        #(
+                            #computed_names => {
+                                self.#computed(node_id).into()
+                            }
+                        )*
                         _ => ValueRef::Empty,
                     }
                     Path::Composite(lhs, rhs) => {
@@ -49,6 +78,16 @@ pub fn state_derive(strct: syn::ItemStruct) -> Result {
                     _ => ValueRef::Empty,
                 }
             }
+
+            fn state_keys(&self) -> Option<Vec<String>> {
+                Some(vec![#(#field_names.to_string(),)* #(#computed_names.to_string()),*])
+            }
+
+            fn state_poll(&mut self) {
+                #(
+                    self.#field_idents.state_poll();
+                )*
+            }
         }
 
         impl<'a> Into<ValueRef<'a>> for &'a #name {
@@ -58,3 +97,35 @@ pub fn state_derive(strct: syn::ItemStruct) -> Result {
         }
     })
 }
+
+// A computed field can't have the same name as a field,
+// as only one of them could be read from the template.
+fn check_computed(fields: &[&Ident], computed: &[Ident]) -> syn::Result<()> {
+    match computed.iter().find(|c| fields.contains(c)) {
+        Some(ident) => Err(syn::Error::new_spanned(
+            ident,
+            format!("computed field `{ident}` has the same name as a field"),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn computed_field_with_the_name_of_a_field() {
+        let name: Ident = parse_quote!(name);
+        let count: Ident = parse_quote!(count);
+        assert!(check_computed(&[&name], &[parse_quote!(count)]).is_ok());
+
+        let err = check_computed(&[&name, &count], &[parse_quote!(name)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "computed field `name` has the same name as a field"
+        );
+    }
+}
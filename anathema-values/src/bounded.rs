@@ -0,0 +1,197 @@
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::ops::Index;
+
+use crate::state::State;
+use crate::{Change, Collection, List, NodeId, Path, StateValue, ValueRef, DIRTY_NODES};
+
+/// A list that holds at most `capacity` values.
+///
+/// Pushing a value onto a full list evicts the oldest value (at the front).
+/// Subscribers are notified of the eviction first ([`Change::RemoveIndex`] with index zero)
+/// followed by the new value ([`Change::Push`]), so anything bound to the list can shift
+/// the indices before appending.
+///
+/// ```
+/// use anathema_values::BoundedList;
+///
+/// let mut list = BoundedList::new(2);
+/// list.push(1);
+/// list.push(2);
+/// let evicted = list.push(3);
+///
+/// assert_eq!(*evicted.unwrap(), 1);
+/// assert_eq!(list[0], 2);
+/// ```
+#[derive(Debug)]
+pub struct BoundedList<T> {
+    inner: List<T>,
+    capacity: usize,
+    subscribers: RefCell<Vec<NodeId>>,
+}
+
+impl<T> BoundedList<T> {
+    /// Create an empty list that holds at most `capacity` values.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: List::empty(),
+            capacity,
+            subscribers: RefCell::new(vec![]),
+        }
+    }
+
+    /// The max number of values held by the list.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Push a value to the back of the list.
+    /// If the list is full the oldest value is removed and returned.
+    pub fn push(&mut self, value: T) -> Option<StateValue<T>> {
+        let mut changes = vec![];
+        let evicted = self.push_value(value, &mut changes);
+        self.notify(changes);
+        evicted
+    }
+
+    /// Push all the values to the back of the list, evicting
+    /// the oldest values as needed.
+    pub fn extend(&mut self, values: impl IntoIterator<Item = T>) {
+        let mut changes = vec![];
+        for value in values {
+            let _ = self.push_value(value, &mut changes);
+        }
+        self.notify(changes);
+    }
+
+    /// The values held by the list, from the oldest to the newest.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.inner.len()).map(|i| &self.inner[i])
+    }
+
+    fn push_value(&mut self, value: T, changes: &mut Vec<Change>) -> Option<StateValue<T>> {
+        if self.capacity == 0 {
+            return Some(StateValue::new(value));
+        }
+
+        let mut evicted = None;
+        if self.inner.len() >= self.capacity {
+            evicted = self.inner.pop_front();
+            changes.push(Change::RemoveIndex(0));
+        }
+
+        self.inner.push_back(value);
+        changes.push(Change::Push);
+        evicted
+    }
+
+    fn notify(&self, changes: Vec<Change>) {
+        if changes.is_empty() {
+            return;
+        }
+
+        for s in self.subscribers.borrow_mut().drain(..) {
+            DIRTY_NODES.with(|nodes| {
+                let mut nodes = nodes.borrow_mut();
+                for change in &changes {
+                    nodes.push((s.clone(), change.clone()));
+                }
+            });
+        }
+    }
+}
+
+impl<T> Index<usize> for BoundedList<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.inner[index]
+    }
+}
+
+impl<T: Debug> BoundedList<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    pub fn get_value(&self, _node_id: &NodeId) -> ValueRef<'_> {
+        ValueRef::List(self)
+    }
+}
+
+impl<T: Debug> Collection for BoundedList<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn subscribe(&self, node_id: NodeId) {
+        self.subscribers.borrow_mut().push(node_id);
+    }
+
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+}
+
+impl<T: Debug> State for BoundedList<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_> {
+        self.inner.state_get(key, node_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::drain_dirty_nodes;
+
+    #[test]
+    fn evict_oldest() {
+        let mut list = BoundedList::new(3);
+        list.extend(0..5usize);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn eviction_changes() {
+        let mut list = BoundedList::<usize>::new(1);
+        let node_id: NodeId = 0.into();
+
+        Collection::subscribe(&list, node_id.clone());
+        assert!(list.push(1).is_none());
+        assert_eq!(drain_dirty_nodes(), vec![(node_id.clone(), Change::Push)]);
+
+        Collection::subscribe(&list, node_id.clone());
+        assert_eq!(*list.push(2).unwrap(), 1);
+        assert_eq!(
+            drain_dirty_nodes(),
+            vec![
+                (node_id.clone(), Change::RemoveIndex(0)),
+                (node_id.clone(), Change::Push)
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut list = BoundedList::<usize>::new(0);
+        let node_id: NodeId = 0.into();
+        Collection::subscribe(&list, node_id);
+        assert_eq!(*list.push(1).unwrap(), 1);
+        assert!(list.is_empty());
+        assert!(drain_dirty_nodes().is_empty());
+    }
+}
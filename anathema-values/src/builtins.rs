@@ -0,0 +1,186 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use crate::function::{FunctionError, FunctionResult};
+use crate::{Num, Owned, ValueRef};
+
+// -----------------------------------------------------------------------------
+//   - Built-in functions -
+//   These are registered before any other function, so registering a
+//   function with the same name replaces the built-in function.
+// -----------------------------------------------------------------------------
+type Builtin = fn(&[ValueRef<'_>]) -> FunctionResult;
+
+pub(crate) const BUILTINS: &[(&str, Builtin)] = &[
+    ("len", len),
+    ("upper", upper),
+    ("lower", lower),
+    ("trim", trim),
+    ("abs", abs),
+    ("min", min),
+    ("max", max),
+];
+
+// The number of characters in a string, or the number of values in a list or a map
+fn len(args: &[ValueRef<'_>]) -> FunctionResult {
+    FunctionError::check_arity(args, 1)?;
+    let len = match &args[0] {
+        ValueRef::Str(s) => Some(s.chars().count()),
+        ValueRef::List(list) => Some(list.len()),
+        ValueRef::Expressions(list) => Some(list.0.len()),
+        ValueRef::Map(map) => map.state_len(),
+        ValueRef::ExpressionMap(map) => Some(map.0.len()),
+        _ => None,
+    };
+
+    match len {
+        Some(len) => Ok(ValueRef::Owned(len.into())),
+        None => Err(FunctionError::InvalidArgument {
+            index: 0,
+            expected: "a string, a list or a map",
+        }),
+    }
+}
+
+fn upper(args: &[ValueRef<'_>]) -> FunctionResult {
+    map_str(args, str::to_uppercase)
+}
+
+fn lower(args: &[ValueRef<'_>]) -> FunctionResult {
+    map_str(args, str::to_lowercase)
+}
+
+fn trim(args: &[ValueRef<'_>]) -> FunctionResult {
+    map_str(args, |s| s.trim().to_string())
+}
+
+fn map_str(args: &[ValueRef<'_>], f: impl Fn(&str) -> String) -> FunctionResult {
+    FunctionError::check_arity(args, 1)?;
+    match &args[0] {
+        ValueRef::Str(s) => Ok(ValueRef::Str(Cow::Owned(f(s)))),
+        _ => Err(FunctionError::InvalidArgument {
+            index: 0,
+            expected: "a string",
+        }),
+    }
+}
+
+fn abs(args: &[ValueRef<'_>]) -> FunctionResult {
+    FunctionError::check_arity(args, 1)?;
+    let num = match num_arg(args, 0)? {
+        Num::Signed(n) => Num::Unsigned(n.unsigned_abs()),
+        Num::Float(n) => Num::Float(n.abs()),
+        num @ Num::Unsigned(_) => num,
+    };
+    Ok(ValueRef::Owned(Owned::Num(num)))
+}
+
+fn min(args: &[ValueRef<'_>]) -> FunctionResult {
+    select_num(args, Ordering::Less)
+}
+
+fn max(args: &[ValueRef<'_>]) -> FunctionResult {
+    select_num(args, Ordering::Greater)
+}
+
+// Select the number that compares as `ordering` to all the other numbers.
+// Numbers are compared by value across variants, and the selected number keeps its variant.
+// NaN is never selected over another number.
+fn select_num(args: &[ValueRef<'_>], ordering: Ordering) -> FunctionResult {
+    if args.is_empty() {
+        return Err(FunctionError::Arity {
+            expected: 1,
+            actual: 0,
+        });
+    }
+
+    let mut selected = num_arg(args, 0)?;
+    for index in 1..args.len() {
+        let num = num_arg(args, index)?;
+        let replace = match selected.partial_cmp(&num) {
+            Some(ord) => ord == ordering.reverse(),
+            None => matches!(selected, Num::Float(n) if n.is_nan()),
+        };
+
+        if replace {
+            selected = num;
+        }
+    }
+
+    Ok(ValueRef::Owned(Owned::Num(selected)))
+}
+
+fn num_arg(args: &[ValueRef<'_>], index: usize) -> Result<Num, FunctionError> {
+    match args[index] {
+        ValueRef::Owned(Owned::Num(num)) => Ok(num),
+        _ => Err(FunctionError::InvalidArgument {
+            index,
+            expected: "a number",
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{List, Map};
+
+    fn num(n: impl Into<Num>) -> ValueRef<'static> {
+        ValueRef::Owned(Owned::Num(n.into()))
+    }
+
+    #[test]
+    fn len_of_values() {
+        assert_eq!(len(&["håll".into()]), Ok(num(4u8)));
+
+        let list = List::new([1u8, 2, 3]);
+        assert_eq!(len(&[ValueRef::List(&list)]), Ok(num(3u8)));
+
+        let map = Map::new([("a", 1u8)]);
+        assert_eq!(len(&[ValueRef::Map(&map)]), Ok(num(1u8)));
+
+        assert!(len(&[num(1u8)]).is_err());
+        assert!(len(&[]).is_err());
+    }
+
+    #[test]
+    fn string_functions() {
+        assert_eq!(upper(&["Hello".into()]), Ok("HELLO".into()));
+        assert_eq!(lower(&["Hello".into()]), Ok("hello".into()));
+        assert_eq!(trim(&["  Hello \n".into()]), Ok("Hello".into()));
+        assert_eq!(
+            upper(&[num(1u8)]),
+            Err(FunctionError::InvalidArgument {
+                index: 0,
+                expected: "a string"
+            })
+        );
+    }
+
+    #[test]
+    fn abs_of_numbers() {
+        assert_eq!(abs(&[num(-5i8)]), Ok(num(5u8)));
+        assert_eq!(abs(&[num(i64::MIN)]), Ok(num(i64::MIN.unsigned_abs())));
+        assert_eq!(abs(&[num(Num::Float(-1.5))]), Ok(num(Num::Float(1.5))));
+        assert_eq!(abs(&[num(3u8)]), Ok(num(3u8)));
+    }
+
+    #[test]
+    fn min_max_across_variants() {
+        let args = [num(3u8), num(-2i8), num(Num::Float(2.5))];
+        assert!(matches!(
+            min(&args),
+            Ok(ValueRef::Owned(Owned::Num(Num::Signed(-2))))
+        ));
+        assert!(matches!(
+            max(&args),
+            Ok(ValueRef::Owned(Owned::Num(Num::Unsigned(3))))
+        ));
+
+        let args = [num(Num::Float(f64::NAN)), num(1u8)];
+        assert_eq!(max(&args), Ok(num(1u8)));
+
+        assert!(min(&[]).is_err());
+        assert!(max(&[num(1u8), "2".into()]).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt::Debug;
+use std::sync::mpsc::Receiver;
+
+use crate::state::State;
+use crate::{BoundedList, Collection, NodeId, Path, ValueRef};
+
+/// A collection fed by a channel.
+///
+/// Values are moved from the receiver into a [`BoundedList`] when calling
+/// [`ChannelCollection::poll`]. The runtime polls the collections in the state of
+/// every view at the start of each frame, if the view gives mutable access to the state
+/// (see [`View::state_mut`]).
+/// Once the list is full the oldest value is dropped to make room for the new one.
+///
+/// Subscribers are notified of both the new values ([`Change::Push`]) and the
+/// evicted ones ([`Change::RemoveIndex`]).
+///
+/// [`View::state_mut`]: ../anathema_widget_core/views/trait.View.html#method.state_mut
+/// [`Change::Push`]: crate::Change::Push
+/// [`Change::RemoveIndex`]: crate::Change::RemoveIndex
+#[derive(Debug)]
+pub struct ChannelCollection<T> {
+    receiver: Receiver<T>,
+    inner: BoundedList<T>,
+}
+
+impl<T> ChannelCollection<T> {
+    /// Create a new channel collection that holds at most `capacity` values.
+    pub fn new(receiver: Receiver<T>, capacity: usize) -> Self {
+        Self {
+            receiver,
+            inner: BoundedList::new(capacity),
+        }
+    }
+
+    /// Drain the receiver into the buffer.
+    /// Returns the number of values received.
+    pub fn poll(&mut self) -> usize {
+        let mut count = 0;
+        let values = self.receiver.try_iter().inspect(|_| count += 1);
+        self.inner.extend(values);
+        count
+    }
+
+    /// The max number of values held by the collection.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// The buffered values, in the order they were received.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.inner.iter()
+    }
+}
+
+impl<T: Debug> ChannelCollection<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    pub fn get_value(&self, _node_id: &NodeId) -> ValueRef<'_> {
+        ValueRef::List(self)
+    }
+}
+
+impl<T: Debug> Collection for ChannelCollection<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn subscribe(&self, node_id: NodeId) {
+        self.inner.subscribe(node_id);
+    }
+
+    fn generation(&self) -> u64 {
+        self.inner.generation()
+    }
+}
+
+impl<T: Debug> State for ChannelCollection<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_> {
+        self.inner.state_get(key, node_id)
+    }
+
+    fn state_poll(&mut self) {
+        self.poll();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::{drain_dirty_nodes, Change};
+
+    #[test]
+    fn drop_oldest() {
+        let (tx, rx) = mpsc::channel();
+        let mut collection = ChannelCollection::new(rx, 2);
+
+        for i in 0..3 {
+            tx.send(i).unwrap();
+        }
+
+        assert_eq!(collection.poll(), 3);
+        assert_eq!(collection.len(), 2);
+        assert_eq!(
+            collection.iter().copied().collect::<Vec<usize>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn notify_on_append_and_evict() {
+        let (tx, rx) = mpsc::channel();
+        let mut collection = ChannelCollection::new(rx, 1);
+        let node_id: NodeId = 0.into();
+
+        tx.send(1usize).unwrap();
+        Collection::subscribe(&collection, node_id.clone());
+        collection.poll();
+        assert_eq!(drain_dirty_nodes(), vec![(node_id.clone(), Change::Push)]);
+
+        tx.send(2).unwrap();
+        Collection::subscribe(&collection, node_id.clone());
+        collection.poll();
+        assert_eq!(
+            drain_dirty_nodes(),
+            vec![
+                (node_id.clone(), Change::RemoveIndex(0)),
+                (node_id.clone(), Change::Push)
+            ]
+        );
+
+        Collection::subscribe(&collection, node_id.clone());
+        assert_eq!(collection.poll(), 0);
+        assert!(drain_dirty_nodes().is_empty());
+    }
+}
@@ -1,8 +1,335 @@
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+
 use crate::state::State;
-use crate::NodeId;
+use crate::{Change, NodeId, Path, StateValue, ValueRef};
 
 pub trait Collection: State {
     fn len(&self) -> usize;
 
     fn subscribe(&self, node_id: NodeId);
+
+    /// A counter that is incremented every time the collection changes,
+    /// including changes to nested collections (e.g a `Map` inside a `List`)
+    /// made through [`ValueMut`].
+    ///
+    /// Comparing generations is a cheap way to know if a collection is unchanged.
+    /// Collections that don't track changes (e.g `Vec<T>`) are always at generation zero.
+    fn generation(&self) -> u64 {
+        0
+    }
+}
+
+// This is implemented on the trait object rather than as a default method,
+// so it works for every collection (including `ValueRef::List`) without each
+// implementation having to provide an iterator.
+impl<'a> dyn Collection + 'a {
+    /// An iterator over the values in the collection.
+    /// The values are subscribed to by `node_id`, the same as [`State::state_get`].
+    ///
+    /// A concrete collection has to be borrowed as a `&dyn Collection` first, e.g
+    /// `(&list as &dyn Collection).iter(&node_id)`.
+    ///
+    /// ```
+    /// # use anathema_values::{Collection, List, NodeId};
+    /// let list = List::new([1u8, 2, 3]);
+    /// let collection: &dyn Collection = &list;
+    ///
+    /// let node_id = NodeId::from(0);
+    /// let sum = collection
+    ///     .iter(&node_id)
+    ///     .filter_map(|value| u8::try_from(value).ok())
+    ///     .sum::<u8>();
+    /// assert_eq!(sum, 6);
+    /// ```
+    pub fn iter<'b>(&'b self, node_id: &'b NodeId) -> Iter<'b> {
+        Iter::new(self, node_id)
+    }
+}
+
+/// An iterator over the values of a [`Collection`], see [`iter`](trait.Collection.html#method.iter).
+pub struct Iter<'a> {
+    collection: &'a dyn Collection,
+    node_id: &'a NodeId,
+    index: usize,
+    len: usize,
+}
+
+impl<'a> Iter<'a> {
+    fn new(collection: &'a dyn Collection, node_id: &'a NodeId) -> Self {
+        Self {
+            len: collection.len(),
+            collection,
+            node_id,
+            index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = ValueRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+
+        let value = self
+            .collection
+            .state_get(&Path::Index(self.index), self.node_id);
+        self.index += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.index;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+// -----------------------------------------------------------------------------
+//   - Change log -
+//   The generation and the changes of a collection.
+//
+//   Changes are recorded from the moment the collection is created.
+//   So a collection that nobody drains doesn't grow a log forever, the log
+//   is capped: once it's full the changes are replaced by a single
+//   `Change::Update`, meaning the entire collection has to be read again.
+//
+//   Changing a nested collection (e.g a `Map` inside a `List`) goes through
+//   a `ValueMut`, which records the change in the parent as well.
+// -----------------------------------------------------------------------------
+pub(crate) const MAX_CHANGES: usize = 1024;
+
+#[derive(Debug, Default)]
+pub(crate) struct ChangeLog {
+    generation: u64,
+    changes: Vec<Change>,
+}
+
+impl ChangeLog {
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub(crate) fn push(&mut self, change: &Change) {
+        self.generation += 1;
+
+        if self.changes == [Change::Update] {
+            return;
+        }
+
+        match self.changes.len() < MAX_CHANGES {
+            true => self.changes.push(change.clone()),
+            false => self.changes = vec![Change::Update],
+        }
+    }
+
+    pub(crate) fn drain(&mut self) -> Vec<Change> {
+        std::mem::take(&mut self.changes)
+    }
+}
+
+/// A mutable reference to a value in a [`List`](crate::List) or a [`Map`](crate::Map).
+///
+/// The value is only recorded as changed once it's accessed mutably,
+/// so reading the value through the reference is not a change.
+///
+/// Changing a nested collection through the reference (e.g `map.get_mut("list")`)
+/// is recorded as a change to the parent as well, and increments its generation.
+pub struct ValueMut<'a, T> {
+    value: &'a mut StateValue<T>,
+    log: &'a mut ChangeLog,
+    change: Option<Change>,
+}
+
+impl<'a, T> ValueMut<'a, T> {
+    // `change` is recorded the first time the value is accessed mutably
+    pub(crate) fn new(
+        value: &'a mut StateValue<T>,
+        log: &'a mut ChangeLog,
+        change: Option<Change>,
+    ) -> Self {
+        Self { value, log, change }
+    }
+}
+
+impl<T> Deref for ValueMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value.deref()
+    }
+}
+
+impl<T> DerefMut for ValueMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        if let Some(change) = self.change.take() {
+            self.log.push(&change);
+        }
+        self.value.deref_mut()
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Plain collections -
+//   `Vec<T>` and arrays can be used as lists in templates.
+//
+//   Unlike `List` these can't notify subscribers of changes, so the values
+//   are only read again when something else causes the nodes to update.
+//
+//   Note: a slice (`&[T]`) can't be used directly, as the collection
+//   has to be a sized value to be referenced as a `dyn Collection`.
+// -----------------------------------------------------------------------------
+fn slice_get<'a, T>(slice: &'a [T], key: &Path, node_id: &NodeId) -> ValueRef<'a>
+where
+    for<'b> &'b T: Into<ValueRef<'b>>,
+{
+    match key {
+        Path::Index(index) => match slice.get(*index) {
+            Some(value) => value.into(),
+            None => ValueRef::Empty,
+        },
+        Path::Composite(lhs, rhs) => match slice_get::<T>(slice, lhs, node_id) {
+            ValueRef::Map(map) => map.state_get(rhs, node_id),
+            ValueRef::List(collection) => collection.state_get(rhs, node_id),
+            _ => ValueRef::Empty,
+        },
+        Path::Key(_) => ValueRef::Empty,
+    }
+}
+
+impl<T: Debug> State for Vec<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_> {
+        slice_get::<T>(self, key, node_id)
+    }
+}
+
+impl<T: Debug> Collection for Vec<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn subscribe(&self, _node_id: NodeId) {}
+}
+
+impl<T: Debug, const N: usize> State for [T; N]
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_> {
+        slice_get::<T>(self, key, node_id)
+    }
+}
+
+impl<T: Debug, const N: usize> Collection for [T; N]
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn subscribe(&self, _node_id: NodeId) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Owned;
+
+    #[test]
+    fn vec_as_list() {
+        let names = vec!["first".to_string(), "second".to_string()];
+        let value: ValueRef<'_> = (&names).into();
+        let ValueRef::List(list) = value else {
+            panic!("expected a list")
+        };
+
+        let node_id = 0.into();
+        assert_eq!(list.len(), 2);
+        assert_eq!(
+            list.state_get(&Path::Index(1), &node_id).as_str(),
+            Some("second")
+        );
+        assert!(matches!(
+            list.state_get(&Path::Index(2), &node_id),
+            ValueRef::Empty
+        ));
+    }
+
+    #[test]
+    fn nested_arrays() {
+        let numbers = [[1i64, 2], [3, 4]];
+        let value: ValueRef<'_> = (&numbers).into();
+        let ValueRef::List(list) = value else {
+            panic!("expected a list")
+        };
+
+        let path = Path::Index(1).compose(0);
+        let node_id = 0.into();
+        assert_eq!(
+            list.state_get(&path, &node_id),
+            ValueRef::Owned(Owned::from(3i64))
+        );
+    }
+
+    #[test]
+    fn iter_values() {
+        let node_id = 0.into();
+
+        let numbers = vec![1u8, 2, 3];
+        let collection: &dyn Collection = &numbers;
+        let values = collection.iter(&node_id).collect::<Vec<_>>();
+        let num = |n: u8| ValueRef::Owned(Owned::from(n));
+        assert_eq!(values, vec![num(1), num(2), num(3)]);
+
+        let list = crate::List::new(["a".to_string(), "b".to_string()]);
+        let collection: &dyn Collection = &list;
+        let mut iter = collection.iter(&node_id);
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next().unwrap().as_str(), Some("a"));
+        assert_eq!(iter.len(), 1);
+
+        let empty: [u8; 0] = [];
+        let collection: &dyn Collection = &empty;
+        assert_eq!(collection.iter(&node_id).count(), 0);
+    }
+
+    #[test]
+    fn record_changes() {
+        let mut log = ChangeLog::default();
+        log.push(&Change::Push);
+        log.push(&Change::RemoveIndex(0));
+        assert_eq!(log.generation(), 2);
+        assert_eq!(log.drain(), vec![Change::Push, Change::RemoveIndex(0)]);
+        assert!(log.drain().is_empty());
+        assert_eq!(log.generation(), 2);
+    }
+
+    #[test]
+    fn full_log_is_an_update() {
+        let mut log = ChangeLog::default();
+        for _ in 0..MAX_CHANGES {
+            log.push(&Change::Push);
+        }
+        assert_eq!(log.changes.len(), MAX_CHANGES);
+
+        log.push(&Change::Push);
+        log.push(&Change::RemoveIndex(0));
+        assert_eq!(log.drain(), vec![Change::Update]);
+        assert_eq!(log.generation(), MAX_CHANGES as u64 + 2);
+
+        // Recording starts over once the log is drained
+        log.push(&Change::Push);
+        assert_eq!(log.drain(), vec![Change::Push]);
+    }
 }
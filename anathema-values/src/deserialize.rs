@@ -0,0 +1,394 @@
+use std::fmt::{self, Display};
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use crate::{Collection, NodeId, Num, Owned, Path, State, ValueRef};
+
+// -----------------------------------------------------------------------------
+//   - Deserialize error -
+// -----------------------------------------------------------------------------
+/// Error produced when a [`ValueRef`] can not be deserialized into a given type.
+/// The path points to the value that failed, if the value was nested.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeserializeError {
+    pub path: Option<Path>,
+    pub msg: String,
+}
+
+impl DeserializeError {
+    fn at(mut self, path: &Option<Path>) -> Self {
+        if self.path.is_none() {
+            self.path = path.clone();
+        }
+        self
+    }
+}
+
+impl Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{path}: {}", self.msg),
+            None => write!(f, "{}", self.msg),
+        }
+    }
+}
+
+impl std::error::Error for DeserializeError {}
+
+impl de::Error for DeserializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self {
+            path: None,
+            msg: msg.to_string(),
+        }
+    }
+}
+
+/// Deserialize a value (generally a `ValueRef::Map` or `ValueRef::List`) into `T`.
+///
+/// Maps with known keys (a [`Map`](crate::Map) or a state using `#[derive(State)]`)
+/// can be deserialized into any map or struct. Other [`State`]s can only be
+/// deserialized into structs, as there is no way to list their keys.
+///
+/// The `node_id` is subscribed to any value it reads.
+///
+/// ```
+/// # extern crate anathema_values as anathema;
+/// # use anathema_values::{deserialize, List, NodeId, State, StateValue, ValueRef};
+/// #[derive(Debug, State)]
+/// struct Input {
+///     name: StateValue<String>,
+///     scores: List<usize>,
+/// }
+///
+/// #[derive(serde::Deserialize)]
+/// struct Output {
+///     name: String,
+///     scores: Vec<usize>,
+/// }
+///
+/// let input = Input {
+///     name: StateValue::new("Ford".into()),
+///     scores: List::new(vec![1, 2]),
+/// };
+///
+/// let output: Output = deserialize(ValueRef::Map(&input), &NodeId::new(0)).unwrap();
+/// assert_eq!(output.name, "Ford");
+/// assert_eq!(output.scores, vec![1, 2]);
+/// ```
+pub fn deserialize<T: DeserializeOwned>(
+    value: ValueRef<'_>,
+    node_id: &NodeId,
+) -> Result<T, DeserializeError> {
+    let deserializer = ValueDeserializer {
+        value,
+        node_id,
+        path: None,
+    };
+    T::deserialize(deserializer)
+}
+
+// -----------------------------------------------------------------------------
+//   - Deserializer -
+// -----------------------------------------------------------------------------
+struct ValueDeserializer<'a, 'id> {
+    value: ValueRef<'a>,
+    node_id: &'id NodeId,
+    path: Option<Path>,
+}
+
+impl<'a, 'id> ValueDeserializer<'a, 'id> {
+    fn child(&self, value: ValueRef<'a>, key: impl Into<Path>) -> Self {
+        let path = match &self.path {
+            Some(path) => path.compose(key),
+            None => key.into(),
+        };
+
+        Self {
+            value,
+            node_id: self.node_id,
+            path: Some(path),
+        }
+    }
+
+    fn error(&self, msg: &str) -> DeserializeError {
+        DeserializeError {
+            path: self.path.clone(),
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl<'de, 'a, 'id> de::Deserializer<'de> for ValueDeserializer<'a, 'id> {
+    type Error = DeserializeError;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let path = self.path.clone();
+        match self.value {
+            ValueRef::Str(s) => visitor.visit_str(&s),
+            ValueRef::Owned(Owned::Num(Num::Signed(n))) => visitor.visit_i64(n),
+            ValueRef::Owned(Owned::Num(Num::Unsigned(n))) => visitor.visit_u64(n),
+            ValueRef::Owned(Owned::Num(Num::Float(n))) => visitor.visit_f64(n),
+            ValueRef::Owned(Owned::Bool(b)) => visitor.visit_bool(b),
+            ValueRef::Owned(Owned::Char(c)) => visitor.visit_char(c),
+            ValueRef::Owned(Owned::Color(_)) => Err(self.error("colours can not be deserialized")),
+            ValueRef::List(list) => visitor.visit_seq(ListAccess {
+                list,
+                index: 0,
+                parent: self,
+            }),
+            ValueRef::Map(state) => match state.state_keys() {
+                Some(keys) => visitor.visit_map(StructAccess {
+                    state,
+                    keys,
+                    index: 0,
+                    value: ValueRef::Empty,
+                    parent: self,
+                }),
+                None => {
+                    Err(self.error("maps without known keys can only be deserialized into structs"))
+                }
+            },
+            ValueRef::Expressions(_) | ValueRef::ExpressionMap(_) => {
+                Err(self.error("expressions has to be evaluated before deserialization"))
+            }
+            ValueRef::Deferred => Err(self.error("deferred values can not be deserialized")),
+            ValueRef::Empty => visitor.visit_unit(),
+        }
+        .map_err(|e| e.at(&path))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            ValueRef::Empty => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let path = self.path.clone();
+        match self.value {
+            ValueRef::Map(state) => visitor
+                .visit_map(StructAccess {
+                    state,
+                    keys: fields.iter().map(|field| field.to_string()).collect(),
+                    index: 0,
+                    value: ValueRef::Empty,
+                    parent: self,
+                })
+                .map_err(|e| e.at(&path)),
+            _ => self.deserialize_any(visitor).map_err(|e| {
+                let msg = format!("expected a map for `{name}` ({})", e.msg);
+                DeserializeError { path: e.path, msg }
+            }),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let path = self.path.clone();
+        match self.value {
+            ValueRef::Str(s) => visitor
+                .visit_enum(s.into_deserializer())
+                .map_err(|e: DeserializeError| e.at(&path)),
+            _ => Err(self.error("only unit variants can be deserialized")),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - List access -
+// -----------------------------------------------------------------------------
+struct ListAccess<'a, 'id> {
+    list: &'a dyn Collection,
+    index: usize,
+    parent: ValueDeserializer<'a, 'id>,
+}
+
+impl<'de, 'a, 'id> SeqAccess<'de> for ListAccess<'a, 'id> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.index >= self.list.len() {
+            return Ok(None);
+        }
+
+        let index = self.index;
+        self.index += 1;
+
+        let value = self
+            .list
+            .state_get(&Path::Index(index), self.parent.node_id);
+        let child = self.parent.child(value, index);
+        let path = child.path.clone();
+        seed.deserialize(child).map(Some).map_err(|e| e.at(&path))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.list.len() - self.index)
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Struct access -
+//   Only the keys (the fields of a struct, or the keys of a map) are looked up,
+//   and empty values are skipped so missing fields are reported by the visitor.
+// -----------------------------------------------------------------------------
+struct StructAccess<'a, 'id> {
+    state: &'a dyn State,
+    keys: Vec<String>,
+    index: usize,
+    value: ValueRef<'a>,
+    parent: ValueDeserializer<'a, 'id>,
+}
+
+impl<'de, 'a, 'id> MapAccess<'de> for StructAccess<'a, 'id> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        while let Some(key) = self.keys.get(self.index) {
+            self.index += 1;
+            let value = self
+                .state
+                .state_get(&Path::from(key.as_str()), self.parent.node_id);
+            if let ValueRef::Empty = value {
+                continue;
+            }
+            self.value = value;
+            return seed.deserialize(key.as_str().into_deserializer()).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let key = self.keys[self.index - 1].as_str();
+        let child = self.parent.child(std::mem::take(&mut self.value), key);
+        let path = child.path.clone();
+        seed.deserialize(child).map_err(|e| e.at(&path))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::testing::TestState;
+
+    #[derive(Debug, Deserialize)]
+    struct Inner {
+        name: String,
+        names: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Simple {
+        name: String,
+        counter: u32,
+        debug: bool,
+        inner: Inner,
+        nested_list: Vec<Vec<u8>>,
+        missing: Option<usize>,
+    }
+
+    #[test]
+    fn deserialize_state() {
+        let state = TestState::new();
+        let output: Simple = deserialize(ValueRef::Map(&state), &NodeId::new(0)).unwrap();
+
+        assert_eq!(output.name, "Dirk Gently");
+        assert_eq!(output.counter, 3);
+        assert!(!output.debug);
+        assert_eq!(output.inner.name, "Fiddle McStick");
+        assert_eq!(output.inner.names, vec!["arthur", "bobby"]);
+        assert_eq!(output.nested_list, vec![vec![1, 2, 3]]);
+        assert!(output.missing.is_none());
+    }
+
+    #[test]
+    fn deserialize_list() {
+        let state = TestState::new();
+        let list = state.state_get(&Path::from("generic_list"), &NodeId::new(0));
+        let output: Vec<usize> = deserialize(list, &NodeId::new(0)).unwrap();
+        assert_eq!(output, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn deserialize_map() {
+        let state = TestState::new();
+        let map = state.state_get(&Path::from("generic_map"), &NodeId::new(0));
+        let output: std::collections::BTreeMap<String, std::collections::BTreeMap<String, u8>> =
+            deserialize(map, &NodeId::new(0)).unwrap();
+        assert_eq!(output["inner"]["first"], 1);
+        assert_eq!(output["inner"]["second"], 2);
+    }
+
+    #[test]
+    fn type_mismatch_reports_path() {
+        #[derive(Debug, Deserialize)]
+        struct Names {
+            #[allow(dead_code)]
+            names: Vec<u8>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Wrong {
+            #[allow(dead_code)]
+            inner: Names,
+        }
+
+        let state = TestState::new();
+        let err = deserialize::<Wrong>(ValueRef::Map(&state), &NodeId::new(0)).unwrap_err();
+        let expected = Path::from("inner").compose("names").compose(0);
+        assert_eq!(err.path, Some(expected));
+    }
+
+    #[test]
+    fn missing_field() {
+        #[derive(Debug, Deserialize)]
+        struct Missing {
+            #[allow(dead_code)]
+            does_not_exist: String,
+        }
+
+        let state = TestState::new();
+        let err = deserialize::<Missing>(ValueRef::Map(&state), &NodeId::new(0)).unwrap_err();
+        assert_eq!(err.msg, "missing field `does_not_exist`");
+    }
+}
@@ -0,0 +1,303 @@
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use crate::builtins::BUILTINS;
+use crate::hashmap::HashMap;
+use crate::ValueRef;
+
+pub type FunctionResult = Result<ValueRef<'static>, FunctionError>;
+
+type Function = dyn Fn(&[ValueRef<'_>]) -> FunctionResult + Send + Sync;
+
+thread_local! {
+    // The functions called from the templates (see `Functions::enter`)
+    static ACTIVE: RefCell<Option<Functions>> = const { RefCell::new(None) };
+    static RESOLVE_ERRORS: RefCell<Vec<ResolveError>> = const { RefCell::new(vec![]) };
+}
+
+// -----------------------------------------------------------------------------
+//   - Function error -
+// -----------------------------------------------------------------------------
+/// Error returned by a template function.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FunctionError {
+    /// No function registered with the given name
+    NotFound,
+    /// The function was called with the wrong number of arguments
+    Arity { expected: usize, actual: usize },
+    /// The argument at `index` has the wrong type
+    InvalidArgument {
+        index: usize,
+        expected: &'static str,
+    },
+    /// Any other error
+    Custom(String),
+}
+
+impl FunctionError {
+    /// Return an arity error if the number of arguments are not `expected`.
+    pub fn check_arity(args: &[ValueRef<'_>], expected: usize) -> Result<(), Self> {
+        match args.len() == expected {
+            true => Ok(()),
+            false => Err(Self::Arity {
+                expected,
+                actual: args.len(),
+            }),
+        }
+    }
+}
+
+impl Display for FunctionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "function not found"),
+            Self::Arity { expected, actual } => {
+                write!(f, "expected {expected} argument(s), got {actual}")
+            }
+            Self::InvalidArgument { index, expected } => {
+                write!(f, "argument {index} should be {expected}")
+            }
+            Self::Custom(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for FunctionError {}
+
+// -----------------------------------------------------------------------------
+//   - Resolve error -
+// -----------------------------------------------------------------------------
+/// A function call that failed during expression evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolveError {
+    pub function: String,
+    pub error: FunctionError,
+}
+
+impl Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: {}", self.function, self.error)
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// Drain all the errors produced by function calls since the last drain.
+/// The same error is only reported once per drain, even if the expression
+/// is evaluated every frame.
+pub fn drain_resolve_errors() -> Vec<ResolveError> {
+    RESOLVE_ERRORS.with(|errors| errors.borrow_mut().drain(..).collect())
+}
+
+// -----------------------------------------------------------------------------
+//   - Registered functions -
+// -----------------------------------------------------------------------------
+/// Functions that can be called from a template.
+///
+/// Every set of functions starts out with the built-in functions,
+/// and registering a function with the same name as an existing one replaces it:
+///
+/// * `len(value)`: the number of characters in a string, or values in a list or a map
+/// * `upper(string)`, `lower(string)` and `trim(string)`
+/// * `abs(number)`
+/// * `min(number, ...)` and `max(number, ...)`
+///
+/// Cloning `Functions` shares the registered functions between the clones.
+/// The functions are only called while they are entered (see [`Functions::enter`]),
+/// otherwise only the built-in functions are available.
+///
+/// ```
+/// use anathema_values::{FunctionError, Functions, Num, Owned, ValueRef};
+///
+/// let functions = Functions::new();
+/// functions.register_fn("double", |args: &[ValueRef<'_>]| {
+///     FunctionError::check_arity(args, 1)?;
+///     match args[0] {
+///         ValueRef::Owned(Owned::Num(Num::Unsigned(n))) => Ok(Owned::from(n * 2).into()),
+///         _ => Err(FunctionError::InvalidArgument {
+///             index: 0,
+///             expected: "an unsigned integer",
+///         }),
+///     }
+/// });
+/// ```
+#[derive(Clone)]
+pub struct Functions(Arc<RwLock<HashMap<String, Arc<Function>>>>);
+
+impl Functions {
+    /// Create a new set of functions, containing only the built-in functions.
+    pub fn new() -> Self {
+        let builtins = BUILTINS
+            .iter()
+            .map(|(name, f)| (name.to_string(), Arc::new(*f) as Arc<Function>));
+        Self(Arc::new(RwLock::new(builtins.collect())))
+    }
+
+    /// Register a function that can be called from a template.
+    pub fn register_fn<F>(&self, name: impl Into<String>, f: F)
+    where
+        F: Fn(&[ValueRef<'_>]) -> FunctionResult + Send + Sync + 'static,
+    {
+        self.0.write().insert(name.into(), Arc::new(f));
+    }
+
+    /// Call these functions from the templates until the guard is dropped,
+    /// after which the previously entered functions (if any) are called again.
+    pub fn enter(&self) -> FunctionsGuard {
+        let previous = ACTIVE.with(|active| active.replace(Some(self.clone())));
+        FunctionsGuard { previous }
+    }
+
+    // The function is cloned out of the registry so the lock
+    // isn't held while the function is running.
+    fn get(&self, name: &str) -> Option<Arc<Function>> {
+        self.0.read().get(name).cloned()
+    }
+}
+
+impl Default for Functions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Functions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.read().keys()).finish()
+    }
+}
+
+/// Restores the previously entered functions when dropped.
+/// See [`Functions::enter`].
+pub struct FunctionsGuard {
+    previous: Option<Functions>,
+}
+
+impl Drop for FunctionsGuard {
+    fn drop(&mut self) {
+        ACTIVE.with(|active| *active.borrow_mut() = self.previous.take());
+    }
+}
+
+// Call a function by name.
+// Any error is recorded (see `drain_resolve_errors`) and the call
+// evaluates to an empty value.
+pub(crate) fn call<'a>(name: &str, args: &[ValueRef<'_>]) -> ValueRef<'a> {
+    let function = ACTIVE.with(|active| match &*active.borrow() {
+        Some(functions) => functions.get(name),
+        None => BUILTINS
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, f)| Arc::new(*f) as Arc<Function>),
+    });
+
+    let result = match function {
+        Some(function) => function(args),
+        None => Err(FunctionError::NotFound),
+    };
+
+    match result {
+        Ok(value) => value,
+        Err(error) => {
+            let error = ResolveError {
+                function: name.to_string(),
+                error,
+            };
+
+            RESOLVE_ERRORS.with(|errors| {
+                let mut errors = errors.borrow_mut();
+                if !errors.contains(&error) {
+                    errors.push(error);
+                }
+            });
+
+            ValueRef::Empty
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Num, Owned};
+
+    fn sum(args: &[ValueRef<'_>]) -> FunctionResult {
+        let mut total = Num::Unsigned(0);
+        for (index, arg) in args.iter().enumerate() {
+            match arg {
+                ValueRef::Owned(Owned::Num(n)) => total = total + *n,
+                _ => {
+                    return Err(FunctionError::InvalidArgument {
+                        index,
+                        expected: "a number",
+                    })
+                }
+            }
+        }
+        Ok(Owned::Num(total).into())
+    }
+
+    #[test]
+    fn call_registered_function() {
+        let functions = Functions::new();
+        functions.register_fn("sum", sum);
+        let _functions = functions.enter();
+
+        let args = [ValueRef::Owned(1u8.into()), ValueRef::Owned(2u8.into())];
+        assert_eq!(call("sum", &args), ValueRef::Owned(3u8.into()));
+    }
+
+    #[test]
+    fn call_builtin_function() {
+        let args = ["hello".into()];
+        assert_eq!(call("upper", &args), "HELLO".into());
+    }
+
+    #[test]
+    fn functions_are_not_shared() {
+        let args = ["  hello ".into()];
+
+        let first = Functions::new();
+        first.register_fn("trim", |_: &[ValueRef<'_>]| Ok("replaced".into()));
+        let second = Functions::new();
+
+        {
+            let _first = first.enter();
+            assert_eq!(call("trim", &args), "replaced".into());
+
+            // Entering other functions only lasts as long as the guard
+            {
+                let _second = second.enter();
+                assert_eq!(call("trim", &args), "hello".into());
+            }
+
+            assert_eq!(call("trim", &args), "replaced".into());
+        }
+
+        assert_eq!(call("trim", &args), "hello".into());
+    }
+
+    #[test]
+    fn errors_carry_function_name() {
+        let functions = Functions::new();
+        functions.register_fn("sum", sum);
+        let _functions = functions.enter();
+        let _ = drain_resolve_errors();
+
+        let args: [ValueRef<'_>; 1] = ["one".into()];
+        assert!(matches!(call("sum", &args), ValueRef::Empty));
+        assert!(matches!(call("sum", &args), ValueRef::Empty));
+        assert!(matches!(call("does_not_exist", &[]), ValueRef::Empty));
+
+        let errors = drain_resolve_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].to_string(),
+            "`sum`: argument 0 should be a number"
+        );
+        assert_eq!(errors[1].error, FunctionError::NotFound);
+    }
+}
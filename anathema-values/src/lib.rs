@@ -1,28 +1,48 @@
 use std::cell::RefCell;
 
+use anathema_render::{Gradient, GradientDirection};
 pub use anathema_value_derive::State;
 
-pub use self::collection::Collection;
+pub use self::bounded::BoundedList;
+pub use self::channel::ChannelCollection;
+pub use self::collection::{Collection, Iter as CollectionIter, ValueMut};
+#[cfg(feature = "serde")]
+pub use self::deserialize::{deserialize, DeserializeError};
+pub use self::function::{
+    drain_resolve_errors, FunctionError, FunctionResult, Functions, FunctionsGuard, ResolveError,
+};
 pub use self::id::{NextNodeId, NodeId};
 pub use self::list::List;
 pub use self::map::Map;
-pub use self::path::Path;
+pub use self::path::{Path, PathId};
 pub use self::scope::{Context, Scope, ScopeStorage, ScopeValue};
+#[cfg(feature = "serde")]
+pub use self::serialize::OwnedValue;
 pub use self::slab::Slab;
-pub use self::state::{Change, State, StateValue};
-pub use self::value::{ExpressionMap, Expressions, Num, Owned, ValueRef};
+pub use self::state::{Change, CompositeState, State, StateValue};
+pub use self::tween::{Easing, Tween};
+pub use self::value::{ExpressionMap, Expressions, Num, Owned, ParseNumError, ValueRef};
 pub use self::value_expr::{Deferred, Immediate, Resolver, ValueExpr};
 
 pub mod hashmap;
 mod path;
 
+mod bounded;
+mod builtins;
+mod channel;
 mod collection;
+#[cfg(feature = "serde")]
+mod deserialize;
+mod function;
 mod id;
 mod list;
 mod map;
 mod scope;
+#[cfg(feature = "serde")]
+mod serialize;
 mod slab;
 pub mod state;
+mod tween;
 mod value;
 mod value_expr;
 
@@ -242,13 +262,15 @@ impl DynValue for bool {
     }
 }
 
-impl DynValue for anathema_render::Color {
+impl_dyn_value!(anathema_render::Color);
+
+/// A gradient is a list of colours, optionally including the direction:
+/// `["vertical", "blue", "purple"]`.
+/// The direction is horizontal by default, and a single colour is a solid gradient.
+impl DynValue for Gradient {
     fn init_value(context: &Context<'_, '_>, node_id: &NodeId, expr: &ValueExpr) -> Value<Self> {
         let mut resolver = Immediate::new(context.lookup(), node_id);
-        let inner = match expr.eval(&mut resolver) {
-            ValueRef::Str(col) => anathema_render::Color::try_from(col).ok(),
-            val => val.try_into().ok(),
-        };
+        let inner = eval_gradient(expr, &mut resolver);
 
         match resolver.is_deferred() {
             true => Value::Dyn {
@@ -265,12 +287,40 @@ impl DynValue for anathema_render::Color {
     fn resolve(value: &mut Value<Self>, context: &Context<'_, '_>, node_id: &NodeId) {
         if let Value::Dyn { inner, expr } = value {
             let mut resolver = Immediate::new(context.lookup(), node_id);
-            *inner = expr.eval(&mut resolver).try_into().ok()
+            *inner = eval_gradient(expr, &mut resolver);
         }
     }
 }
 
-// impl_dyn_value!(anathema_render::Color);
+// Values that are neither a direction nor a colour are ignored
+fn eval_gradient<'expr>(
+    expr: &'expr ValueExpr,
+    resolver: &mut impl Resolver<'expr>,
+) -> Option<Gradient> {
+    let values = match expr.eval(resolver) {
+        ValueRef::Expressions(Expressions(list)) => {
+            list.iter().map(|expr| expr.eval(resolver)).collect()
+        }
+        ValueRef::Empty => return None,
+        value => vec![value],
+    };
+
+    let mut direction = GradientDirection::default();
+    let mut stops = vec![];
+    for value in values {
+        match &value {
+            ValueRef::Str(s) if &**s == "horizontal" => direction = GradientDirection::Horizontal,
+            ValueRef::Str(s) if &**s == "vertical" => direction = GradientDirection::Vertical,
+            ValueRef::Str(s) if &**s == "diagonal" => direction = GradientDirection::Diagonal,
+            _ => stops.extend(anathema_render::Color::try_from(value).ok()),
+        }
+    }
+
+    match stops.is_empty() {
+        true => None,
+        false => Some(Gradient::new(stops, direction)),
+    }
+}
 
 impl_dyn_value!(usize);
 impl_dyn_value!(u64);
@@ -288,3 +338,4 @@ impl_dyn_value!(f64);
 impl_dyn_value!(f32);
 
 impl_dyn_value!(char);
+impl_dyn_value!(std::time::Duration);
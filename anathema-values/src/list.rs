@@ -3,13 +3,20 @@ use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::ops::{Deref, Index, IndexMut};
 
+use crate::collection::ChangeLog;
 use crate::state::State;
-use crate::{Change, Collection, NodeId, Path, StateValue, ValueRef, DIRTY_NODES};
+use crate::{Change, Collection, NodeId, Path, StateValue, ValueMut, ValueRef, DIRTY_NODES};
 
+/// A list of values.
+///
+/// The changes are recorded until they are drained with [`List::drain_changes`].
+///
+/// Changing a value through `list[index]` is not recorded, use [`List::get_mut`] instead.
 #[derive(Debug)]
 pub struct List<T> {
     inner: VecDeque<StateValue<T>>,
     subscribers: RefCell<Vec<NodeId>>,
+    log: ChangeLog,
 }
 
 impl<T> List<T> {
@@ -21,6 +28,7 @@ impl<T> List<T> {
         Self {
             inner: inner.into_iter().map(StateValue::new).collect(),
             subscribers: RefCell::new(vec![]),
+            log: ChangeLog::default(),
         }
     }
 
@@ -34,65 +42,83 @@ impl<T> List<T> {
 
     pub fn pop_front(&mut self) -> Option<StateValue<T>> {
         let ret = self.inner.pop_front()?;
-        let index = self.inner.len();
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::RemoveIndex(index)))
-            });
-        }
+        self.notify(Change::RemoveIndex(0));
         Some(ret)
     }
 
     pub fn pop_back(&mut self) -> Option<StateValue<T>> {
         let ret = self.inner.pop_back()?;
         let index = self.inner.len();
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::RemoveIndex(index)))
-            });
-        }
+        self.notify(Change::RemoveIndex(index));
         Some(ret)
     }
 
     pub fn remove(&mut self, index: usize) -> Option<StateValue<T>> {
         let ret = self.inner.remove(index);
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::RemoveIndex(index)))
-            });
-        }
+        self.notify(Change::RemoveIndex(index));
         ret
     }
 
     pub fn push_front(&mut self, value: T) {
         self.inner.push_front(StateValue::new(value));
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| nodes.borrow_mut().push((s.clone(), Change::InsertIndex(0))));
-        }
+        self.notify(Change::InsertIndex(0));
     }
 
     pub fn push_back(&mut self, value: T) {
         self.inner.push_back(StateValue::new(value));
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| nodes.borrow_mut().push((s.clone(), Change::Push)));
-        }
+        self.notify(Change::Push);
     }
 
     pub fn insert(&mut self, index: usize, value: T) {
         self.inner.insert(index, StateValue::new(value));
-
+        self.notify(Change::InsertIndex(index));
+    }
+
+    /// Get a mutable reference to a value.
+    ///
+    /// Changing the value is recorded as [`Change::UpdateIndex`],
+    /// while only reading it through the reference is not.
+    pub fn get_mut(&mut self, index: usize) -> Option<ValueMut<'_, T>> {
+        let value = self.inner.get_mut(index)?;
+        Some(ValueMut::new(
+            value,
+            &mut self.log,
+            Some(Change::UpdateIndex(index)),
+        ))
+    }
+
+    /// Drain the changes made to the list since the last drain.
+    ///
+    /// If the list changed too many times since the last drain, the changes are
+    /// replaced by a single [`Change::Update`], and the entire list should be read again.
+    ///
+    /// Changes to nested collections (e.g a `Map` inside the list) made through
+    /// [`List::get_mut`] are recorded as [`Change::UpdateIndex`], and the nested
+    /// collection records the change itself.
+    ///
+    /// ```
+    /// use anathema_values::{Change, List};
+    ///
+    /// let mut list = List::new(vec![1, 2]);
+    ///
+    /// list.push_back(3);
+    /// list.remove(0);
+    /// *list.get_mut(0).unwrap() += 1;
+    ///
+    /// let changes = list.drain_changes();
+    /// assert_eq!(
+    ///     changes,
+    ///     vec![Change::Push, Change::RemoveIndex(0), Change::UpdateIndex(0)]
+    /// );
+    /// ```
+    pub fn drain_changes(&mut self) -> Vec<Change> {
+        self.log.drain()
+    }
+
+    fn notify(&mut self, change: Change) {
+        self.log.push(&change);
         for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::InsertIndex(index)))
-            });
+            DIRTY_NODES.with(|nodes| nodes.borrow_mut().push((s.clone(), change.clone())));
         }
     }
 }
@@ -117,6 +143,10 @@ where
     fn subscribe(&self, node_id: NodeId) {
         self.subscribers.borrow_mut().push(node_id);
     }
+
+    fn generation(&self) -> u64 {
+        self.log.generation()
+    }
 }
 
 impl<T: Debug> State for List<T>
@@ -160,7 +190,7 @@ impl<T> IndexMut<usize> for List<T> {
 mod test {
     use super::*;
     use crate::testing::TestState;
-    use crate::Owned;
+    use crate::{Map, Owned};
 
     #[test]
     fn access_list() {
@@ -177,4 +207,49 @@ mod test {
     fn create_list() {
         let _list = List::new(vec![1, 2, 3]);
     }
+
+    #[test]
+    fn nested_change_bumps_generation() {
+        let mut list = List::new([Map::new([("name", "first".to_string())])]);
+        let generation = list.generation();
+
+        list.get_mut(0).unwrap().get_mut("name").unwrap().push('!');
+
+        assert_eq!(list.generation(), generation + 1);
+        assert_eq!(list.drain_changes(), vec![Change::UpdateIndex(0)]);
+        assert_eq!(
+            list[0].drain_changes(),
+            vec![Change::UpdateKey("name".into())]
+        );
+        assert_eq!(list[0].get("name").unwrap(), "first!");
+    }
+
+    #[test]
+    fn record_from_creation() {
+        let mut list = List::new([1usize]);
+        for n in 0..crate::collection::MAX_CHANGES {
+            list.push_back(n);
+        }
+        assert_eq!(list.drain_changes().len(), crate::collection::MAX_CHANGES);
+
+        // Too many changes to record
+        for n in 0..=crate::collection::MAX_CHANGES {
+            list.push_back(n);
+        }
+        assert_eq!(list.drain_changes(), vec![Change::Update]);
+    }
+
+    #[test]
+    fn reading_is_not_a_change() {
+        let mut list = List::new([1usize, 2]);
+
+        let _ = list[0];
+        assert_eq!(*list.get_mut(1).unwrap(), 2);
+        assert_eq!(list.generation(), 0);
+        assert_eq!(list.drain_changes(), vec![]);
+
+        *list.get_mut(1).unwrap() += 1;
+        assert_eq!(list.generation(), 1);
+        assert_eq!(list.drain_changes(), vec![Change::UpdateIndex(1)]);
+    }
 }
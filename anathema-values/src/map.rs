@@ -1,15 +1,27 @@
 use std::cell::RefCell;
 use std::fmt::Debug;
-use std::ops::{Deref, DerefMut};
+use std::ops::Deref;
 
+use crate::collection::ChangeLog;
 use crate::hashmap::HashMap;
 use crate::state::State;
-use crate::{Change, Collection, NodeId, Path, StateValue, ValueRef, DIRTY_NODES};
+use crate::{Change, Collection, NodeId, Path, StateValue, ValueMut, ValueRef, DIRTY_NODES};
 
+/// A map of values, in the order the keys were inserted.
+///
+/// Looking up a value by key is O(1), while removing a key is O(n)
+/// as the order of the remaining keys is preserved.
+///
+/// In a template a map is iterated over in insertion order, e.g `for value in map`.
+///
+/// The changes are recorded until they are drained with [`Map::drain_changes`].
 #[derive(Debug)]
 pub struct Map<T> {
     inner: HashMap<String, StateValue<T>>,
+    // The keys in insertion order
+    keys: Vec<String>,
     subscribers: RefCell<Vec<NodeId>>,
+    log: ChangeLog,
 }
 
 impl<T> Map<T> {
@@ -17,14 +29,21 @@ impl<T> Map<T> {
         Self::new::<String>(HashMap::new())
     }
 
+    /// Create a new map. If a key occurs more than once the last value is kept,
+    /// in the position of the first occurrence of the key.
     pub fn new<K: Into<String>>(inner: impl IntoIterator<Item = (K, T)>) -> Self {
-        let inner = inner
-            .into_iter()
-            .map(|(k, v)| (k.into(), StateValue::new(v)));
-        Self {
-            inner: HashMap::from_iter(inner),
+        let mut map = Self {
+            inner: HashMap::new(),
+            keys: vec![],
             subscribers: RefCell::new(vec![]),
+            log: ChangeLog::default(),
+        };
+
+        for (key, value) in inner {
+            map.insert_value(key.into(), value);
         }
+
+        map
     }
 
     pub fn subscribe(&self, node_id: NodeId) {
@@ -33,24 +52,52 @@ impl<T> Map<T> {
 
     pub fn remove(&mut self, key: String) -> Option<StateValue<T>> {
         let ret = self.inner.remove(&key);
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::RemoveKey(key.clone())))
-            });
+        if ret.is_some() {
+            self.keys.retain(|k| *k != key);
+            self.notify(Change::RemoveKey(key));
         }
         ret
     }
 
+    /// Insert a value. Replacing the value of an existing key keeps
+    /// the position of the key, and is recorded as [`Change::UpdateKey`].
     pub fn insert(&mut self, key: String, value: T) {
-        self.inner.insert(key.clone(), StateValue::new(value));
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::InsertKey(key.clone())))
-            });
+        let change = match self.insert_value(key.clone(), value) {
+            true => Change::UpdateKey(key),
+            false => Change::InsertKey(key),
+        };
+        self.notify(change);
+    }
+
+    // Returns true if the key already existed
+    fn insert_value(&mut self, key: String, value: T) -> bool {
+        match self.inner.get_mut(&key) {
+            Some(existing) => {
+                *existing = StateValue::new(value);
+                true
+            }
+            None => {
+                self.keys.push(key.clone());
+                self.inner.insert(key, StateValue::new(value));
+                false
+            }
+        }
+    }
+
+    /// Get the value of a key, inserting it if the key doesn't exist.
+    ///
+    /// ```
+    /// # use anathema_values::Map;
+    /// let mut map = Map::new([("a", 1)]);
+    /// *map.entry("a").or_insert(0) += 1;
+    /// *map.entry("b").or_insert(0) += 1;
+    /// assert_eq!(map.get("a"), Some(&2));
+    /// assert_eq!(map.get("b"), Some(&1));
+    /// ```
+    pub fn entry(&mut self, key: impl Into<String>) -> Entry<'_, T> {
+        Entry {
+            map: self,
+            key: key.into(),
         }
     }
 
@@ -58,8 +105,94 @@ impl<T> Map<T> {
         self.inner.get(key).map(|v| &v.inner)
     }
 
-    pub fn get_mut(&mut self, key: &str) -> Option<&mut T> {
-        self.inner.get_mut(key).map(|v| v.deref_mut())
+    /// Get a mutable reference to a value.
+    ///
+    /// Changing the value is recorded as [`Change::UpdateKey`],
+    /// while only reading it through the reference is not.
+    pub fn get_mut(&mut self, key: &str) -> Option<ValueMut<'_, T>> {
+        let value = self.inner.get_mut(key)?;
+        let change = Change::UpdateKey(key.to_string());
+        Some(ValueMut::new(value, &mut self.log, Some(change)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The keys, in insertion order
+    pub fn keys(&self) -> impl Iterator<Item = &str> + '_ {
+        self.keys.iter().map(String::as_str)
+    }
+
+    /// The keys and values, in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &T)> + '_ {
+        self.keys
+            .iter()
+            .map(|key| (key.as_str(), &self.inner[key].inner))
+    }
+
+    /// Drain the changes made to the map since the last drain.
+    ///
+    /// If the map changed too many times since the last drain, the changes are
+    /// replaced by a single [`Change::Update`], and the entire map should be read again.
+    ///
+    /// Changes to nested collections (e.g a `List` inside the map) made through
+    /// [`Map::get_mut`] are recorded as [`Change::UpdateKey`], and the nested
+    /// collection records the change itself.
+    pub fn drain_changes(&mut self) -> Vec<Change> {
+        self.log.drain()
+    }
+
+    fn notify(&mut self, change: Change) {
+        self.log.push(&change);
+        for s in self.subscribers.borrow_mut().drain(..) {
+            DIRTY_NODES.with(|nodes| nodes.borrow_mut().push((s.clone(), change.clone())));
+        }
+    }
+}
+
+/// A key in a [`Map`] that may or may not have a value, see [`Map::entry`].
+pub struct Entry<'a, T> {
+    map: &'a mut Map<T>,
+    key: String,
+}
+
+impl<'a, T> Entry<'a, T> {
+    /// A mutable reference to the value, inserting `value` if the key doesn't exist.
+    pub fn or_insert(self, value: T) -> ValueMut<'a, T> {
+        self.or_insert_with(|| value)
+    }
+
+    /// A mutable reference to the value, inserting the result of `f` if the key doesn't exist.
+    ///
+    /// Like [`Map::get_mut`], changing an existing value is recorded as [`Change::UpdateKey`].
+    pub fn or_insert_with(self, f: impl FnOnce() -> T) -> ValueMut<'a, T> {
+        let change = match self.map.inner.contains_key(&self.key) {
+            true => Some(Change::UpdateKey(self.key.clone())),
+            // The insert is already recorded
+            false => {
+                self.map.insert(self.key.clone(), f());
+                None
+            }
+        };
+
+        let value = self
+            .map
+            .inner
+            .get_mut(&self.key)
+            .expect("the key was just inserted");
+        ValueMut::new(value, &mut self.map.log, change)
+    }
+
+    pub fn or_default(self) -> ValueMut<'a, T>
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
     }
 }
 
@@ -78,11 +211,15 @@ where
     for<'a> &'a T: Into<ValueRef<'a>>,
 {
     fn len(&self) -> usize {
-        self.inner.len()
+        self.keys.len()
+    }
+
+    fn subscribe(&self, node_id: NodeId) {
+        Map::subscribe(self, node_id)
     }
 
-    fn subscribe(&self, _node_id: NodeId) {
-        todo!()
+    fn generation(&self) -> u64 {
+        self.log.generation()
     }
 }
 
@@ -104,9 +241,32 @@ where
                 ValueRef::List(collection) => collection.state_get(rhs, node_id),
                 _ => ValueRef::Empty,
             },
-            Path::Index(_) => ValueRef::Empty,
+            // The value at the index, in insertion order
+            Path::Index(index) => {
+                let Some(value) = self.keys.get(*index).map(|key| &self.inner[key]) else {
+                    return ValueRef::Empty;
+                };
+                value.subscribe(node_id.clone());
+                value.deref().into()
+            }
         }
     }
+
+    fn state_len(&self) -> Option<usize> {
+        Some(self.keys.len())
+    }
+
+    fn state_keys(&self) -> Option<Vec<String>> {
+        Some(self.keys.clone())
+    }
+
+    fn state_subscribe(&self, node_id: NodeId) {
+        self.subscribe(node_id)
+    }
+
+    fn state_generation(&self) -> u64 {
+        self.generation()
+    }
 }
 
 #[cfg(test)]
@@ -125,4 +285,88 @@ mod test {
         };
         assert_eq!(x.to_i128(), 2);
     }
+
+    #[test]
+    fn record_changes() {
+        let mut map = Map::new([("a", 1usize)]);
+
+        map.insert("b".into(), 2);
+        *map.get_mut("a").unwrap() += 1;
+        assert!(map.get_mut("does not exist").is_none());
+        map.remove("b".into());
+        // Nothing to remove
+        map.remove("b".into());
+
+        assert_eq!(
+            map.drain_changes(),
+            vec![
+                Change::InsertKey("b".into()),
+                Change::UpdateKey("a".into()),
+                Change::RemoveKey("b".into()),
+            ]
+        );
+        assert_eq!(map.generation(), 3);
+
+        map.insert("a".into(), 5);
+        assert_eq!(map.drain_changes(), vec![Change::UpdateKey("a".into())]);
+        assert_eq!(map.generation(), 4);
+
+        // Only reading the value is not a change
+        assert_eq!(*map.get_mut("a").unwrap(), 5);
+        assert_eq!(map.generation(), 4);
+        assert_eq!(map.drain_changes(), vec![]);
+    }
+
+    #[test]
+    fn nested_change_bumps_generation() {
+        let mut map = Map::new([("inner", Map::new([("a", 1usize)]))]);
+        let generation = map.generation();
+
+        *map.get_mut("inner").unwrap().get_mut("a").unwrap() += 1;
+        assert_eq!(map.generation(), generation + 1);
+        assert_eq!(map.drain_changes(), vec![Change::UpdateKey("inner".into())]);
+        assert_eq!(
+            map.get_mut("inner").unwrap().drain_changes(),
+            vec![Change::UpdateKey("a".into())]
+        );
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut map = Map::new([("b", 1usize), ("a", 2), ("c", 3)]);
+        map.insert("d".into(), 4);
+        // Replacing a value keeps the position
+        map.insert("b".into(), 5);
+        map.remove("a".into());
+
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec!["b", "c", "d"]);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![("b", &5), ("c", &3), ("d", &4)]
+        );
+        assert_eq!(map.state_keys().unwrap(), vec!["b", "c", "d"]);
+
+        let node_id = 0.into();
+        assert_eq!(
+            map.state_get(&Path::Index(1), &node_id),
+            ValueRef::Owned(3usize.into())
+        );
+        assert!(matches!(
+            map.state_get(&Path::Index(3), &node_id),
+            ValueRef::Empty
+        ));
+    }
+
+    #[test]
+    fn entry() {
+        let mut map = Map::new([("a", 1usize)]);
+
+        *map.entry("a").or_insert(0) += 1;
+        *map.entry("b").or_default() += 1;
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![("a", &2), ("b", &1)]);
+        assert_eq!(
+            map.drain_changes(),
+            vec![Change::UpdateKey("a".into()), Change::InsertKey("b".into())]
+        );
+    }
 }
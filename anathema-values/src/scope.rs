@@ -8,7 +8,7 @@ use crate::{NodeId, Path, ValueExpr, ValueRef};
 
 // Scopes can only borrow values with the same lifetime as an expressions.
 // Any scoped value that belongs to or contains state can only be scoped as deferred expressions.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum ScopeValue<'expr> {
     Value(ValueRef<'expr>),
     Deferred(&'expr ValueExpr),
@@ -24,7 +24,7 @@ impl<'expr> ScopeStorage<'expr> {
     }
 
     fn get(&self, lookup_path: &Path) -> Option<ScopeValue<'expr>> {
-        self.0.get(lookup_path).copied()
+        self.0.get(lookup_path).cloned()
     }
 
     pub fn insert(&mut self, path: impl Into<Path>, value: ScopeValue<'expr>) {
@@ -155,7 +155,7 @@ mod test {
     #[test]
     fn scope_value() {
         let mut store = ScopeStorage::new();
-        store.value("value", ValueRef::Str("hello world"));
+        store.value("value", "hello world".into());
         let scope = Scope {
             store: &store,
             parent: None,
@@ -163,7 +163,7 @@ mod test {
 
         {
             let mut store = ScopeStorage::new();
-            store.value("value", ValueRef::Str("inner hello"));
+            store.value("value", "inner hello".into());
             let scope = Scope {
                 store: &store,
                 parent: Some(&scope),
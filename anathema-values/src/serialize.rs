@@ -0,0 +1,377 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anathema_render::Color;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+
+use crate::{NodeId, Num, Owned, Path, ValueRef};
+
+// -----------------------------------------------------------------------------
+//   - Num -
+//   Numbers are serialized as the number they hold.
+// -----------------------------------------------------------------------------
+impl Serialize for Num {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Self::Signed(n) => serializer.serialize_i64(n),
+            Self::Unsigned(n) => serializer.serialize_u64(n),
+            Self::Float(n) => serializer.serialize_f64(n),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Num {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(OwnedVisitor)
+            .and_then(|owned| match owned {
+                Owned::Num(num) => Ok(num),
+                _ => Err(de::Error::custom("expected a number")),
+            })
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Owned -
+//   Owned values are serialized as the value they hold, without a tag.
+//   Colours are serialized as strings (e.g `"red"` or `"rgb_(255,0,0)"`).
+//
+//   As there is no tag, a string with a single character is deserialized
+//   as a char, and any other string as a colour.
+// -----------------------------------------------------------------------------
+impl Serialize for Owned {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Num(num) => num.serialize(serializer),
+            Self::Bool(b) => serializer.serialize_bool(*b),
+            Self::Char(c) => serializer.serialize_char(*c),
+            Self::Color(color) => color.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Owned {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(OwnedVisitor)
+    }
+}
+
+struct OwnedVisitor;
+
+impl<'de> Visitor<'de> for OwnedVisitor {
+    type Value = Owned;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a number, a bool, a char or a colour")
+    }
+
+    fn visit_bool<E: de::Error>(self, b: bool) -> Result<Owned, E> {
+        Ok(Owned::Bool(b))
+    }
+
+    fn visit_i64<E: de::Error>(self, n: i64) -> Result<Owned, E> {
+        Ok(Owned::Num(Num::Signed(n)))
+    }
+
+    fn visit_u64<E: de::Error>(self, n: u64) -> Result<Owned, E> {
+        Ok(Owned::Num(Num::Unsigned(n)))
+    }
+
+    fn visit_f64<E: de::Error>(self, n: f64) -> Result<Owned, E> {
+        Ok(Owned::Num(Num::Float(n)))
+    }
+
+    fn visit_char<E: de::Error>(self, c: char) -> Result<Owned, E> {
+        Ok(Owned::Char(c))
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Owned, E> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Owned::Char(c));
+        }
+
+        Color::deserialize(de::value::StrDeserializer::<E>::new(s)).map(Owned::Color)
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Owned value -
+// -----------------------------------------------------------------------------
+/// An owned copy of a [`ValueRef`], that can be serialized and deserialized.
+///
+/// A `ValueRef` borrows lists and maps from the state, so it can't be deserialized.
+/// Use [`OwnedValue::resolve`] to copy a value (e.g a snapshot of the state),
+/// and deserialize into an `OwnedValue` to read it back.
+///
+/// * `Empty` is serialized as null, and is left out of maps
+/// * Strings are always deserialized as `Str`, never as a char or a colour
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum OwnedValue {
+    Str(String),
+    Owned(Owned),
+    List(Vec<OwnedValue>),
+    Map(BTreeMap<String, OwnedValue>),
+    #[default]
+    Empty,
+}
+
+impl OwnedValue {
+    /// Copy a value, resolving lists and maps into owned values.
+    ///
+    /// * The keys of a map are only known for a [`Map`](crate::Map), and any state using
+    ///   `#[derive(State)]`. Any other map resolves to `Empty`
+    /// * Deferred values and unevaluated expressions resolve to `Empty`
+    ///
+    /// The `node_id` is subscribed to any value it reads.
+    pub fn resolve(value: ValueRef<'_>, node_id: &NodeId) -> Self {
+        match value {
+            ValueRef::Str(s) => Self::Str(s.into_owned()),
+            ValueRef::Owned(owned) => Self::Owned(owned),
+            ValueRef::List(list) => Self::List(
+                list.iter(node_id)
+                    .map(|value| Self::resolve(value, node_id))
+                    .collect(),
+            ),
+            ValueRef::Map(state) => match state.state_keys() {
+                Some(keys) => Self::Map(
+                    keys.into_iter()
+                        .filter_map(|key| {
+                            let value = state.state_get(&Path::from(key.as_str()), node_id);
+                            match Self::resolve(value, node_id) {
+                                Self::Empty => None,
+                                value => Some((key, value)),
+                            }
+                        })
+                        .collect(),
+                ),
+                None => Self::Empty,
+            },
+            ValueRef::Expressions(_)
+            | ValueRef::ExpressionMap(_)
+            | ValueRef::Deferred
+            | ValueRef::Empty => Self::Empty,
+        }
+    }
+}
+
+impl Serialize for OwnedValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Str(s) => serializer.serialize_str(s),
+            Self::Owned(owned) => owned.serialize(serializer),
+            Self::List(list) => {
+                let mut seq = serializer.serialize_seq(Some(list.len()))?;
+                for value in list {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            Self::Map(map) => {
+                let entries = map.iter().filter(|(_, value)| **value != Self::Empty);
+                let mut ser = serializer.serialize_map(Some(entries.clone().count()))?;
+                for (key, value) in entries {
+                    ser.serialize_entry(key, value)?;
+                }
+                ser.end()
+            }
+            Self::Empty => serializer.serialize_unit(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OwnedValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(OwnedValueVisitor)
+    }
+}
+
+struct OwnedValueVisitor;
+
+impl<'de> Visitor<'de> for OwnedValueVisitor {
+    type Value = OwnedValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a value")
+    }
+
+    fn visit_bool<E: de::Error>(self, b: bool) -> Result<OwnedValue, E> {
+        OwnedVisitor.visit_bool(b).map(OwnedValue::Owned)
+    }
+
+    fn visit_i64<E: de::Error>(self, n: i64) -> Result<OwnedValue, E> {
+        OwnedVisitor.visit_i64(n).map(OwnedValue::Owned)
+    }
+
+    fn visit_u64<E: de::Error>(self, n: u64) -> Result<OwnedValue, E> {
+        OwnedVisitor.visit_u64(n).map(OwnedValue::Owned)
+    }
+
+    fn visit_f64<E: de::Error>(self, n: f64) -> Result<OwnedValue, E> {
+        OwnedVisitor.visit_f64(n).map(OwnedValue::Owned)
+    }
+
+    fn visit_char<E: de::Error>(self, c: char) -> Result<OwnedValue, E> {
+        OwnedVisitor.visit_char(c).map(OwnedValue::Owned)
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<OwnedValue, E> {
+        Ok(OwnedValue::Str(s.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, s: String) -> Result<OwnedValue, E> {
+        Ok(OwnedValue::Str(s))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<OwnedValue, E> {
+        Ok(OwnedValue::Empty)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<OwnedValue, E> {
+        Ok(OwnedValue::Empty)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<OwnedValue, D::Error> {
+        OwnedValue::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<OwnedValue, A::Error> {
+        let mut list = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            list.push(value);
+        }
+        Ok(OwnedValue::List(list))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<OwnedValue, A::Error> {
+        let mut map = BTreeMap::new();
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value);
+        }
+        Ok(OwnedValue::Map(map))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::de::IntoDeserializer;
+
+    use super::*;
+    use crate::testing::TestState;
+    use crate::{deserialize, List, Map, State};
+
+    type ValueError = de::value::Error;
+
+    fn num(n: impl Into<Num>) -> OwnedValue {
+        OwnedValue::Owned(Owned::Num(n.into()))
+    }
+
+    fn str(s: &str) -> OwnedValue {
+        OwnedValue::Str(s.to_string())
+    }
+
+    fn to_string(value: &impl Serialize) -> String {
+        serde_json::to_string(value).unwrap()
+    }
+
+    #[test]
+    fn serialize_owned() {
+        assert_eq!(to_string(&Num::Signed(-1)), "-1");
+        assert_eq!(to_string(&Num::Float(1.5)), "1.5");
+        assert_eq!(to_string(&Owned::Bool(true)), "true");
+        assert_eq!(to_string(&Owned::Char('a')), "\"a\"");
+        assert_eq!(to_string(&Owned::Color(Color::Red)), "\"red\"");
+    }
+
+    #[test]
+    fn deserialize_owned() {
+        let owned =
+            |s: &str| -> Result<_, ValueError> { Owned::deserialize(s.into_deserializer()) };
+
+        assert_eq!(
+            Owned::deserialize(1u64.into_deserializer()),
+            Ok::<_, ValueError>(Owned::Num(Num::Unsigned(1)))
+        );
+        assert_eq!(owned("a"), Ok(Owned::Char('a')));
+        assert_eq!(owned("dark_red"), Ok(Owned::Color(Color::DarkRed)));
+        assert!(owned("not a colour").is_err());
+
+        let num = Num::deserialize(2.5f64.into_deserializer());
+        assert_eq!(num, Ok::<_, ValueError>(Num::Float(2.5)));
+        let num: Result<_, ValueError> = Num::deserialize(true.into_deserializer());
+        assert!(num.is_err());
+    }
+
+    #[test]
+    fn resolve_state() {
+        let state = TestState::new();
+        let value = OwnedValue::resolve(ValueRef::Map(&state), &NodeId::new(0));
+
+        let OwnedValue::Map(map) = &value else {
+            panic!("expected a map")
+        };
+        assert_eq!(map["name"], str("Dirk Gently"));
+        assert_eq!(map["counter"], num(3u8));
+        assert_eq!(
+            map["generic_list"],
+            OwnedValue::List(vec![num(1u8), num(2u8), num(3u8)])
+        );
+        let OwnedValue::Map(inner) = &map["generic_map"] else {
+            panic!("expected a map")
+        };
+        assert_eq!(
+            inner["inner"],
+            OwnedValue::Map(BTreeMap::from([
+                ("first".into(), num(1u8)),
+                ("second".into(), num(2u8)),
+            ]))
+        );
+    }
+
+    #[test]
+    fn serialize_value() {
+        let list = Map::new([("a", List::new(vec![1u8, 2])), ("b", List::new(vec![]))]);
+        let value = OwnedValue::resolve(ValueRef::Map(&list), &NodeId::new(0));
+        assert_eq!(to_string(&value), "{\"a\":[1,2],\"b\":[]}");
+
+        let mut map = BTreeMap::new();
+        map.insert("empty".to_string(), OwnedValue::Empty);
+        map.insert("name".to_string(), str("Arthur"));
+        assert_eq!(to_string(&OwnedValue::Map(map)), "{\"name\":\"Arthur\"}");
+        assert_eq!(to_string(&OwnedValue::Empty), "null");
+    }
+
+    #[test]
+    fn round_trip() {
+        let state = TestState::new();
+        let value = OwnedValue::resolve(ValueRef::Map(&state), &NodeId::new(0));
+        let round_trip: OwnedValue = deserialize(ValueRef::Map(&state), &NodeId::new(0)).unwrap();
+        assert_eq!(value, round_trip);
+
+        let map = BTreeMap::from([("a", vec![1u64, 2])]).into_deserializer();
+        let value = OwnedValue::deserialize::<de::value::MapDeserializer<'_, _, ValueError>>(map);
+        let expected = BTreeMap::from([("a".into(), OwnedValue::List(vec![num(1u8), num(2u8)]))]);
+        assert_eq!(value.unwrap(), OwnedValue::Map(expected));
+
+        let value = OwnedValue::deserialize(().into_deserializer());
+        assert_eq!(value, Ok::<_, ValueError>(OwnedValue::Empty));
+    }
+
+    #[test]
+    fn unresolved_values_are_empty() {
+        let node_id = NodeId::new(0);
+        assert_eq!(
+            OwnedValue::resolve(ValueRef::Deferred, &node_id),
+            OwnedValue::Empty
+        );
+        assert_eq!(
+            OwnedValue::resolve(ValueRef::Map(&()), &node_id),
+            OwnedValue::Empty
+        );
+
+        let state = TestState::new();
+        let missing = state.state_get(&Path::from("missing"), &node_id);
+        assert_eq!(OwnedValue::resolve(missing, &node_id), OwnedValue::Empty);
+    }
+}
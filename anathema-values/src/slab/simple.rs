@@ -70,7 +70,7 @@ impl<T> Slab<T> {
                     }
                     Entry::Vacant(next_id) => {
                         self.next_id = next_id.take();
-                        std::mem::swap(entry, &mut Entry::Occupied(val));
+                        *entry = Entry::Occupied(val);
                         index
                     }
                 }
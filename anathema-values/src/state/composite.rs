@@ -0,0 +1,112 @@
+use super::State;
+use crate::{NodeId, Path, ValueRef};
+
+/// Layer multiple states so they resolve as if they were one.
+///
+/// The states are tried in the order they were added, and the first
+/// value that isn't `ValueRef::Empty` is returned.
+/// This means the first state has the highest precedence:
+///
+/// ```
+/// use anathema_values::{CompositeState, Map, NodeId, Path, State};
+///
+/// let local = Map::new([("title", "local".to_string())]);
+/// let global = Map::new([
+///     ("title", "global".to_string()),
+///     ("theme", "dark".to_string()),
+/// ]);
+///
+/// let state = CompositeState::new([&local as &dyn State, &global]);
+/// let node_id = NodeId::new(0);
+///
+/// let title = state.state_get(&Path::from("title"), &node_id);
+/// assert_eq!(title.as_str(), Some("local"));
+///
+/// let theme = state.state_get(&Path::from("theme"), &node_id);
+/// assert_eq!(theme.as_str(), Some("dark"));
+/// ```
+#[derive(Default)]
+pub struct CompositeState<'a> {
+    states: Vec<&'a dyn State>,
+}
+
+impl<'a> CompositeState<'a> {
+    /// Create a composite state, where the first state has the highest precedence.
+    pub fn new(states: impl IntoIterator<Item = &'a dyn State>) -> Self {
+        Self {
+            states: states.into_iter().collect(),
+        }
+    }
+
+    /// Add a state with a lower precedence than all the existing states.
+    pub fn push(&mut self, state: &'a dyn State) {
+        self.states.push(state);
+    }
+}
+
+impl State for CompositeState<'_> {
+    fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_> {
+        self.states
+            .iter()
+            .map(|state| state.state_get(key, node_id))
+            .find(|value| !matches!(value, ValueRef::Empty))
+            .unwrap_or_default()
+    }
+
+    // The keys of all the states, or `None` if the keys of any state are unknown
+    fn state_keys(&self) -> Option<Vec<String>> {
+        let mut keys = vec![];
+        for state in &self.states {
+            for key in state.state_keys()? {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        Some(keys)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestState;
+    use crate::Map;
+
+    #[test]
+    fn first_state_wins() {
+        let local = Map::new([("name", "Arthur".to_string())]);
+        let global = TestState::new();
+        let state = CompositeState::new([&local as &dyn State, &global]);
+        let node_id = NodeId::new(0);
+
+        let name = state.state_get(&Path::from("name"), &node_id);
+        assert_eq!(name.as_str(), Some("Arthur"));
+
+        let counter = state.state_get(&Path::from("counter"), &node_id);
+        assert_eq!(counter, ValueRef::Owned(3usize.into()));
+
+        let missing = state.state_get(&Path::from("missing"), &node_id);
+        assert!(matches!(missing, ValueRef::Empty));
+    }
+
+    #[test]
+    fn union_of_keys() {
+        let local = Map::new([("name", 1usize), ("title", 2)]);
+        let global = Map::new([("theme", 3usize), ("name", 4)]);
+        let state = CompositeState::new([&local as &dyn State, &global]);
+
+        // Duplicate keys are only included once, in the position of the first state
+        assert_eq!(state.state_keys().unwrap(), vec!["name", "title", "theme"]);
+
+        // The keys of a list are unknown
+        let list = vec![1usize];
+        let state = CompositeState::new([&local as &dyn State, &list]);
+        assert!(state.state_keys().is_none());
+
+        assert_eq!(
+            CompositeState::default().state_keys().unwrap(),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -7,9 +7,11 @@
 //   State owned by the `View` is referred to as Internal State.
 //   State passed to the `View` is External State.
 // -----------------------------------------------------------------------------
+pub use self::composite::CompositeState;
 pub use self::value::{Change, StateValue};
 use crate::{NodeId, Path, ValueRef};
 
+mod composite;
 mod value;
 
 /// Represents the internal state of a view
@@ -21,6 +23,33 @@ mod value;
 ///     value: StateValue<String>,
 /// }
 /// ```
+///
+/// A derived state can also have computed fields, which are methods on the state
+/// that are called every time the field is read.
+/// The method is given the id of the node reading the value, and should
+/// subscribe the node to the values it depends on, so the node is updated
+/// when any of them change.
+///
+/// Computed values are not stored, so only a computed field that produces a
+/// new string (rather than borrowing one from the state) allocates.
+/// ```ignore
+/// use anathema::values::{NodeId, State, StateValue, ValueRef};
+///
+/// #[derive(State)]
+/// #[state(computed(full_name))]
+/// struct Person {
+///     first: StateValue<String>,
+///     last: StateValue<String>,
+/// }
+///
+/// impl Person {
+///     fn full_name(&self, node_id: &NodeId) -> ValueRef<'_> {
+///         self.first.subscribe(node_id.clone());
+///         self.last.subscribe(node_id.clone());
+///         format!("{} {}", *self.first, *self.last).into()
+///     }
+/// }
+/// ```
 pub trait State {
     #[doc(hidden)]
     fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_>;
@@ -32,6 +61,42 @@ pub trait State {
     {
         ValueRef::Map(self)
     }
+
+    /// The number of values, if the state is a collection of values (e.g a `Map`).
+    /// This is used by `len(map)`, and an empty state is false in a template, e.g `if map`.
+    #[doc(hidden)]
+    fn state_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// The keys of the state, if they are known.
+    /// This is used to resolve a state into an owned value (e.g to serialize it).
+    #[doc(hidden)]
+    fn state_keys(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Subscribe a node to changes of the state, if the state is a collection of values.
+    /// This is used by a `for` loop over a `Map`.
+    #[doc(hidden)]
+    fn state_subscribe(&self, _node_id: NodeId) {}
+
+    /// Receive any pending values, if the state is fed by a channel (see [`ChannelCollection`]).
+    /// This is called by the runtime at the start of every frame.
+    ///
+    /// [`ChannelCollection`]: crate::ChannelCollection
+    #[doc(hidden)]
+    fn state_poll(&mut self) {}
+
+    /// The generation of the state, if the state is a collection of values
+    /// (see [`Collection::generation`]).
+    /// This is used to notice changes to a `Map` inside a `List` or another `Map`.
+    ///
+    /// [`Collection::generation`]: crate::Collection::generation
+    #[doc(hidden)]
+    fn state_generation(&self) -> u64 {
+        0
+    }
 }
 
 /// This exists so you can have a view with a default state of a unit
@@ -40,3 +105,43 @@ impl State for () {
         ValueRef::Empty
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{drain_dirty_nodes, Change, StateValue};
+
+    #[derive(crate::State)]
+    #[state(computed(full_name))]
+    struct Person {
+        first: StateValue<String>,
+        last: StateValue<String>,
+    }
+
+    impl Person {
+        fn full_name(&self, node_id: &NodeId) -> ValueRef<'_> {
+            self.first.subscribe(node_id.clone());
+            self.last.subscribe(node_id.clone());
+            format!("{} {}", *self.first, *self.last).into()
+        }
+    }
+
+    #[test]
+    fn computed_field() {
+        let mut person = Person {
+            first: StateValue::new("Arthur".into()),
+            last: StateValue::new("Dent".into()),
+        };
+        let node_id = NodeId::new(0);
+
+        let value = person.state_get(&Path::from("full_name"), &node_id);
+        assert_eq!(value, ValueRef::Str("Arthur Dent".into()));
+        assert_eq!(person.state_keys().unwrap(), ["first", "last", "full_name"]);
+
+        // Reading the computed field subscribes the node to the fields it depends on
+        *person.first = "Ford".into();
+        assert_eq!(drain_dirty_nodes(), [(node_id.clone(), Change::Update)]);
+        let value = person.state_get(&Path::from("full_name"), &node_id);
+        assert_eq!(value, ValueRef::Str("Ford Dent".into()));
+    }
+}
@@ -23,6 +23,10 @@ pub enum Change {
     InsertKey(String),
     RemoveIndex(usize),
     RemoveKey(String),
+    /// The value at the index was changed through a mutable reference
+    UpdateIndex(usize),
+    /// The value of the key was changed through a mutable reference
+    UpdateKey(String),
 }
 
 #[derive(Debug, Default)]
@@ -44,6 +48,9 @@ impl<T> StateValue<T> {
         ValueRef::Empty
     }
 
+    #[doc(hidden)]
+    pub fn state_poll(&mut self) {}
+
     pub fn subscribe(&self, subscriber: NodeId) {
         self.subscribers.borrow_mut().insert(subscriber);
     }
@@ -85,7 +92,7 @@ impl<T> From<T> for StateValue<T> {
 
 impl<'a> From<&'a StateValue<String>> for ValueRef<'a> {
     fn from(value: &'a StateValue<String>) -> Self {
-        ValueRef::Str(value.inner.as_str())
+        value.inner.as_str().into()
     }
 }
 
@@ -102,6 +109,14 @@ impl<T: State> State for StateValue<T> {
     fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_> {
         self.inner.state_get(key, node_id)
     }
+
+    fn state_len(&self) -> Option<usize> {
+        self.inner.state_len()
+    }
+
+    fn state_keys(&self) -> Option<Vec<String>> {
+        self.inner.state_keys()
+    }
 }
 
 #[cfg(test)]
@@ -22,6 +22,7 @@ pub struct TestState {
     pub counter: StateValue<usize>,
     pub inner: Inner,
     pub generic_map: Map<Map<usize>>,
+    pub ordered_map: Map<usize>,
     pub generic_list: List<usize>,
     pub nested_list: List<List<usize>>,
     pub debug: StateValue<bool>,
@@ -34,6 +35,7 @@ impl TestState {
             counter: StateValue::new(3),
             inner: Inner::new(),
             generic_map: Map::new([("inner", Map::new([("first", 1), ("second", 2)]))]),
+            ordered_map: Map::new([("c", 3), ("a", 1), ("b", 2)]),
             generic_list: List::new(vec![1, 2, 3]),
             nested_list: List::new(vec![List::new(vec![1, 2, 3])]),
             debug: StateValue::new(false),
@@ -84,6 +86,16 @@ where
         };
         assert_eq!(owned, expected.into())
     }
+
+    pub fn expect_empty(&self) {
+        let context = Context::root(&self.state);
+        let mut resolver = Immediate::new(context.lookup(), &self.node_id);
+        let val = self.expr.eval(&mut resolver);
+        assert!(
+            matches!(val, ValueRef::Empty),
+            "expected an empty value, found {val:?}"
+        );
+    }
 }
 
 impl ValueExpr {
@@ -210,3 +222,11 @@ pub fn and(lhs: Box<ValueExpr>, rhs: Box<ValueExpr>) -> Box<ValueExpr> {
 pub fn or(lhs: Box<ValueExpr>, rhs: Box<ValueExpr>) -> Box<ValueExpr> {
     ValueExpr::Or(lhs, rhs).into()
 }
+
+// -----------------------------------------------------------------------------
+//   - Functions -
+// -----------------------------------------------------------------------------
+pub fn call(fun: &str, args: impl IntoIterator<Item = Box<ValueExpr>>) -> Box<ValueExpr> {
+    let args = args.into_iter().map(|arg| *arg).collect();
+    ValueExpr::Call(fun.into(), args).into()
+}
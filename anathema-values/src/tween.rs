@@ -0,0 +1,229 @@
+use std::fmt::{self, Debug};
+use std::time::Duration;
+
+use crate::{Num, Owned, ValueRef};
+
+/// The rate of change of a [`Tween`] over time.
+#[derive(Debug, Copy, Clone)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Start slow and speed up
+    EaseIn,
+    /// Start fast and slow down
+    EaseOut,
+    /// Start slow, speed up, and slow down towards the end
+    EaseInOut,
+    /// Map the progress (from zero to one) to the eased progress
+    Custom(fn(f64) -> f64),
+}
+
+impl Easing {
+    fn apply(self, t: f64) -> f64 {
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::EaseInOut if t < 0.5 => 2.0 * t * t,
+            Self::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0,
+            Self::Custom(f) => f(t),
+        }
+    }
+}
+
+/// Interpolate a number from `start` to `end` over a duration.
+///
+/// The tween is advanced with the time since the last frame,
+/// e.g from [`View::animate`](../anathema_widget_core/views/trait.View.html#method.animate):
+///
+/// ```
+/// # use std::time::Duration;
+/// # use anathema_values::{Num, Tween};
+/// let mut tween = Tween::new(0u8, 10u8, Duration::from_secs(1));
+/// tween.advance(Duration::from_millis(250));
+/// assert_eq!(tween.value(), Num::Float(2.5));
+///
+/// tween.advance(Duration::from_secs(5));
+/// assert!(tween.is_done());
+/// assert_eq!(tween.value(), Num::Unsigned(10));
+/// ```
+///
+/// The intermediate values follow the rules of the arithmetic on [`Num`]:
+/// they are floats, while the start and end values keep their variant.
+pub struct Tween {
+    start: Num,
+    end: Num,
+    duration: Duration,
+    elapsed: Duration,
+    easing: Easing,
+    on_complete: Option<Box<dyn FnMut() + Send>>,
+    completed: bool,
+}
+
+impl Tween {
+    /// Create a new linear tween.
+    pub fn new(start: impl Into<Num>, end: impl Into<Num>, duration: Duration) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+            duration,
+            elapsed: Duration::ZERO,
+            easing: Easing::Linear,
+            on_complete: None,
+            completed: false,
+        }
+    }
+
+    /// Set the easing of the tween.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Call `f` once the tween completes, e.g to emit an event with an [`Emitter`](../anathema_runtime/struct.Emitter.html).
+    ///
+    /// The function is called again if the tween is restarted and completes again.
+    pub fn on_complete(mut self, f: impl FnMut() + Send + 'static) -> Self {
+        self.on_complete = Some(Box::new(f));
+        self
+    }
+
+    /// Advance the tween by `delta`.
+    pub fn advance(&mut self, delta: Duration) {
+        self.elapsed = self.elapsed.saturating_add(delta).min(self.duration);
+
+        if self.is_done() && !self.completed {
+            self.completed = true;
+            if let Some(f) = self.on_complete.as_mut() {
+                f();
+            }
+        }
+    }
+
+    /// Start over from the beginning.
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.completed = false;
+    }
+
+    /// The progress of the tween, from zero to one, before easing.
+    pub fn progress(&self) -> f64 {
+        match self.duration.is_zero() {
+            true => 1.0,
+            false => self.elapsed.as_secs_f64() / self.duration.as_secs_f64(),
+        }
+    }
+
+    /// True once the duration has elapsed.
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// The current value.
+    pub fn value(&self) -> Num {
+        let progress = self.progress();
+        if progress <= 0.0 {
+            return self.start;
+        }
+
+        if progress >= 1.0 {
+            return self.end;
+        }
+
+        let t = Num::Float(self.easing.apply(progress));
+        self.start + (self.end - self.start) * t
+    }
+
+    /// The current value as a [`ValueRef`].
+    pub fn value_ref(&self) -> ValueRef<'static> {
+        ValueRef::Owned(Owned::Num(self.value()))
+    }
+}
+
+impl Debug for Tween {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tween")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("duration", &self.duration)
+            .field("elapsed", &self.elapsed)
+            .field("easing", &self.easing)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn linear() {
+        let mut tween = Tween::new(10u8, 20u8, ms(100));
+        assert!(matches!(tween.value(), Num::Unsigned(10)));
+
+        tween.advance(ms(50));
+        assert_eq!(tween.value(), Num::Float(15.0));
+
+        tween.advance(ms(50));
+        assert!(matches!(tween.value(), Num::Unsigned(20)));
+    }
+
+    #[test]
+    fn unsigned_towards_zero() {
+        let mut tween = Tween::new(10u8, 0u8, ms(100));
+        tween.advance(ms(25));
+        assert_eq!(tween.value(), Num::Float(7.5));
+    }
+
+    #[test]
+    fn easing() {
+        let tween = |easing| {
+            let mut tween = Tween::new(0, 100, ms(100)).with_easing(easing);
+            tween.advance(ms(25));
+            tween.value().to_f64()
+        };
+
+        assert_eq!(tween(Easing::Linear), 25.0);
+        assert_eq!(tween(Easing::EaseIn), 6.25);
+        assert_eq!(tween(Easing::EaseOut), 43.75);
+        assert_eq!(tween(Easing::EaseInOut), 12.5);
+        assert_eq!(tween(Easing::Custom(|_| 0.5)), 50.0);
+    }
+
+    #[test]
+    fn complete_once() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut tween = Tween::new(0, 1, ms(10)).on_complete({
+            let count = count.clone();
+            move || {
+                count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        tween.advance(ms(5));
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        tween.advance(ms(5));
+        tween.advance(ms(5));
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+
+        tween.restart();
+        assert!(!tween.is_done());
+        tween.advance(ms(10));
+        assert_eq!(count.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn zero_duration() {
+        let mut tween = Tween::new(1.5, 3, Duration::ZERO);
+        assert!(tween.is_done());
+        assert!(matches!(tween.value(), Num::Signed(3) | Num::Unsigned(3)));
+        tween.advance(Duration::ZERO);
+    }
+}
@@ -1,14 +1,17 @@
 #![allow(clippy::from_over_into)]
 
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::fmt::{Debug, Formatter};
+use std::time::Duration;
 
-use anathema_render::Color;
+use anathema_render::{Color, ColorExt};
 
-pub use self::num::Num;
+pub use self::num::{Num, ParseNumError};
 pub use self::owned::Owned;
 use crate::hashmap::HashMap;
 use crate::map::Map;
-use crate::{Collection, List, State, ValueExpr};
+use crate::{Collection, Context, Immediate, List, NodeId, Resolver, State, ValueExpr};
 
 mod num;
 mod owned;
@@ -45,9 +48,12 @@ impl<'a> ExpressionMap<'a> {
 // -----------------------------------------------------------------------------
 /// A value reference is either owned or referencing something
 /// inside an expression.
-#[derive(Clone, Copy, Default)]
+///
+/// Strings are either borrowed from an expression / state, or
+/// owned when produced during evaluation.
+#[derive(Clone, Default)]
 pub enum ValueRef<'a> {
-    Str(&'a str),
+    Str(Cow<'a, str>),
     Map(&'a dyn State),
     List(&'a dyn Collection),
     Expressions(Expressions<'a>),
@@ -55,21 +61,130 @@ pub enum ValueRef<'a> {
     Owned(Owned),
     /// * This should only ever occur when using a deferred resolver.
     /// * A state should never return a deferred value.
+    ///
+    /// Use [`ValueRef::resolve_deferred`] to resolve the value.
     Deferred,
     #[default]
     Empty,
 }
 
 impl<'a> ValueRef<'a> {
+    /// The truthiness of a value, as used by `if` and the logical operators.
+    ///
+    /// * Strings, lists and maps are true if they are not empty
+    /// * Floats are true if they are not zero (and not NaN)
+    /// * Empty and deferred values are always false
     pub fn is_true(&self) -> bool {
         match self {
             Self::Str(s) => !s.is_empty(),
             Self::Owned(Owned::Bool(b)) => *b,
             Self::Owned(Owned::Num(Num::Unsigned(n))) => *n > 0,
             Self::Owned(Owned::Num(Num::Signed(n))) => *n > 0,
+            Self::Owned(Owned::Num(Num::Float(n))) => *n != 0.0 && !n.is_nan(),
+            Self::List(list) => list.len() > 0,
+            Self::Expressions(list) => !list.0.is_empty(),
+            Self::Map(map) => map.state_len() != Some(0),
+            Self::ExpressionMap(map) => !map.0.is_empty(),
             _ => false,
         }
     }
+
+    /// The string value, if this is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Resolve a deferred value by evaluating the expression that produced it
+    /// with the given resolver (e.g [`Immediate`]).
+    /// Any value that isn't deferred is returned as is.
+    ///
+    /// The resolved value borrows from the resolver, so a list or a map
+    /// lives as long as the state it was resolved from.
+    pub fn resolve_deferred<'expr>(
+        self,
+        expr: &'expr ValueExpr,
+        resolver: &mut impl Resolver<'expr>,
+    ) -> ValueRef<'expr>
+    where
+        'a: 'expr,
+    {
+        match self {
+            Self::Deferred => expr.eval(resolver),
+            value => value,
+        }
+    }
+
+    /// Follow a path of keys through nested maps and lists (e.g `["user", "address", "city"]`).
+    /// Segments that parse as a number are used as indices into lists.
+    ///
+    /// Returns `Empty` if a segment is missing, or if a value along the path
+    /// is neither a map nor a list.
+    ///
+    /// Like [`ValueRef::resolve_deferred`] the value borrows from the resolver,
+    /// so it lives as long as the map or list it was found in.
+    pub fn lookup_path<'expr>(
+        &self,
+        path: &[&str],
+        resolver: &mut impl Resolver<'expr>,
+    ) -> ValueRef<'expr>
+    where
+        'a: 'expr,
+    {
+        let mut value: ValueRef<'expr> = self.clone();
+
+        for segment in path {
+            value = match value {
+                ValueRef::Map(map) => resolver.resolve_map(map, segment),
+                ValueRef::ExpressionMap(ExpressionMap(map)) => match map.get(*segment) {
+                    Some(expr) => expr.eval(resolver),
+                    None => ValueRef::Empty,
+                },
+                ValueRef::List(list) => match segment.parse() {
+                    Ok(index) if index < list.len() => resolver.resolve_list(list, index),
+                    _ => ValueRef::Empty,
+                },
+                ValueRef::Expressions(expressions) => {
+                    match segment.parse().ok().and_then(|i| expressions.get(i)) {
+                        Some(expr) => expr.eval(resolver),
+                        None => ValueRef::Empty,
+                    }
+                }
+                ValueRef::Deferred => return ValueRef::Deferred,
+                _ => return ValueRef::Empty,
+            };
+        }
+
+        value
+    }
+
+    /// Convert a list into a `Vec<T>`, converting each value with `T::try_from`.
+    /// Returns an error if this isn't a list, or if any of the values can't be converted.
+    ///
+    /// Expressions (e.g a list in a template) are evaluated before they are converted.
+    // The error is `()`, the same as the conversions from a `ValueRef`
+    #[allow(clippy::result_unit_err)]
+    pub fn into_vec<T>(self, context: &Context<'_, '_>, node_id: &NodeId) -> Result<Vec<T>, ()>
+    where
+        T: for<'b> TryFrom<ValueRef<'b>>,
+    {
+        match self {
+            Self::List(collection) => collection
+                .iter(node_id)
+                .map(|value| T::try_from(value).map_err(|_| ()))
+                .collect(),
+            Self::Expressions(Expressions(expressions)) => expressions
+                .iter()
+                .map(|expr| {
+                    let mut resolver = Immediate::new(context.lookup(), node_id);
+                    T::try_from(expr.eval(&mut resolver)).map_err(|_| ())
+                })
+                .collect(),
+            _ => Err(()),
+        }
+    }
 }
 
 impl Debug for ValueRef<'_> {
@@ -97,17 +212,45 @@ impl<'a> PartialEq for ValueRef<'a> {
     }
 }
 
+/// Numbers are ordered by value (regardless of the type of number),
+/// strings are ordered lexicographically and `false` is less than `true`.
+///
+/// Any other combination of values, including `Empty` and `Deferred`, can not be compared.
+impl<'a> PartialOrd for ValueRef<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Str(lhs), Self::Str(rhs)) => lhs.partial_cmp(rhs),
+            (Self::Owned(Owned::Num(lhs)), Self::Owned(Owned::Num(rhs))) => lhs.partial_cmp(rhs),
+            (Self::Owned(Owned::Bool(lhs)), Self::Owned(Owned::Bool(rhs))) => lhs.partial_cmp(rhs),
+            (Self::Owned(Owned::Char(lhs)), Self::Owned(Owned::Char(rhs))) => lhs.partial_cmp(rhs),
+            _ => None,
+        }
+    }
+}
+
 // -----------------------------------------------------------------------------
 //   - From for value ref -
 // -----------------------------------------------------------------------------
 
 impl<'a> Into<ValueRef<'a>> for &'a String {
     fn into(self) -> ValueRef<'a> {
-        ValueRef::Str(self)
+        ValueRef::Str(Cow::Borrowed(self))
     }
 }
 
 impl<'a> Into<ValueRef<'a>> for &'a str {
+    fn into(self) -> ValueRef<'a> {
+        ValueRef::Str(Cow::Borrowed(self))
+    }
+}
+
+impl<'a> Into<ValueRef<'a>> for String {
+    fn into(self) -> ValueRef<'a> {
+        ValueRef::Str(Cow::Owned(self))
+    }
+}
+
+impl<'a> Into<ValueRef<'a>> for Cow<'a, str> {
     fn into(self) -> ValueRef<'a> {
         ValueRef::Str(self)
     }
@@ -148,6 +291,26 @@ where
     }
 }
 
+impl<'a, T> Into<ValueRef<'a>> for &'a Vec<T>
+where
+    T: Debug,
+    for<'b> &'b T: Into<ValueRef<'b>>,
+{
+    fn into(self) -> ValueRef<'a> {
+        ValueRef::List(self)
+    }
+}
+
+impl<'a, T, const N: usize> Into<ValueRef<'a>> for &'a [T; N]
+where
+    T: Debug,
+    for<'b> &'b T: Into<ValueRef<'b>>,
+{
+    fn into(self) -> ValueRef<'a> {
+        ValueRef::List(self)
+    }
+}
+
 impl<'a> Into<ValueRef<'a>> for &'a dyn State {
     fn into(self) -> ValueRef<'a> {
         ValueRef::Map(self)
@@ -167,6 +330,12 @@ macro_rules! num_try_from {
                 match value {
                     ValueRef::Owned(Owned::Num(Num::Signed(num))) => Ok(num as $t),
                     ValueRef::Owned(Owned::Num(Num::Unsigned(num))) => Ok(num as $t),
+                    // Floats are never truncated
+                    ValueRef::Str(s) => match s.parse::<Num>() {
+                        Ok(Num::Signed(num)) => Ok(num as $t),
+                        Ok(Num::Unsigned(num)) => Ok(num as $t),
+                        _ => Err(()),
+                    },
                     _ => Err(()),
                 }
             }
@@ -182,6 +351,12 @@ macro_rules! float_try_from {
             fn try_from(value: ValueRef<'_>) -> Result<Self, Self::Error> {
                 match value {
                     ValueRef::Owned(Owned::Num(Num::Float(num))) => Ok(num as $t),
+                    ValueRef::Str(s) => match s.parse::<Num>() {
+                        Ok(Num::Float(num)) => Ok(num as $t),
+                        Ok(Num::Signed(num)) => Ok(num as $t),
+                        Ok(Num::Unsigned(num)) => Ok(num as $t),
+                        Err(_) => Err(()),
+                    },
                     _ => Err(()),
                 }
             }
@@ -205,7 +380,6 @@ macro_rules! val_try_from {
 }
 
 val_try_from!(bool, Bool);
-val_try_from!(Color, Color);
 val_try_from!(char, Char);
 
 num_try_from!(usize, Unsigned);
@@ -223,20 +397,91 @@ num_try_from!(i8, Signed);
 float_try_from!(f64);
 float_try_from!(f32);
 
+/// Strings are parsed as a hex colour (e.g `#ff8800`, see [`ColorExt::from_hex`]),
+/// a terminal colour name (e.g `red` or `dark_grey`), or a CSS colour name
+/// (e.g `rebeccapurple`, see [`ColorExt::from_name`]), in that order.
+///
+/// The terminal colours use the palette of the terminal, so `red` is the terminal's red
+/// and not the CSS `#ff0000`.
+impl TryFrom<ValueRef<'_>> for Color {
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> Result<Self, Self::Error> {
+        match value {
+            ValueRef::Str(s) => Color::from_hex(&s)
+                .or_else(|_| Color::try_from(&*s))
+                .or_else(|_| Color::from_name(&s).ok_or(())),
+            ValueRef::Owned(Owned::Color(color)) => Ok(color),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Numbers are milliseconds.
+/// Strings are a number followed by a unit: `ms`, `s` or `m` (e.g `500ms`, `1.5s`),
+/// where a number without a unit is milliseconds.
+///
+/// Negative durations and unknown units are rejected.
+impl TryFrom<ValueRef<'_>> for Duration {
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> Result<Self, Self::Error> {
+        match value {
+            ValueRef::Owned(Owned::Num(Num::Unsigned(ms))) => Ok(Duration::from_millis(ms)),
+            ValueRef::Owned(Owned::Num(num)) => millis(num.to_f64()),
+            ValueRef::Str(s) => parse_duration(&s),
+            _ => Err(()),
+        }
+    }
+}
+
+fn parse_duration(s: &str) -> Result<Duration, ()> {
+    let s = s.trim();
+    let split = s.find(|c: char| c.is_alphabetic()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let num = num.trim_end().parse::<f64>().map_err(|_| ())?;
+
+    let ms = match unit {
+        "" | "ms" => num,
+        "s" => num * 1000.0,
+        "m" => num * 60_000.0,
+        _ => return Err(()),
+    };
+
+    millis(ms)
+}
+
+fn millis(ms: f64) -> Result<Duration, ()> {
+    Duration::try_from_secs_f64(ms / 1000.0).map_err(|_| ())
+}
+
 impl TryFrom<ValueRef<'_>> for String {
     type Error = ();
 
     fn try_from(value: ValueRef<'_>) -> Result<Self, Self::Error> {
         match value {
-            ValueRef::Str(s) => Ok(s.to_string()),
+            ValueRef::Str(s) => Ok(s.into_owned()),
             _ => Err(()),
         }
     }
 }
 
+/// Only borrowed strings can be converted into a `&str`.
+/// Use `String` or `Cow<str>` for strings produced during evaluation.
 impl<'expr> TryFrom<ValueRef<'expr>> for &'expr str {
     type Error = ();
 
+    fn try_from(value: ValueRef<'expr>) -> Result<Self, Self::Error> {
+        match value {
+            ValueRef::Str(Cow::Borrowed(s)) => Ok(s),
+            _ => Err(()),
+        }
+    }
+}
+
+impl<'expr> TryFrom<ValueRef<'expr>> for Cow<'expr, str> {
+    type Error = ();
+
     fn try_from(value: ValueRef<'expr>) -> Result<Self, Self::Error> {
         match value {
             ValueRef::Str(s) => Ok(s),
@@ -244,3 +489,238 @@ impl<'expr> TryFrom<ValueRef<'expr>> for &'expr str {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::{ident, list, strlit, unum, TestState};
+    use crate::{Deferred, Path};
+
+    #[test]
+    fn owned_str() {
+        let value: ValueRef<'_> = String::from("hello").into();
+        assert_eq!(value, "hello".into());
+        assert!(<&str>::try_from(value.clone()).is_err());
+        assert_eq!(String::try_from(value).unwrap(), "hello");
+    }
+
+    #[test]
+    fn numbers_eq_across_variants() {
+        let unsigned = ValueRef::Owned(5u8.into());
+        let signed = ValueRef::Owned(5i8.into());
+        let float = ValueRef::Owned(Num::Float(5.0).into());
+        assert_eq!(unsigned, signed);
+        assert_eq!(signed, float);
+
+        let string: ValueRef<'_> = "5".into();
+        assert_ne!(string, unsigned);
+        assert_ne!(ValueRef::Empty, ValueRef::Empty);
+        assert_ne!(ValueRef::Deferred, ValueRef::Deferred);
+    }
+
+    #[test]
+    fn truthiness() {
+        assert!(ValueRef::Owned(Num::Float(0.5).into()).is_true());
+        assert!(ValueRef::Owned(Num::Float(-0.5).into()).is_true());
+        assert!(!ValueRef::Owned(Num::Float(0.0).into()).is_true());
+        assert!(!ValueRef::Owned(Num::Float(f64::NAN).into()).is_true());
+
+        let list = List::new([1usize]);
+        assert!(ValueRef::List(&list).is_true());
+        assert!(!ValueRef::List(&List::<usize>::empty()).is_true());
+        assert!(!ValueRef::Expressions(Expressions::new(&[])).is_true());
+
+        let map = Map::new([("a", 1usize)]);
+        assert!(ValueRef::Map(&map).is_true());
+        assert!(!ValueRef::Map(&Map::<usize>::empty()).is_true());
+        assert!(!ValueRef::ExpressionMap(ExpressionMap::new(&HashMap::new())).is_true());
+
+        assert!(!ValueRef::Empty.is_true());
+        assert!(!ValueRef::Deferred.is_true());
+    }
+
+    #[test]
+    fn durations() {
+        let duration = Duration::from_millis(1500);
+        let value: ValueRef<'_> = (&duration).into();
+        assert_eq!(value, ValueRef::Owned(1500u64.into()));
+        assert_eq!(Duration::try_from(value), Ok(duration));
+
+        let value = ValueRef::Owned(Num::Float(2.5).into());
+        assert_eq!(Duration::try_from(value), Ok(Duration::from_micros(2500)));
+        assert!(Duration::try_from(ValueRef::Owned((-1i8).into())).is_err());
+
+        let parse = |s: &str| Duration::try_from(ValueRef::Str(s.into()));
+        assert_eq!(parse("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse("1.5s"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse(" 3 m "), Ok(Duration::from_secs(180)));
+        assert_eq!(parse("250"), Ok(Duration::from_millis(250)));
+
+        assert!(parse("2h").is_err());
+        assert!(parse("ms").is_err());
+        assert!(parse("-1s").is_err());
+        assert!(parse("1sec").is_err());
+    }
+
+    #[test]
+    fn str_into_num() {
+        let value: ValueRef<'_> = "42".into();
+        assert_eq!(usize::try_from(value.clone()), Ok(42));
+        assert_eq!(f64::try_from(value), Ok(42.0));
+
+        let value: ValueRef<'_> = "-1.5".into();
+        assert_eq!(f32::try_from(value.clone()), Ok(-1.5));
+        assert!(i64::try_from(value).is_err());
+
+        let value: ValueRef<'_> = "not a number".into();
+        assert!(usize::try_from(value).is_err());
+    }
+
+    #[test]
+    fn str_into_color() {
+        let hex: ValueRef<'_> = "#ff8800".into();
+        let color = Color::try_from(hex).unwrap();
+        assert_eq!(
+            color,
+            Color::Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            }
+        );
+
+        let name: ValueRef<'_> = "red".into();
+        assert_eq!(Color::try_from(name).unwrap(), Color::Red);
+
+        let css: ValueRef<'_> = "RebeccaPurple".into();
+        let expected = Color::Rgb {
+            r: 0x66,
+            g: 0x33,
+            b: 0x99,
+        };
+        assert_eq!(Color::try_from(css).unwrap(), expected);
+
+        let unknown: ValueRef<'_> = "not a colour".into();
+        assert!(Color::try_from(unknown).is_err());
+
+        let invalid: ValueRef<'_> = "#ff88".into();
+        assert!(Color::try_from(invalid).is_err());
+
+        let owned = ValueRef::Owned(Owned::Color(Color::Blue));
+        assert_eq!(Color::try_from(owned).unwrap(), Color::Blue);
+    }
+
+    #[test]
+    fn list_into_vec() {
+        let state = TestState::new();
+        let context = Context::root(&state);
+        let node_id = NodeId::new(0);
+
+        let list = state.state_get(&Path::from("generic_list"), &node_id);
+        let values = list.into_vec::<usize>(&context, &node_id).unwrap();
+        assert_eq!(values, vec![1, 2, 3]);
+
+        let list = state.state_get(&Path::from("generic_list"), &node_id);
+        assert!(list.into_vec::<String>(&context, &node_id).is_err());
+
+        let name = state.state_get(&Path::from("name"), &node_id);
+        assert!(name.into_vec::<String>(&context, &node_id).is_err());
+    }
+
+    #[test]
+    fn expressions_into_vec() {
+        let state = TestState::new();
+        let context = Context::root(&state);
+        let node_id = NodeId::new(0);
+
+        let expressions = [*unum(1), *ident("counter")];
+        let list = ValueRef::Expressions(Expressions::new(&expressions));
+        let values = list.into_vec::<usize>(&context, &node_id).unwrap();
+        assert_eq!(values, vec![1, 3]);
+
+        let expressions = [*unum(1), *strlit("two")];
+        let list = ValueRef::Expressions(Expressions::new(&expressions));
+        assert!(list.into_vec::<usize>(&context, &node_id).is_err());
+    }
+
+    #[test]
+    fn resolve_deferred_values() {
+        let state = TestState::new();
+        let context = Context::root(&state);
+        let node_id = NodeId::new(0);
+
+        let expr = ident("counter");
+        let value = expr.eval(&mut Deferred::new(context.lookup()));
+        assert!(matches!(value, ValueRef::Deferred));
+        let value = value.resolve_deferred(&expr, &mut Immediate::new(context.lookup(), &node_id));
+        assert_eq!(value, ValueRef::Owned(3usize.into()));
+
+        let expr = ident("generic_list");
+        let value = expr.eval(&mut Deferred::new(context.lookup()));
+        let value = value.resolve_deferred(&expr, &mut Immediate::new(context.lookup(), &node_id));
+        let ValueRef::List(list) = value else {
+            panic!("expected a list")
+        };
+        assert_eq!(list.len(), 3);
+
+        // Values that aren't deferred are returned as is
+        let expr = unum(1);
+        let hello = strlit("hello");
+        let value = hello.eval(&mut Deferred::new(context.lookup()));
+        let value = value.resolve_deferred(&expr, &mut Immediate::new(context.lookup(), &node_id));
+        assert_eq!(value.as_str(), Some("hello"));
+    }
+
+    #[test]
+    fn lookup_nested_values() {
+        let state = TestState::new();
+        let context = Context::root(&state);
+        let node_id = NodeId::new(0);
+        let root: ValueRef<'_> = (&state as &dyn State).into();
+        let mut resolver = Immediate::new(context.lookup(), &node_id);
+
+        let value = root.lookup_path(&["generic_map", "inner", "second"], &mut resolver);
+        assert_eq!(value, ValueRef::Owned(2usize.into()));
+
+        let value = root.lookup_path(&["nested_list", "0", "2"], &mut resolver);
+        assert_eq!(value, ValueRef::Owned(3usize.into()));
+
+        // Missing keys, out of bounds, and paths through values
+        for path in [
+            &["generic_map", "missing", "second"][..],
+            &["nested_list", "1", "0"],
+            &["nested_list", "first"],
+            &["counter", "value"],
+        ] {
+            let value = root.lookup_path(path, &mut resolver);
+            assert!(matches!(value, ValueRef::Empty), "{path:?}");
+        }
+
+        // Lists in expressions
+        let expr = list([unum(1), unum(2)]);
+        let value = expr.eval(&mut resolver);
+        let value = value.lookup_path(&["1"], &mut resolver);
+        assert_eq!(value, ValueRef::Owned(2u64.into()));
+    }
+
+    #[test]
+    fn ordering() {
+        let five = ValueRef::Owned(5u8.into());
+        let minus_one = ValueRef::Owned((-1i8).into());
+        let half = ValueRef::Owned(Num::Float(0.5).into());
+        assert!(minus_one < half);
+        assert!(half < five);
+
+        let a: ValueRef<'_> = "a".into();
+        let b: ValueRef<'_> = String::from("b").into();
+        assert!(a < b);
+
+        assert!(ValueRef::Owned(false.into()) < ValueRef::Owned(true.into()));
+
+        assert!(a.partial_cmp(&ValueRef::Owned(true.into())).is_none());
+        assert!(five.partial_cmp(&ValueRef::Owned(true.into())).is_none());
+        assert!(ValueRef::Empty.partial_cmp(&ValueRef::Empty).is_none());
+        assert!(ValueRef::Deferred.partial_cmp(&five).is_none());
+    }
+}
@@ -1,5 +1,8 @@
+use std::cmp::Ordering;
 use std::fmt::{self, Display};
 use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::str::FromStr;
+use std::time::Duration;
 
 macro_rules! to_num {
     ($fn_name:ident, $num_type:ty) => {
@@ -13,7 +16,12 @@ macro_rules! to_num {
     };
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+/// A number.
+///
+/// Numbers compare equal by value, regardless of the variant,
+/// so `Unsigned(5)`, `Signed(5)` and `Float(5.0)` are all equal.
+/// They are ordered the same way.
+#[derive(Debug, Copy, Clone)]
 pub enum Num {
     Signed(i64),
     Unsigned(u64),
@@ -45,8 +53,18 @@ impl Num {
 
     to_num!(to_i8, i8);
 
+    /// Negate the number.
+    /// Negating an unsigned value that doesn't fit in a signed value produces a float.
     pub fn to_negative(self) -> Self {
-        Self::Signed(-self.to_i128() as i64)
+        match self {
+            Self::Float(num) => Self::Float(-num),
+            _ => self
+                .to_i128()
+                .checked_neg()
+                .and_then(|num| i64::try_from(num).ok())
+                .map(Self::Signed)
+                .unwrap_or_else(|| Self::Float(-self.to_f64())),
+        }
     }
 
     pub fn is_zero(&self) -> bool {
@@ -58,6 +76,43 @@ impl Num {
     }
 }
 
+impl PartialEq for Num {
+    fn eq(&self, other: &Self) -> bool {
+        match (*self, *other) {
+            (Self::Signed(lhs), Self::Signed(rhs)) => lhs == rhs,
+            (Self::Unsigned(lhs), Self::Unsigned(rhs)) => lhs == rhs,
+            (Self::Float(lhs), Self::Float(rhs)) => lhs == rhs,
+
+            (Self::Signed(lhs), Self::Unsigned(rhs)) | (Self::Unsigned(rhs), Self::Signed(lhs)) => {
+                lhs as i128 == rhs as i128
+            }
+
+            (Self::Float(lhs), Self::Signed(rhs)) | (Self::Signed(rhs), Self::Float(lhs)) => {
+                lhs == rhs as f64
+            }
+            (Self::Float(lhs), Self::Unsigned(rhs)) | (Self::Unsigned(rhs), Self::Float(lhs)) => {
+                lhs == rhs as f64
+            }
+        }
+    }
+}
+
+impl PartialOrd for Num {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (*self, *other) {
+            (Self::Signed(lhs), Self::Signed(rhs)) => lhs.partial_cmp(&rhs),
+            (Self::Unsigned(lhs), Self::Unsigned(rhs)) => lhs.partial_cmp(&rhs),
+            (Self::Signed(lhs), Self::Unsigned(rhs)) => (lhs as i128).partial_cmp(&(rhs as i128)),
+            (Self::Unsigned(lhs), Self::Signed(rhs)) => (lhs as i128).partial_cmp(&(rhs as i128)),
+            (Self::Float(lhs), Self::Float(rhs)) => lhs.partial_cmp(&rhs),
+            (Self::Float(lhs), Self::Signed(rhs)) => lhs.partial_cmp(&(rhs as f64)),
+            (Self::Float(lhs), Self::Unsigned(rhs)) => lhs.partial_cmp(&(rhs as f64)),
+            (Self::Signed(lhs), Self::Float(rhs)) => (lhs as f64).partial_cmp(&rhs),
+            (Self::Unsigned(lhs), Self::Float(rhs)) => (lhs as f64).partial_cmp(&rhs),
+        }
+    }
+}
+
 impl Display for Num {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -68,148 +123,178 @@ impl Display for Num {
     }
 }
 
-impl Mul for Num {
-    type Output = Num;
+// -----------------------------------------------------------------------------
+//   - Parsing -
+// -----------------------------------------------------------------------------
+/// Error returned when parsing a [`Num`] from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseNumError {
+    /// The string is empty
+    Empty,
+    /// The string is not a number, or the number is out of range
+    Invalid(String),
+}
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Signed(lhs), Self::Signed(rhs)) => Self::Signed(lhs * rhs),
-            (Self::Unsigned(lhs), Self::Unsigned(rhs)) => Self::Unsigned(lhs * rhs),
-            _ => panic!(),
+impl Display for ParseNumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "cannot parse a number from an empty string"),
+            Self::Invalid(s) => write!(f, "`{s}` is not a valid number"),
         }
     }
 }
 
-impl Add for Num {
-    type Output = Num;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Signed(lhs), Self::Signed(rhs)) => Self::Signed(lhs + rhs),
-            (Self::Unsigned(lhs), Self::Unsigned(rhs)) => Self::Unsigned(lhs + rhs),
-
-            (Self::Signed(lhs), Self::Unsigned(rhs)) if lhs.is_negative() => {
-                if lhs.unsigned_abs() >= rhs {
-                    Self::Signed(-((lhs.unsigned_abs() - rhs) as i64))
-                } else {
-                    Self::Unsigned(rhs - lhs.unsigned_abs())
-                }
-            }
-
-            (Self::Unsigned(lhs), Self::Signed(rhs)) if rhs.is_negative() => {
-                if rhs.unsigned_abs() >= lhs {
-                    Self::Signed(-((rhs.unsigned_abs() - lhs) as i64))
-                } else {
-                    Self::Unsigned(lhs - rhs.unsigned_abs())
-                }
-            }
-
-            (Self::Signed(lhs), Self::Unsigned(rhs)) => Self::Unsigned(lhs as u64 + rhs),
-            (Self::Unsigned(lhs), Self::Signed(rhs)) => Self::Unsigned(rhs as u64 + lhs),
-            _ => panic!(),
+impl std::error::Error for ParseNumError {}
+
+/// Parse a number.
+///
+/// * A decimal point or an exponent makes the number a `Float`
+/// * A negative integer is `Signed`
+/// * Any other integer is `Unsigned`
+///
+/// ```
+/// # use anathema_values::Num;
+/// assert_eq!("+5".parse::<Num>().unwrap(), Num::Unsigned(5));
+/// assert!(matches!("-5".parse::<Num>().unwrap(), Num::Signed(-5)));
+/// assert!(matches!("1e3".parse::<Num>().unwrap(), Num::Float(_)));
+/// assert!("five".parse::<Num>().is_err());
+/// ```
+impl FromStr for Num {
+    type Err = ParseNumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseNumError::Empty);
         }
+
+        // `inf` and `nan` are not numeric literals, and are rejected
+        // as they contain neither a decimal point or an exponent.
+        let num = if s.contains(['.', 'e', 'E']) {
+            s.parse().ok().map(Self::Float)
+        } else if s.starts_with('-') {
+            s.parse().ok().map(Self::Signed)
+        } else {
+            s.parse().ok().map(Self::Unsigned)
+        };
+
+        num.ok_or_else(|| ParseNumError::Invalid(s.to_string()))
     }
 }
 
-impl Sub for Num {
-    type Output = Num;
+// -----------------------------------------------------------------------------
+//   - Arithmetic -
+//   If either side is a float the result is a float,
+//   if either side is signed the result is signed,
+//   otherwise the result is unsigned.
+//
+//   Subtracting unsigned values that would underflow produces a signed value.
+// -----------------------------------------------------------------------------
+impl Num {
+    /// Convert the number to a float
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Self::Signed(num) => num as f64,
+            Self::Unsigned(num) => num as f64,
+            Self::Float(num) => num,
+        }
+    }
 
-    fn sub(self, rhs: Self) -> Self::Output {
+    fn checked_op(
+        self,
+        rhs: Self,
+        int_op: fn(i128, i128) -> Option<i128>,
+        float_op: fn(f64, f64) -> f64,
+    ) -> Option<Self> {
         match (self, rhs) {
-            (Self::Signed(lhs), Self::Signed(rhs)) => Self::Signed(lhs - rhs),
-            (Self::Unsigned(lhs), Self::Unsigned(rhs)) => Self::Unsigned(lhs - rhs),
-
-            (Self::Signed(lhs), Self::Unsigned(rhs)) => {
-                let lhs = lhs as i128;
-                let rhs = rhs as i128;
-                let res = lhs - rhs;
-                if res.is_negative() {
-                    Self::Signed(res as i64)
-                } else {
-                    Self::Unsigned(res as u64)
-                }
+            (Self::Float(_), _) | (_, Self::Float(_)) => {
+                Some(Self::Float(float_op(self.to_f64(), rhs.to_f64())))
             }
-            (Self::Unsigned(lhs), Self::Signed(rhs)) => {
-                let lhs = lhs as i128;
-                let rhs = rhs as i128;
-                let res = lhs - rhs;
-                if res.is_negative() {
-                    Self::Signed(res as i64)
-                } else {
-                    Self::Unsigned(res as u64)
+            (Self::Unsigned(lhs), Self::Unsigned(rhs)) => {
+                let res = int_op(lhs as i128, rhs as i128)?;
+                match u64::try_from(res) {
+                    Ok(res) => Some(Self::Unsigned(res)),
+                    Err(_) => i64::try_from(res).ok().map(Self::Signed),
                 }
             }
-            _ => panic!(),
+            (lhs, rhs) => {
+                let res = int_op(lhs.to_i128(), rhs.to_i128())?;
+                i64::try_from(res).ok().map(Self::Signed)
+            }
         }
     }
-}
 
-impl Div for Num {
-    type Output = Num;
+    /// Add two numbers.
+    /// Returns `None` if the result doesn't fit.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.checked_op(rhs, i128::checked_add, |lhs, rhs| lhs + rhs)
+    }
 
-    fn div(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Signed(lhs), Self::Signed(rhs)) => Self::Signed(lhs / rhs),
-            (Self::Unsigned(lhs), Self::Unsigned(rhs)) => Self::Unsigned(lhs / rhs),
-
-            (Self::Signed(lhs), Self::Unsigned(rhs)) => {
-                let lhs = lhs as i128;
-                let rhs = rhs as i128;
-                let res = lhs / rhs;
-                if res.is_negative() {
-                    Self::Signed(res as i64)
-                } else {
-                    Self::Unsigned(res as u64)
-                }
-            }
-            (Self::Unsigned(lhs), Self::Signed(rhs)) => {
-                let lhs = lhs as i128;
-                let rhs = rhs as i128;
-                let res = lhs / rhs;
-                if res.is_negative() {
-                    Self::Signed(res as i64)
-                } else {
-                    Self::Unsigned(res as u64)
-                }
-            }
-            _ => panic!(),
+    /// Subtract `rhs` from `self`.
+    /// Returns `None` if the result doesn't fit.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_op(rhs, i128::checked_sub, |lhs, rhs| lhs - rhs)
+    }
+
+    /// Multiply two numbers.
+    /// Returns `None` if the result doesn't fit.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        self.checked_op(rhs, i128::checked_mul, |lhs, rhs| lhs * rhs)
+    }
+
+    /// Divide `self` by `rhs`.
+    /// Returns `None` if `rhs` is zero or the result doesn't fit.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
         }
+        self.checked_op(rhs, i128::checked_div, |lhs, rhs| lhs / rhs)
     }
-}
 
-impl Rem for Num {
-    type Output = Num;
+    /// The remainder of dividing `self` by `rhs`.
+    /// Returns `None` if `rhs` is zero.
+    pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        self.checked_op(rhs, i128::checked_rem, |lhs, rhs| lhs % rhs)
+    }
+}
 
-    fn rem(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Self::Signed(lhs), Self::Signed(rhs)) => Self::Signed(lhs % rhs),
-            (Self::Unsigned(lhs), Self::Unsigned(rhs)) => Self::Unsigned(lhs % rhs),
-
-            (Self::Signed(lhs), Self::Unsigned(rhs)) => {
-                let lhs = lhs as i128;
-                let rhs = rhs as i128;
-                let res = lhs % rhs;
-                if res.is_negative() {
-                    Self::Signed(res as i64)
-                } else {
-                    Self::Unsigned(res as u64)
-                }
+// The operators never panic: if the checked operation overflows the result is a float.
+// There is no sensible value for a division by zero, so the division operators
+// return `None` instead (see `checked_div` and `checked_rem`).
+macro_rules! num_op {
+    ($trait:ident, $fn_name:ident, $checked:ident, $op:tt) => {
+        impl $trait for Num {
+            type Output = Num;
+
+            fn $fn_name(self, rhs: Self) -> Self::Output {
+                self.$checked(rhs)
+                    .unwrap_or_else(|| Self::Float(self.to_f64() $op rhs.to_f64()))
             }
-            (Self::Unsigned(lhs), Self::Signed(rhs)) => {
-                let lhs = lhs as i128;
-                let rhs = rhs as i128;
-                let res = lhs % rhs;
-                if res.is_negative() {
-                    Self::Signed(res as i64)
-                } else {
-                    Self::Unsigned(res as u64)
-                }
+        }
+    };
+}
+
+num_op!(Add, add, checked_add, +);
+num_op!(Sub, sub, checked_sub, -);
+num_op!(Mul, mul, checked_mul, *);
+
+macro_rules! num_div_op {
+    ($trait:ident, $fn_name:ident, $checked:ident) => {
+        impl $trait for Num {
+            type Output = Option<Num>;
+
+            fn $fn_name(self, rhs: Self) -> Self::Output {
+                self.$checked(rhs)
             }
-            _ => panic!(),
         }
-    }
+    };
 }
 
+num_div_op!(Div, div, checked_div);
+num_div_op!(Rem, rem, checked_rem);
+
 macro_rules! into_unsigned_num {
     ($t:ty) => {
         impl From<$t> for Num {
@@ -242,6 +327,22 @@ macro_rules! into_signed_num {
     };
 }
 
+macro_rules! into_float_num {
+    ($t:ty) => {
+        impl From<$t> for Num {
+            fn from(n: $t) -> Self {
+                Self::Float(n as f64)
+            }
+        }
+
+        impl From<&$t> for Num {
+            fn from(n: &$t) -> Self {
+                Self::Float(*n as f64)
+            }
+        }
+    };
+}
+
 into_unsigned_num!(u8);
 into_unsigned_num!(u16);
 into_unsigned_num!(u32);
@@ -253,3 +354,155 @@ into_signed_num!(i16);
 into_signed_num!(i32);
 into_signed_num!(i64);
 into_signed_num!(isize);
+
+into_float_num!(f32);
+into_float_num!(f64);
+
+/// A duration is stored as whole milliseconds.
+impl From<Duration> for Num {
+    fn from(duration: Duration) -> Self {
+        Self::Unsigned(duration.as_millis().try_into().unwrap_or(u64::MAX))
+    }
+}
+
+impl From<&Duration> for Num {
+    fn from(duration: &Duration) -> Self {
+        (*duration).into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn eq_across_variants() {
+        assert_eq!(Num::Unsigned(5), Num::Signed(5));
+        assert_eq!(Num::Signed(5), Num::Float(5.0));
+        assert_eq!(Num::Float(5.0), Num::Unsigned(5));
+
+        assert_ne!(Num::Unsigned(5), Num::Signed(-5));
+        assert_ne!(Num::Unsigned(u64::MAX), Num::Signed(-1));
+        assert_ne!(Num::Float(5.5), Num::Unsigned(5));
+        assert_ne!(Num::Float(f64::NAN), Num::Float(f64::NAN));
+    }
+
+    #[test]
+    fn parse() {
+        assert!(matches!("42".parse(), Ok(Num::Unsigned(42))));
+        assert!(matches!("+42".parse(), Ok(Num::Unsigned(42))));
+        assert!(matches!("-42".parse(), Ok(Num::Signed(-42))));
+        assert!(matches!("-0".parse(), Ok(Num::Signed(0))));
+        assert!(matches!("4.0".parse(), Ok(Num::Float(f)) if f == 4.0));
+        assert!(matches!("-2.5e-1".parse(), Ok(Num::Float(f)) if f == -0.25));
+        assert!(matches!("1E2".parse(), Ok(Num::Float(f)) if f == 100.0));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!("".parse::<Num>(), Err(ParseNumError::Empty));
+        for s in ["-", "+", "1.2.3", " 1", "inf", "NaN", "0x10", "1_000"] {
+            assert_eq!(
+                s.parse::<Num>(),
+                Err(ParseNumError::Invalid(s.to_string())),
+                "{s}"
+            );
+        }
+
+        // Out of range
+        assert!("18446744073709551616".parse::<Num>().is_err());
+        assert!("-9223372036854775809".parse::<Num>().is_err());
+    }
+
+    #[test]
+    fn ord_across_variants() {
+        assert!(Num::Signed(-1) < Num::Unsigned(0));
+        assert!(Num::Unsigned(u64::MAX) > Num::Signed(i64::MAX));
+        assert!(Num::Float(2.5) > Num::Unsigned(2));
+        assert!(Num::Signed(-3) < Num::Float(-2.5));
+        assert!(Num::Unsigned(3) >= Num::Float(3.0));
+        assert!(Num::Float(f64::NAN)
+            .partial_cmp(&Num::Unsigned(1))
+            .is_none());
+    }
+
+    #[test]
+    fn float_wins() {
+        assert!(matches!(Num::Unsigned(1) + Num::Float(0.5), Num::Float(f) if f == 1.5));
+        assert!(matches!(Num::Float(3.0) * Num::Signed(-2), Num::Float(f) if f == -6.0));
+        assert!(matches!(
+            Num::Unsigned(1).checked_div(Num::Float(2.0)),
+            Some(Num::Float(f)) if f == 0.5
+        ));
+    }
+
+    #[test]
+    fn signed_wins() {
+        assert!(matches!(Num::Unsigned(2) + Num::Signed(3), Num::Signed(5)));
+        assert!(matches!(
+            Num::Signed(-7).checked_rem(Num::Unsigned(4)),
+            Some(Num::Signed(-3))
+        ));
+        assert!(matches!(
+            Num::Unsigned(7).checked_div(Num::Unsigned(2)),
+            Some(Num::Unsigned(3))
+        ));
+    }
+
+    #[test]
+    fn unsigned_underflow_is_signed() {
+        assert!(matches!(
+            Num::Unsigned(1) - Num::Unsigned(3),
+            Num::Signed(-2)
+        ));
+        assert!(matches!(
+            Num::Unsigned(3) - Num::Unsigned(1),
+            Num::Unsigned(2)
+        ));
+
+        // Too small for a signed value
+        assert!(Num::Unsigned(0)
+            .checked_sub(Num::Unsigned(u64::MAX))
+            .is_none());
+        assert!(matches!(
+            Num::Unsigned(0) - Num::Unsigned(u64::MAX),
+            Num::Float(_)
+        ));
+    }
+
+    #[test]
+    fn division_by_zero() {
+        for zero in [Num::Unsigned(0), Num::Signed(0), Num::Float(0.0)] {
+            assert!(Num::Unsigned(1).checked_div(zero).is_none());
+            assert!(Num::Float(1.0).checked_rem(zero).is_none());
+        }
+        assert!(Num::Signed(i64::MIN).checked_div(Num::Signed(-1)).is_none());
+        assert!((Num::Unsigned(1) / Num::Signed(0)).is_none());
+        assert!((Num::Unsigned(1) % Num::Float(0.0)).is_none());
+        assert!(matches!(
+            Num::Signed(-7) / Num::Unsigned(2),
+            Some(Num::Signed(-3))
+        ));
+        assert!(matches!(
+            Num::Unsigned(7) % Num::Unsigned(4),
+            Some(Num::Unsigned(3))
+        ));
+    }
+
+    #[test]
+    fn negative() {
+        assert!(matches!(Num::Unsigned(2).to_negative(), Num::Signed(-2)));
+        assert!(matches!(Num::Signed(-2).to_negative(), Num::Signed(2)));
+        assert!(matches!(Num::Float(1.5).to_negative(), Num::Float(f) if f == -1.5));
+
+        // Too big for a signed value
+        assert!(matches!(
+            Num::Unsigned(u64::MAX).to_negative(),
+            Num::Float(f) if f == -(u64::MAX as f64)
+        ));
+        assert!(matches!(
+            Num::Signed(i64::MIN).to_negative(),
+            Num::Float(f) if f == -(i64::MIN as f64)
+        ));
+    }
+}
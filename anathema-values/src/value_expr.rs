@@ -1,10 +1,11 @@
+use std::borrow::Cow;
 use std::fmt::Display;
 use std::rc::Rc;
 
 use crate::hashmap::HashMap;
 use crate::scope::ContextRef;
 use crate::value::{ExpressionMap, Expressions};
-use crate::{Collection, NodeId, Owned, Path, ScopeValue, State, ValueRef};
+use crate::{function, Collection, NodeId, Owned, Path, ScopeValue, State, ValueRef};
 
 // -----------------------------------------------------------------------------
 //   - Value resolver trait -
@@ -140,6 +141,7 @@ impl<'frame> Resolver<'frame> for Immediate<'frame> {
                             let path = index.into();
                             list.state_get(&path, self.node_id)
                         }
+                        ValueRef::Map(map) => map.state_get(&index.into(), self.node_id),
                         _ => ValueRef::Empty,
                     }
                 }
@@ -197,6 +199,8 @@ pub enum ValueExpr {
     Div(Box<ValueExpr>, Box<ValueExpr>),
     Mul(Box<ValueExpr>, Box<ValueExpr>),
     Mod(Box<ValueExpr>, Box<ValueExpr>),
+
+    Call(Rc<str>, Box<[ValueExpr]>),
 }
 
 impl Display for ValueExpr {
@@ -241,6 +245,16 @@ impl Display for ValueExpr {
             Self::GreaterEqual(lhs, rhs) => write!(f, "{lhs} >= {rhs}"),
             Self::Less(lhs, rhs) => write!(f, "{lhs} < {rhs}"),
             Self::LessEqual(lhs, rhs) => write!(f, "{lhs} <= {rhs}"),
+            Self::Call(fun, args) => {
+                write!(
+                    f,
+                    "{fun}({})",
+                    args.iter()
+                        .map(|arg| arg.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
         }
     }
 }
@@ -281,7 +295,7 @@ impl ValueExpr {
     pub fn eval_vec<'expr>(
         &'expr self,
         resolver: &mut impl Resolver<'expr>,
-    ) -> Option<Vec<ValueRef<'_>>> {
+    ) -> Option<Vec<ValueRef<'expr>>> {
         match self.eval(resolver) {
             ValueRef::Expressions(Expressions(list)) => {
                 let mut v = Vec::with_capacity(list.len());
@@ -303,7 +317,7 @@ impl ValueExpr {
     pub fn eval<'expr>(&'expr self, resolver: &mut impl Resolver<'expr>) -> ValueRef<'expr> {
         match self {
             Self::Owned(value) => ValueRef::Owned(*value),
-            Self::String(value) => ValueRef::Str(value),
+            Self::String(value) => ValueRef::Str(Cow::Borrowed(value)),
 
             // -----------------------------------------------------------------------------
             //   - Maths -
@@ -312,33 +326,26 @@ impl ValueExpr {
             | Self::Sub(lhs, rhs)
             | Self::Mul(lhs, rhs)
             | Self::Mod(lhs, rhs)
-            | Self::Div(lhs, rhs)
-            | Self::Greater(lhs, rhs)
-            | Self::GreaterEqual(lhs, rhs)
-            | Self::Less(lhs, rhs)
-            | Self::LessEqual(lhs, rhs)) => {
+            | Self::Div(lhs, rhs)) => {
                 let lhs = eval_num!(lhs, resolver);
                 let rhs = eval_num!(rhs, resolver);
 
-                match op {
-                    Self::Add(..) => ValueRef::Owned(Owned::Num(lhs + rhs)),
-                    Self::Sub(..) => ValueRef::Owned(Owned::Num(lhs - rhs)),
-                    Self::Mul(..) => ValueRef::Owned(Owned::Num(lhs * rhs)),
-                    Self::Mod(..) => ValueRef::Owned(Owned::Num(lhs % rhs)),
-                    Self::Div(..) if !rhs.is_zero() => ValueRef::Owned(Owned::Num(lhs / rhs)),
-                    Self::Div(..) => ValueRef::Empty,
-                    Self::Greater(..) => {
-                        ValueRef::Owned(Owned::Bool(lhs.to_u128() > rhs.to_u128()))
-                    }
-                    Self::GreaterEqual(..) => {
-                        ValueRef::Owned(Owned::Bool(lhs.to_u128() >= rhs.to_u128()))
-                    }
-                    Self::Less(..) => ValueRef::Owned(Owned::Bool(lhs.to_u128() < rhs.to_u128())),
-                    Self::LessEqual(..) => {
-                        ValueRef::Owned(Owned::Bool(lhs.to_u128() <= rhs.to_u128()))
-                    }
+                let num = match op {
+                    Self::Add(..) => lhs + rhs,
+                    Self::Sub(..) => lhs - rhs,
+                    Self::Mul(..) => lhs * rhs,
+                    // A division by zero is empty
+                    Self::Mod(..) => match lhs.checked_rem(rhs) {
+                        Some(num) => num,
+                        None => return ValueRef::Empty,
+                    },
+                    Self::Div(..) => match lhs.checked_div(rhs) {
+                        Some(num) => num,
+                        None => return ValueRef::Empty,
+                    },
                     _ => unreachable!(),
-                }
+                };
+                ValueRef::Owned(Owned::Num(num))
             }
 
             Self::Negative(expr) => {
@@ -358,6 +365,29 @@ impl ValueExpr {
                 let rhs = rhs.eval(resolver);
                 ValueRef::Owned((lhs == rhs).into())
             }
+            op @ (Self::Greater(lhs, rhs)
+            | Self::GreaterEqual(lhs, rhs)
+            | Self::Less(lhs, rhs)
+            | Self::LessEqual(lhs, rhs)) => {
+                let lhs = lhs.eval(resolver);
+                let rhs = rhs.eval(resolver);
+                if matches!(lhs, ValueRef::Deferred) || matches!(rhs, ValueRef::Deferred) {
+                    return ValueRef::Deferred;
+                }
+
+                let Some(ordering) = lhs.partial_cmp(&rhs) else {
+                    return ValueRef::Empty;
+                };
+
+                let b = match op {
+                    Self::Greater(..) => ordering.is_gt(),
+                    Self::GreaterEqual(..) => ordering.is_ge(),
+                    Self::Less(..) => ordering.is_lt(),
+                    Self::LessEqual(..) => ordering.is_le(),
+                    _ => unreachable!(),
+                };
+                ValueRef::Owned(b.into())
+            }
             Self::Or(lhs, rhs) => {
                 let lhs = lhs.eval(resolver);
                 let rhs = rhs.eval(resolver);
@@ -379,7 +409,7 @@ impl ValueExpr {
             Self::Index(lhs, index) => match lhs.eval(resolver) {
                 ValueRef::Expressions(list) => {
                     let index = eval_num!(index, resolver).to_usize();
-                    return list.0[index].eval(resolver);
+                    list.0[index].eval(resolver)
                 }
                 ValueRef::ExpressionMap(map) => {
                     let key = index.eval_string(resolver).unwrap_or(String::new());
@@ -402,7 +432,7 @@ impl ValueExpr {
                         ValueExpr::Ident(key) => key,
                         _ => return ValueRef::Empty,
                     };
-                    return map.0[&**key].eval(resolver);
+                    map.0[&**key].eval(resolver)
                 }
                 ValueRef::Map(map) => {
                     let key = match &**rhs {
@@ -419,6 +449,20 @@ impl ValueExpr {
             // -----------------------------------------------------------------------------
             Self::List(list) => ValueRef::Expressions(Expressions::new(list)),
             Self::Map(map) => ValueRef::ExpressionMap(ExpressionMap::new(map)),
+
+            // -----------------------------------------------------------------------------
+            //   - Functions -
+            // -----------------------------------------------------------------------------
+            Self::Call(fun, args) => {
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args.iter() {
+                    match arg.eval(resolver) {
+                        ValueRef::Deferred => return ValueRef::Deferred,
+                        val => values.push(val),
+                    }
+                }
+                function::call(fun, &values)
+            }
         }
     }
 }
@@ -454,9 +498,10 @@ impl From<&str> for ValueExpr {
 mod test {
     use crate::map::Map;
     use crate::testing::{
-        add, and, div, dot, eq, greater_than, greater_than_equal, ident, inum, less_than,
+        add, and, call, div, dot, eq, greater_than, greater_than_equal, ident, inum, less_than,
         less_than_equal, list, modulo, mul, neg, not, or, strlit, sub, unum,
     };
+    use crate::{drain_resolve_errors, FunctionError, Functions, Owned, ValueRef};
 
     #[test]
     fn add_dyn() {
@@ -470,6 +515,12 @@ mod test {
         expr.test().expect_owned(-3);
     }
 
+    #[test]
+    fn negate_float() {
+        let expr = neg(ident("counter"));
+        expr.with_data([("counter", 1.5f64)]).expect_owned(-1.5);
+    }
+
     #[test]
     fn sub_static() {
         let expr = sub(unum(10), unum(2));
@@ -494,6 +545,24 @@ mod test {
         expr.test().expect_owned(2u8);
     }
 
+    #[test]
+    fn division_by_zero_is_empty() {
+        let expr = div(unum(10), ident("counter"));
+        expr.with_data([("counter", 0)]).expect_empty();
+
+        let expr = modulo(inum(-10), ident("counter"));
+        expr.with_data([("counter", 0.0)]).expect_empty();
+    }
+
+    #[test]
+    fn division_promotion() {
+        let expr = div(unum(10), ident("counter"));
+        expr.with_data([("counter", 4.0)]).expect_owned(2.5);
+
+        let expr = modulo(neg(unum(7)), ident("counter"));
+        expr.with_data([("counter", 4)]).expect_owned(-3);
+    }
+
     #[test]
     fn greater_than_static() {
         let expr = greater_than(unum(5), unum(3));
@@ -533,6 +602,18 @@ mod test {
         expr.test().expect_owned(true);
     }
 
+    #[test]
+    fn compare_mixed_values() {
+        let expr = less_than(inum(-2), unum(3));
+        expr.test().expect_owned(true);
+
+        let expr = greater_than(strlit("b"), strlit("a"));
+        expr.test().expect_owned(true);
+
+        let expr = greater_than(strlit("b"), unum(1));
+        expr.test().eval_bool(false);
+    }
+
     #[test]
     fn bools() {
         // false
@@ -591,4 +672,44 @@ mod test {
         expr.with_data([("inner", Map::new([("name", "Fiddle McStick".to_string())]))])
             .expect_string("Mr. Fiddle McStick");
     }
+
+    #[test]
+    fn call_builtin_functions() {
+        let expr = call("upper", [ident("name")]);
+        expr.with_data([("name", "Fiddle".to_string())])
+            .expect_string("FIDDLE");
+
+        let expr = call("max", [unum(2), inum(-3), ident("counter")]);
+        expr.with_data([("counter", 4u32)]).expect_owned(4u32);
+
+        let expr = call("len", [list([unum(1), unum(2)])]);
+        expr.test().expect_owned(2u32);
+    }
+
+    #[test]
+    fn call_function() {
+        let functions = Functions::new();
+        functions.register_fn("add_one", |args: &[ValueRef<'_>]| {
+            FunctionError::check_arity(args, 1)?;
+            match args[0] {
+                ValueRef::Owned(Owned::Num(n)) => Ok(Owned::Num(n + 1u8.into()).into()),
+                _ => Err(FunctionError::InvalidArgument {
+                    index: 0,
+                    expected: "a number",
+                }),
+            }
+        });
+        let _functions = functions.enter();
+
+        let expr = call("add_one", [ident("counter")]);
+        expr.with_data([("counter", 2u32)]).expect_owned(3u32);
+
+        let expr = call("add_one", [unum(1), unum(2)]);
+        assert!(expr.test().eval_bool(false));
+        let errors = drain_resolve_errors();
+        assert_eq!(
+            errors[0].to_string(),
+            "`add_one`: expected 1 argument(s), got 2"
+        );
+    }
 }
@@ -1,12 +1,26 @@
+use std::path::PathBuf;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, thiserror::Error)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     /// Compiler error
-    #[error("compiler error: {0}")]
+    #[error(transparent)]
     CompilerError(#[from] anathema_compiler::error::Error),
 
     /// Missing template error
     #[error("template missing")]
     TemplateMissing,
+
+    /// An included template could not be read
+    #[error("failed to include {}: {error}", path.display())]
+    Include {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    /// A template includes itself (directly or through other includes)
+    #[error("include cycle: {} includes itself", .0.display())]
+    IncludeCycle(PathBuf),
 }
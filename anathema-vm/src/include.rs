@@ -0,0 +1,287 @@
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anathema_compiler::error::Error as CompilerError;
+
+use crate::error::{Error, Result};
+
+const INCLUDE: &str = "@include";
+
+// -----------------------------------------------------------------------------
+//   - Includes -
+//   Replace every `@include "path/to/file.tiny"` line with the content of the
+//   file, indented to the same level as the include.
+//
+//   Paths are relative to the directory of the file with the include,
+//   or the include directory of the templates for the root template and views.
+// -----------------------------------------------------------------------------
+pub(crate) fn resolve_includes<'src>(
+    src: &'src str,
+    dir: &Path,
+    included: &mut Vec<PathBuf>,
+) -> Result<Source<'src>> {
+    if !src.lines().any(|line| parse_include(line).is_some()) {
+        return Ok(Source {
+            src: Cow::Borrowed(src),
+            root: src,
+            files: vec![],
+            lines: vec![],
+        });
+    }
+
+    let mut source = Source {
+        src: Cow::Borrowed(""),
+        root: src,
+        files: vec![],
+        lines: vec![],
+    };
+    let mut stack = vec![];
+    let expanded = expand(src, None, dir, &mut stack, &mut source, included)?;
+    source.src = Cow::Owned(expanded);
+    Ok(source)
+}
+
+// -----------------------------------------------------------------------------
+//   - Source -
+//   The template with the includes spliced in, and where each line came from,
+//   so errors can point at the line in the file it was included from.
+// -----------------------------------------------------------------------------
+#[derive(Debug)]
+pub(crate) struct Source<'src> {
+    pub(crate) src: Cow<'src, str>,
+    root: &'src str,
+    // The path and content of every included file
+    files: Vec<(PathBuf, String)>,
+    // One entry per line in `src`.
+    // If there are no includes this is empty, as every line is in the root template
+    lines: Vec<Line>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Line {
+    // Index into `files`, or `None` for the root template
+    file: Option<usize>,
+    // Line number in the file, starting at zero
+    line: usize,
+    // The indentation added in front of the line by the include(s)
+    indent: usize,
+}
+
+impl Source<'_> {
+    // Point the error at the file and line it came from
+    pub(crate) fn locate(&self, mut error: CompilerError) -> CompilerError {
+        let Some(line) = self.lines.get(error.span.line - 1) else {
+            return error;
+        };
+
+        let (path, src) = match line.file {
+            Some(index) => {
+                let (path, src) = &self.files[index];
+                (Some(path.clone()), src.as_str())
+            }
+            None => (None, self.root),
+        };
+
+        // The offset of the error within its line, without the added indentation
+        let line_start = self.src[..error.span.offset]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1);
+        let offset = (error.span.offset - line_start).saturating_sub(line.indent);
+        let file_line_start = src
+            .split_inclusive('\n')
+            .take(line.line)
+            .map(str::len)
+            .sum::<usize>();
+
+        error.span.offset = file_line_start + offset;
+        error.span.line = line.line + 1;
+        error.span.col = error.span.col.saturating_sub(line.indent).max(1);
+        error.src = src.to_string();
+        error.path = path;
+        error
+    }
+}
+
+fn expand(
+    src: &str,
+    file: Option<usize>,
+    dir: &Path,
+    stack: &mut Vec<PathBuf>,
+    source: &mut Source<'_>,
+    included: &mut Vec<PathBuf>,
+) -> Result<String> {
+    let mut output = String::with_capacity(src.len());
+
+    for (line_no, line) in src.lines().enumerate() {
+        let Some((indent, path)) = parse_include(line) else {
+            output.push_str(line);
+            output.push('\n');
+            source.lines.push(Line {
+                file,
+                line: line_no,
+                indent: 0,
+            });
+            continue;
+        };
+
+        let path = dir.join(path);
+        // Even if the file can't be read, so it's included once it exists
+        included.push(path.clone());
+
+        let include_error = |error| Error::Include {
+            path: path.clone(),
+            error,
+        };
+
+        let canonical = path.canonicalize().map_err(include_error)?;
+        if stack.contains(&canonical) {
+            return Err(Error::IncludeCycle(path));
+        }
+
+        let src = fs::read_to_string(&canonical).map_err(include_error)?;
+        let dir = canonical.parent().unwrap_or(dir).to_owned();
+
+        let included_file = source.files.len();
+        source.files.push((path.clone(), src.clone()));
+        let first_line = source.lines.len();
+
+        stack.push(canonical);
+        let expanded = expand(&src, Some(included_file), &dir, stack, source, included)?;
+        stack.pop();
+
+        for (line, mapping) in expanded.lines().zip(&mut source.lines[first_line..]) {
+            if !line.trim().is_empty() {
+                output.push_str(indent);
+                mapping.indent += indent.len();
+            }
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+
+    Ok(output)
+}
+
+// Returns the indentation and the path of an include.
+// `@include` without a quoted path is not an include (e.g a view named `include`).
+fn parse_include(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    let path = trimmed
+        .strip_prefix(INCLUDE)?
+        .trim()
+        .strip_prefix('"')?
+        .strip_suffix('"')?;
+    Some((indent, path))
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_compiler::ViewIds;
+
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("anathema-include-{name}-{}", std::process::id()));
+        fs::create_dir_all(dir.join("partials")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn splice_indented() {
+        let dir = temp_dir("splice");
+        fs::write(
+            dir.join("partials/header.tiny"),
+            "text 'header'\n@include \"title.tiny\"",
+        )
+        .unwrap();
+        fs::write(dir.join("partials/title.tiny"), "text 'title'").unwrap();
+
+        let src = "vstack\n    @include \"partials/header.tiny\"\n    text 'body'";
+        let mut included = vec![];
+        let output = resolve_includes(src, &dir, &mut included).unwrap();
+        assert_eq!(
+            output.src,
+            "vstack\n    text 'header'\n    text 'title'\n    text 'body'\n"
+        );
+        assert_eq!(
+            included,
+            vec![
+                dir.join("partials/header.tiny"),
+                dir.join("partials/title.tiny")
+            ]
+        );
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn include_cycle() {
+        let dir = temp_dir("cycle");
+        fs::write(dir.join("a.tiny"), "@include \"partials/b.tiny\"").unwrap();
+        fs::write(dir.join("partials/b.tiny"), "@include \"../a.tiny\"").unwrap();
+
+        let error = resolve_includes("@include \"a.tiny\"", &dir, &mut vec![]).unwrap_err();
+        assert!(matches!(&error, Error::IncludeCycle(path) if path.ends_with("../a.tiny")));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn missing_include() {
+        let mut included = vec![];
+        let src = "@include \"does-not-exist.tiny\"";
+        let error = resolve_includes(src, Path::new("."), &mut included).unwrap_err();
+        assert!(error.to_string().contains("does-not-exist.tiny"));
+        // The file is still watched, so the template is compiled once the file exists
+        assert_eq!(included, vec![Path::new("./does-not-exist.tiny")]);
+    }
+
+    #[test]
+    fn locate_errors() {
+        let dir = temp_dir("locate");
+        fs::write(dir.join("partials/title.tiny"), "text 'title'\ntext a.b(1)").unwrap();
+
+        let src = "vstack\n    @include \"partials/title.tiny\"\n    text 'body'";
+        let source = resolve_includes(src, &dir, &mut vec![]).unwrap();
+
+        // The error is on the second line of the included file,
+        // which is the third line of the expanded template.
+        let error = anathema_compiler::compile(&source.src, &mut ViewIds::new()).unwrap_err();
+        assert_eq!(error.span.line, 3);
+
+        let error = source.locate(error);
+        let path = dir.join("partials/title.tiny");
+        assert_eq!(error.path.as_ref(), Some(&path));
+        assert_eq!(error.span.line, 2);
+        assert_eq!(error.src, "text 'title'\ntext a.b(1)");
+        assert_eq!((error.span.col, error.span.offset), (6, 18));
+
+        let error = error.to_string();
+        assert!(error.contains(&format!("--> {}:2:", path.display())));
+        assert!(error.contains("2 | text a.b(1)\n"));
+
+        // Lines in the root template after the include
+        fs::write(dir.join("partials/title.tiny"), "text 'one'\ntext 'two'").unwrap();
+        let src = "@include \"partials/title.tiny\"\ntext a.b(1)";
+        let source = resolve_includes(src, &dir, &mut vec![]).unwrap();
+        let error = anathema_compiler::compile(&source.src, &mut ViewIds::new()).unwrap_err();
+        let error = source.locate(error);
+        assert_eq!((error.path, error.span.line), (None, 2));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn not_an_include() {
+        let src = "@include\n@includes \"a\"";
+        assert!(matches!(
+            resolve_includes(src, Path::new("."), &mut vec![])
+                .unwrap()
+                .src,
+            Cow::Borrowed(_)
+        ));
+    }
+}
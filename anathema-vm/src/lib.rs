@@ -1,9 +1,13 @@
 mod error;
+mod include;
 mod scope;
 mod vm;
 
+use std::path::PathBuf;
+
 use anathema_compiler::{ViewId, ViewIds};
 use anathema_values::hashmap::HashMap;
+use anathema_values::{FunctionResult, Functions, ValueRef};
 use anathema_widget_core::expressions::{root_view, Expression};
 use anathema_widget_core::views::{AnyView, RegisteredViews, View};
 pub use vm::VirtualMachine;
@@ -14,6 +18,9 @@ struct ViewTemplates {
     view_ids: ViewIds,
     inner: HashMap<ViewId, Template>,
     dep_list: Vec<ViewId>,
+    include_dir: PathBuf,
+    // The files included by the last compile
+    included: Vec<PathBuf>,
 }
 
 impl ViewTemplates {
@@ -22,6 +29,8 @@ impl ViewTemplates {
             view_ids: ViewIds::new(),
             inner: HashMap::new(),
             dep_list: vec![],
+            include_dir: PathBuf::from("."),
+            included: vec![],
         }
     }
 
@@ -62,6 +71,7 @@ impl ViewTemplates {
 pub struct Templates {
     root: String,
     view_templates: ViewTemplates,
+    functions: Functions,
 }
 
 impl Templates {
@@ -71,13 +81,40 @@ impl Templates {
         Self {
             root,
             view_templates,
+            functions: Functions::new(),
         }
     }
 
+    /// Replace the root template.
+    /// The new template is used the next time the templates are compiled.
+    pub fn set_root(&mut self, root: String) {
+        self.root = root;
+    }
+
+    /// The directory that `@include "path/to/file.tiny"` is relative to,
+    /// in the root template and the view templates (default: the current directory).
+    ///
+    /// Includes inside an included file are relative to that file.
+    pub fn set_include_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.view_templates.include_dir = dir.into();
+    }
+
+    /// The files included by the last compile, in the root template and the view templates.
+    ///
+    /// If the compile failed this is every file included up to the error,
+    /// including a file that couldn't be read.
+    pub fn included_files(&self) -> &[PathBuf] {
+        &self.view_templates.included
+    }
+
     pub fn compile(&mut self) -> Result<CompiledTemplates> {
+        self.view_templates.included.clear();
         let expressions = templates(&self.root, &mut self.view_templates)?;
         let root = root_view(expressions, self.view_templates.view_ids.root_id());
-        Ok(CompiledTemplates { root: vec![root] })
+        Ok(CompiledTemplates {
+            root: vec![root],
+            functions: self.functions.clone(),
+        })
     }
 
     pub fn add_view(
@@ -100,16 +137,35 @@ impl Templates {
         let view_id = self.view_templates.insert(ident.clone(), template);
         RegisteredViews::add_prototype(view_id.0, f)
     }
+
+    /// Register a function that can be called from the templates, e.g `format_currency(price)`.
+    ///
+    /// The functions are only available to these templates
+    /// (and the templates compiled from them).
+    /// Errors returned by the function are reported with the function name
+    /// through [`anathema_values::drain_resolve_errors`].
+    pub fn register_fn<F>(&mut self, name: impl Into<String>, f: F)
+    where
+        F: Fn(&[ValueRef<'_>]) -> FunctionResult + Send + Sync + 'static,
+    {
+        self.functions.register_fn(name, f)
+    }
 }
 
 pub struct CompiledTemplates {
     root: Vec<Expression>,
+    functions: Functions,
 }
 
 impl CompiledTemplates {
     pub fn expressions(&self) -> &[Expression] {
         &self.root
     }
+
+    /// The functions registered with the [`Templates`] these were compiled from.
+    pub fn functions(&self) -> &Functions {
+        &self.functions
+    }
 }
 
 enum Template {
@@ -118,7 +174,9 @@ enum Template {
 }
 
 fn templates(root: &str, views: &mut ViewTemplates) -> Result<Vec<Expression>> {
-    let (instructions, constants) = anathema_compiler::compile(root, &mut views.view_ids)?;
+    let source = include::resolve_includes(root, &views.include_dir, &mut views.included)?;
+    let (instructions, constants) = anathema_compiler::compile(&source.src, &mut views.view_ids)
+        .map_err(|error| source.locate(error))?;
     let vm = VirtualMachine::new(instructions, constants);
     vm.exec(views)
 }
@@ -39,17 +39,23 @@ impl<'vm> Scope<'vm> {
                 Instruction::For {
                     binding,
                     data,
+                    sorted_by,
+                    filter,
                     size,
                 } => {
                     let binding = self.consts.lookup_string(binding);
 
                     let collection = self.consts.lookup_value(data).clone();
+                    let sorted_by = sorted_by.map(|key| self.consts.lookup_value(key).clone());
+                    let filter = filter.map(|cond| self.consts.lookup_value(cond).clone());
 
                     let body = self.instructions.drain(..size).collect();
                     let body = Scope::new(body, self.consts).exec(views)?;
                     let template = Expression::Loop(LoopExpr {
                         binding: binding.into(),
                         collection,
+                        sorted_by,
+                        filter,
                         body,
                     });
 
@@ -69,11 +75,7 @@ impl<'vm> Scope<'vm> {
                         elses: vec![],
                     };
 
-                    loop {
-                        let Some(&Instruction::Else { cond, size }) = self.instructions.first()
-                        else {
-                            break;
-                        };
+                    while let Some(&Instruction::Else { cond, size }) = self.instructions.first() {
                         self.instructions.remove(0);
                         let cond = cond.map(|cond| self.consts.lookup_value(cond));
 
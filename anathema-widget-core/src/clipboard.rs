@@ -0,0 +1,91 @@
+// -----------------------------------------------------------------------------
+//   - Clipboard -
+//   The clipboard is owned by the runtime, and passed to the views
+//   while they handle an event (see `RuntimeContext::clipboard`).
+// -----------------------------------------------------------------------------
+
+/// Read and write text to a clipboard (generally the system clipboard).
+///
+/// Implement this for a clipboard crate and pass it to
+/// [`Runtime::set_clipboard`](../../anathema_runtime/struct.Runtime.html#method.set_clipboard).
+/// The runtime has a clipboard using the system clipboard with the `clipboard` feature.
+pub trait Clipboard {
+    /// The text on the clipboard, or `None` if the clipboard is empty
+    /// or can't be read.
+    fn get_text(&mut self) -> Option<String>;
+
+    /// Put text on the clipboard.
+    /// Returns false if the clipboard can't be written to.
+    fn set_text(&mut self, text: &str) -> bool;
+}
+
+/// Insert text into `input` at the `cursor` (a char index), as when pasting into a text field.
+///
+/// * Line breaks are replaced by spaces, as the input is a single line
+/// * If `max_len` is given, only as many chars as fit are inserted
+///
+/// Returns the cursor position after the inserted text.
+///
+/// ```
+/// # use anathema_widget_core::clipboard::paste;
+/// let mut input = String::from("hello");
+/// let cursor = paste(&mut input, 5, " world", Some(8));
+/// assert_eq!(input, "hello wo");
+/// assert_eq!(cursor, 8);
+/// ```
+pub fn paste(input: &mut String, cursor: usize, text: &str, max_len: Option<usize>) -> usize {
+    let len = input.chars().count();
+    let cursor = cursor.min(len);
+    let available = max_len.map_or(usize::MAX, |max| max.saturating_sub(len));
+
+    let text = text
+        .trim_end_matches(['\r', '\n'])
+        .replace("\r\n", " ")
+        .replace(['\r', '\n'], " ");
+    let text = text.chars().take(available).collect::<String>();
+
+    let index = input
+        .char_indices()
+        .nth(cursor)
+        .map_or(input.len(), |(index, _)| index);
+    input.insert_str(index, &text);
+
+    cursor + text.chars().count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn paste_at_cursor() {
+        let mut input = String::from("héllo");
+        let cursor = paste(&mut input, 1, "ey", None);
+        assert_eq!(input, "heyéllo");
+        assert_eq!(cursor, 3);
+
+        // Past the end of the input
+        let mut input = String::from("ab");
+        assert_eq!(paste(&mut input, 10, "c", None), 3);
+        assert_eq!(input, "abc");
+    }
+
+    #[test]
+    fn paste_respects_max_len() {
+        let mut input = String::from("abc");
+        let cursor = paste(&mut input, 0, "12345", Some(5));
+        assert_eq!(input, "12abc");
+        assert_eq!(cursor, 2);
+
+        let mut input = String::from("full");
+        assert_eq!(paste(&mut input, 4, "more", Some(4)), 4);
+        assert_eq!(input, "full");
+    }
+
+    #[test]
+    fn paste_single_line() {
+        let mut input = String::new();
+        paste(&mut input, 0, "one\r\ntwo\nthree\n", None);
+        assert_eq!(input, "one two three");
+    }
+}
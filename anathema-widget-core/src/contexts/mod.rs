@@ -3,6 +3,7 @@ use std::ops::Deref;
 use anathema_render::{Screen, ScreenPos, Size, Style};
 use unicode_width::UnicodeWidthChar;
 
+use crate::clipboard::Clipboard;
 use crate::layout::Constraints;
 use crate::{Align, LocalPos, Pos, Region};
 
@@ -227,6 +228,48 @@ impl PositionCtx {
     }
 }
 
+// -----------------------------------------------------------------------------
+//     - Runtime context -
+// -----------------------------------------------------------------------------
+/// Access to the runtime while a view handles an event,
+/// see [`View::on_event_with_context`](crate::views::View::on_event_with_context).
+pub struct RuntimeContext<'a> {
+    clipboard: Option<&'a mut dyn Clipboard>,
+    quit: bool,
+}
+
+impl<'a> RuntimeContext<'a> {
+    pub fn new(clipboard: Option<&'a mut dyn Clipboard>) -> Self {
+        Self {
+            clipboard,
+            quit: false,
+        }
+    }
+
+    /// The clipboard of the runtime, or `None` if there is no clipboard
+    /// (e.g if the runtime doesn't have access to the system clipboard).
+    pub fn clipboard(&mut self) -> Option<&mut dyn Clipboard> {
+        match &mut self.clipboard {
+            Some(clipboard) => Some(&mut **clipboard),
+            None => None,
+        }
+    }
+
+    /// Stop the runtime once the event is handled.
+    ///
+    /// This is useful if ctrl+c is used for something else (e.g copying),
+    /// and [`Runtime::enable_ctrlc`](../../anathema_runtime/struct.Runtime.html#structfield.enable_ctrlc)
+    /// is disabled.
+    pub fn quit(&mut self) {
+        self.quit = true;
+    }
+
+    /// True if [`RuntimeContext::quit`] was called.
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+}
+
 #[cfg(test)]
 mod test {
     use anathema_render::Screen;
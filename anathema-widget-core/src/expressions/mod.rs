@@ -57,6 +57,8 @@ impl SingleNodeExpr {
         let widget = WidgetContainer {
             display: context.get("display"),
             background: context.get("background"),
+            gradient: context.get("gradient"),
+            overflow: context.get("overflow"),
             pos: Pos::ZERO,
             size: Size::ZERO,
             inner: Factory::exec(context)?,
@@ -93,6 +95,11 @@ pub struct LoopExpr {
     pub body: Vec<Expression>,
     pub binding: Path,
     pub collection: ValueExpr,
+    /// Iterate over the values ordered by a key, e.g `for item in items sorted_by item.name`.
+    /// The sort is stable.
+    pub sorted_by: Option<ValueExpr>,
+    /// Skip the values where the condition is false, e.g `for item in items where item.active`
+    pub filter: Option<ValueExpr>,
 }
 
 impl LoopExpr {
@@ -116,6 +123,11 @@ impl LoopExpr {
                                 list.subscribe(node_id.clone());
                                 list.len()
                             }
+                            // A map is iterated over in insertion order
+                            ValueRef::Map(map) => {
+                                map.state_subscribe(node_id.clone());
+                                map.state_len().unwrap_or(0)
+                            }
                             _ => 0,
                         };
 
@@ -130,7 +142,9 @@ impl LoopExpr {
             &self.body,
             self.binding.clone(),
             collection,
-            node_id.child(0),
+            self.sorted_by.as_ref(),
+            self.filter.as_ref(),
+            node_id.clone(),
         );
 
         let node = Node {
@@ -214,6 +228,7 @@ impl ViewExpr {
         let node = Node {
             kind: NodeKind::View(View {
                 view: RegisteredViews::get(self.id)?,
+                id: self.id,
                 nodes: Nodes::new(&self.body, node_id.child(0)),
                 state,
                 tabindex,
@@ -352,4 +367,16 @@ mod test {
         let _ = expr.eval().unwrap();
         let _ = expr.eval().unwrap();
     }
+
+    #[test]
+    fn recycle_consumed_view() {
+        RegisteredViews::add_view(100, AView);
+        let exprs = [view_expression(100, None, vec![])];
+        let mut runtime = test_runtime(&exprs);
+        runtime.layout().unwrap();
+        assert!(RegisteredViews::get(100).is_err());
+
+        runtime.nodes.recycle_views();
+        assert!(RegisteredViews::get(100).is_ok());
+    }
 }
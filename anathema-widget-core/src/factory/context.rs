@@ -1,3 +1,4 @@
+use anathema_render::Link;
 use anathema_values::{Attributes, Context, DynValue, NodeId, Value};
 
 use crate::WidgetStyle;
@@ -32,7 +33,9 @@ impl<'a> FactoryContext<'a> {
     }
 
     pub fn style(&self) -> WidgetStyle {
+        let hyperlink: Value<String> = self.get("hyperlink");
         WidgetStyle {
+            link: hyperlink.value_ref().map(|url| Link::new(url)),
             fg: self.get("foreground"),
             bg: self.get("background"),
             bold: self.get("bold"),
@@ -42,6 +45,7 @@ impl<'a> FactoryContext<'a> {
             crossed_out: self.get("crossed-out"),
             overlined: self.get("overlined"),
             inverse: self.get("inverse"),
+            hyperlink,
         }
     }
 
@@ -55,7 +59,8 @@ impl<'a> FactoryContext<'a> {
 
 #[cfg(test)]
 mod test {
-    use anathema_values::testing::TestState;
+    use anathema_render::{Color, Gradient, GradientDirection};
+    use anathema_values::testing::{list, TestState};
     use anathema_values::ValueExpr;
 
     use super::*;
@@ -72,4 +77,30 @@ mod test {
         let name = ctx.get::<String>("name");
         assert_eq!("Dirk Gently", name.str());
     }
+
+    #[test]
+    fn get_gradient() {
+        let state = TestState::new();
+        let ctx = Context::root(&state);
+        let mut attributes = Attributes::new();
+        attributes.insert("list".to_string(), *list(["red", "vertical", "#0000ff"]));
+        attributes.insert("solid".to_string(), "red".into());
+        attributes.insert("invalid".to_string(), *list(["vertical"]));
+
+        let ctx = FactoryContext::new(&ctx, 0.into(), "border", &attributes, Value::Empty);
+
+        let gradient = ctx.get::<Gradient>("list");
+        let gradient = gradient.value_ref().unwrap();
+        assert_eq!(gradient.direction, GradientDirection::Vertical);
+        assert_eq!(
+            gradient.stops().collect::<Vec<_>>(),
+            vec![Color::Red, Color::Rgb { r: 0, g: 0, b: 255 }]
+        );
+
+        let solid = ctx.get::<Gradient>("solid");
+        let solid = solid.value_ref().unwrap().stops().collect::<Vec<_>>();
+        assert_eq!(solid, vec![Color::Red]);
+
+        assert!(ctx.get::<Gradient>("invalid").value_ref().is_none());
+    }
 }
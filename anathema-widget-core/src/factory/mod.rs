@@ -11,15 +11,38 @@ mod context;
 
 const RESERVED_NAMES: &[&str] = &["if", "for", "else", "with", "view"];
 
+/// Create a widget from a template node (`ident [attributes]: text`).
+///
+/// This is implemented for closures, so a widget can be registered
+/// without a dedicated factory type:
+/// ```
+/// # use anathema_widget_core::{AnyWidget, Factory, FactoryContext};
+/// # use anathema_widget_core::error::Result;
+/// # fn gauge(ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> { todo!() }
+/// Factory::register("gauge", |ctx: FactoryContext<'_>| gauge(ctx)).unwrap();
+/// ```
 pub trait WidgetFactory: Send + Sync {
     fn make(&self, context: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>>;
 }
 
+impl<F> WidgetFactory for F
+where
+    F: Fn(FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> + Send + Sync,
+{
+    fn make(&self, context: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        self(context)
+    }
+}
+
 static FACTORIES: OnceLock<RwLock<HashMap<String, Box<dyn WidgetFactory>>>> = OnceLock::new();
 
 pub struct Factory;
 
 impl Factory {
+    /// Create a widget with the factory registered under `ctx.ident`.
+    ///
+    /// Returns [`Error::UnregisteredWidget`] with the name of the widget
+    /// if there is no such factory.
     pub fn exec(ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
         let factories = FACTORIES.get_or_init(Default::default).read();
         let factory = factories
@@ -29,6 +52,9 @@ impl Factory {
         Ok(Box::new(widget))
     }
 
+    /// Register a widget factory, so `ident` can be used in templates.
+    ///
+    /// Fails if the name is reserved (e.g `for`) or is already registered.
     pub fn register(ident: impl Into<String>, factory: impl WidgetFactory + 'static) -> Result<()> {
         let ident = ident.into();
         if RESERVED_NAMES.contains(&ident.as_str()) {
@@ -46,6 +72,25 @@ impl Factory {
     }
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::nodes::TestWidget;
+
+    #[test]
+    fn register_closure() {
+        let factory =
+            |ctx: FactoryContext<'_>| Ok(Box::new(TestWidget(ctx.text)) as Box<dyn AnyWidget>);
+        Factory::register("closure_widget", factory).unwrap();
+
+        let err = Factory::register("closure_widget", factory).unwrap_err();
+        assert!(matches!(err, Error::ExistingName(name) if name == "closure_widget"));
+
+        let err = Factory::register("for", factory).unwrap_err();
+        assert!(matches!(err, Error::ReservedName(_)));
+    }
+}
+
 // // // -----------------------------------------------------------------------------
 // // //     - Canvas -
 // // // -----------------------------------------------------------------------------
@@ -1,5 +1,7 @@
 use anathema_render::Size;
 
+use crate::error::{Error, Result};
+
 /// `Constraints` are used to ensure that a widget doesn't size it self outside of a set of given bounds.
 /// A constraint can be tight, meaning then minimum and maximum width / height are the same.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -109,6 +111,112 @@ impl Constraints {
         self.min_height = self.max_height;
     }
 
+    /// Shrink the max width / height by a given size.
+    /// Returns [`Error::InsufficientSpaceAvailble`] if the size is larger than the max width or
+    /// the max height.
+    ///
+    /// Unbounded constraints remain unbounded, and the min width / height are
+    /// clamped to the new max width / height.
+    /// ```
+    /// # use anathema_widget_core::layout::Constraints;
+    /// # use anathema_render::Size;
+    /// let constraints = Constraints::new(10, 10);
+    /// let constraints = constraints.shrink_checked(Size::new(2, 2)).unwrap();
+    /// assert_eq!(constraints.max_width, 8);
+    /// assert!(constraints.shrink_checked(Size::new(9, 0)).is_err());
+    /// ```
+    pub fn shrink_checked(mut self, size: Size) -> Result<Self> {
+        if !self.is_width_unbounded() {
+            self.max_width = self
+                .max_width
+                .checked_sub(size.width)
+                .ok_or(Error::InsufficientSpaceAvailble)?;
+        }
+
+        if !self.is_height_unbounded() {
+            self.max_height = self
+                .max_height
+                .checked_sub(size.height)
+                .ok_or(Error::InsufficientSpaceAvailble)?;
+        }
+
+        self.normalize();
+        Ok(self)
+    }
+
+    /// Shrink the max width / height by a given size, stopping at zero.
+    ///
+    /// Unbounded constraints remain unbounded, and the min width / height are
+    /// clamped to the new max width / height.
+    /// ```
+    /// # use anathema_widget_core::layout::Constraints;
+    /// # use anathema_render::Size;
+    /// let constraints = Constraints::new(10, 10);
+    /// let constraints = constraints.shrink_saturating(Size::new(12, 2));
+    /// assert_eq!(constraints.max_width, 0);
+    /// assert_eq!(constraints.max_height, 8);
+    /// ```
+    pub fn shrink_saturating(mut self, size: Size) -> Self {
+        if !self.is_width_unbounded() {
+            self.max_width = self.max_width.saturating_sub(size.width);
+        }
+
+        if !self.is_height_unbounded() {
+            self.max_height = self.max_height.saturating_sub(size.height);
+        }
+
+        self.normalize();
+        self
+    }
+
+    /// Clamp the min width / height to the max width / height,
+    /// so the minimum is never larger than the maximum.
+    /// ```
+    /// # use anathema_widget_core::layout::Constraints;
+    /// let mut constraints = Constraints::new(5, 5);
+    /// constraints.min_width = 10;
+    /// constraints.normalize();
+    /// assert_eq!(constraints.min_width, 5);
+    /// ```
+    pub fn normalize(&mut self) {
+        self.min_width = self.min_width.min(self.max_width);
+        self.min_height = self.min_height.min(self.max_height);
+    }
+
+    /// The constraints that satisfy both `self` and `other`.
+    ///
+    /// If the constraints don't overlap the result is tight
+    /// at the smaller of the two max width / height:
+    /// ```
+    /// # use anathema_widget_core::layout::Constraints;
+    /// let mut wide = Constraints::new(20, 20);
+    /// wide.min_width = 15;
+    /// let narrow = Constraints::new(10, 20);
+    ///
+    /// let constraints = wide.intersect(&narrow);
+    /// assert_eq!(constraints.min_width, 10);
+    /// assert_eq!(constraints.max_width, 10);
+    /// ```
+    pub fn intersect(&self, other: &Constraints) -> Constraints {
+        let mut constraints = Self {
+            min_width: self.min_width.max(other.min_width),
+            min_height: self.min_height.max(other.min_height),
+            max_width: self.max_width.min(other.max_width),
+            max_height: self.max_height.min(other.max_height),
+        };
+        constraints.normalize();
+        constraints
+    }
+
+    /// Clamp a size to fit within the constraints.
+    /// If the constraints are not normalized the max width / height takes precedence.
+    pub fn clamp_size(&self, size: Size) -> Size {
+        Size::new(
+            size.width.max(self.min_width).min(self.max_width),
+            size.height.max(self.min_height).min(self.max_height),
+        )
+    }
+
     pub fn expand_horz(&mut self, mut size: Size) -> Size {
         size.width = self.max_width;
         size
@@ -126,4 +234,97 @@ impl Constraints {
 }
 
 #[cfg(test)]
-mod test {}
+mod test {
+    use super::*;
+
+    #[test]
+    fn shrink_checked_at_boundary() {
+        let constraints = Constraints::new(4, 3);
+
+        let shrunk = constraints.shrink_checked(Size::new(4, 3)).unwrap();
+        assert_eq!(shrunk.max_width, 0);
+        assert_eq!(shrunk.max_height, 0);
+
+        assert!(constraints.shrink_checked(Size::new(5, 3)).is_err());
+        assert!(constraints.shrink_checked(Size::new(4, 4)).is_err());
+    }
+
+    #[test]
+    fn shrink_saturating_at_boundary() {
+        let constraints = Constraints::new(4, 3);
+
+        let shrunk = constraints.shrink_saturating(Size::new(4, 3));
+        assert_eq!(shrunk.max_width, 0);
+        assert_eq!(shrunk.max_height, 0);
+
+        let shrunk = constraints.shrink_saturating(Size::new(5, 4));
+        assert_eq!(shrunk.max_width, 0);
+        assert_eq!(shrunk.max_height, 0);
+    }
+
+    #[test]
+    fn shrink_clamps_min() {
+        let mut constraints = Constraints::new(10, 10);
+        constraints.min_width = 10;
+        constraints.min_height = 2;
+
+        let shrunk = constraints.shrink_saturating(Size::new(2, 2));
+        assert_eq!(shrunk.min_width, 8);
+        assert_eq!(shrunk.min_height, 2);
+
+        let shrunk = constraints.shrink_checked(Size::new(2, 9)).unwrap();
+        assert_eq!(shrunk.min_width, 8);
+        assert_eq!(shrunk.min_height, 1);
+    }
+
+    #[test]
+    fn intersect_overlapping() {
+        let mut a = Constraints::new(10, 8);
+        a.min_width = 2;
+        let mut b = Constraints::new(6, None);
+        b.min_height = 3;
+
+        let constraints = a.intersect(&b);
+        assert_eq!(constraints.min_width, 2);
+        assert_eq!(constraints.max_width, 6);
+        assert_eq!(constraints.min_height, 3);
+        assert_eq!(constraints.max_height, 8);
+        assert_eq!(constraints, b.intersect(&a));
+    }
+
+    #[test]
+    fn intersect_disjoint() {
+        let mut a = Constraints::new(20, 20);
+        a.min_width = 15;
+        a.min_height = 15;
+        let b = Constraints::new(10, 5);
+
+        let constraints = a.intersect(&b);
+        assert!(constraints.is_width_tight());
+        assert!(constraints.is_height_tight());
+        assert_eq!(constraints.max_width, 10);
+        assert_eq!(constraints.max_height, 5);
+    }
+
+    #[test]
+    fn clamp_size_to_constraints() {
+        let mut constraints = Constraints::new(10, 10);
+        constraints.min_width = 3;
+        assert_eq!(constraints.clamp_size(Size::new(1, 20)), Size::new(3, 10));
+        assert_eq!(constraints.clamp_size(Size::new(5, 5)), Size::new(5, 5));
+
+        // Inverted constraints, max wins
+        constraints.min_width = 12;
+        assert_eq!(constraints.clamp_size(Size::ZERO), Size::new(10, 0));
+    }
+
+    #[test]
+    fn shrink_unbounded() {
+        let constraints = Constraints::unbounded();
+        let shrunk = constraints.shrink_checked(Size::new(2, 2)).unwrap();
+        assert!(shrunk.is_unbounded());
+        assert!(constraints
+            .shrink_saturating(Size::new(2, 2))
+            .is_unbounded());
+    }
+}
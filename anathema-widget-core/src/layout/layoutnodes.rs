@@ -34,7 +34,8 @@ impl<'nodes, 'state, 'expr> LayoutNodes<'nodes, 'state, 'expr> {
     where
         F: FnMut(LayoutNode<'_, '_, 'expr>) -> Result<()>,
     {
-        self.nodes
+        let _ = self
+            .nodes
             .next(self.context, &mut |widget, children, context| {
                 let node = LayoutNode {
                     widget,
@@ -70,6 +71,16 @@ impl<'nodes, 'state, 'expr> LayoutNodes<'nodes, 'state, 'expr> {
         }
     }
 
+    /// An iterator over the nodes that are already evaluated,
+    /// e.g to lay out the nodes again after [`for_each`](Self::for_each).
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = LayoutNode<'_, 'state, 'expr>> + '_ {
+        self.nodes.iter_mut().map(|(widget, children)| LayoutNode {
+            widget,
+            children,
+            context: self.context,
+        })
+    }
+
     pub fn filter<F>(&mut self, f: F) -> impl Iterator<Item = LayoutNode<'_, 'state, 'expr>> + '_
     where
         F: Fn(&WidgetContainer<'expr>) -> bool + 'static,
@@ -95,6 +106,27 @@ impl<'widget, 'state, 'expr> LayoutNode<'widget, 'state, 'expr> {
     pub fn layout(&mut self, constraints: Constraints) -> Result<Size> {
         self.widget.layout(self.children, constraints, self.context)
     }
+
+    /// Compute the size the widget wants for the given constraints.
+    ///
+    /// Measuring is idempotent: it can be called any number of times before the
+    /// widget is laid out (e.g once with loose constraints and once with tight
+    /// constraints), and a subsequent [`layout`](Self::layout) with the same
+    /// constraints returns the same size.
+    ///
+    /// It is not free of side effects though. The size of the widget itself
+    /// is kept, but its children are laid out with the given constraints,
+    /// so their sizes (and the size of anything below them) are the ones
+    /// from the last measure until the widget is laid out again.
+    ///
+    /// Measuring a widget lays out its entire subtree, so a widget that is
+    /// measured and then laid out costs twice as much as a single layout,
+    /// and this compounds when measuring nested widgets.
+    /// Avoid measuring expensive subtrees (e.g long lists) more than necessary.
+    pub fn measure(&mut self, constraints: Constraints) -> Result<Size> {
+        self.widget
+            .measure(self.children, constraints, self.context)
+    }
 }
 
 impl<'widget, 'state, 'expr> Deref for LayoutNode<'widget, 'state, 'expr> {
@@ -110,3 +142,45 @@ impl<'widget, 'state, 'expr> DerefMut for LayoutNode<'widget, 'state, 'expr> {
         self.widget
     }
 }
+
+#[cfg(test)]
+mod test {
+    use anathema_values::testing::{list, TestState};
+
+    use super::*;
+    use crate::nodes::make_it_so;
+    use crate::testing::expressions::{expression, for_expression};
+    use crate::testing::nodes::register_test_widget;
+
+    #[test]
+    fn measure_before_layout() {
+        register_test_widget();
+        let body = expression("test", Some("hello".into()), [], []);
+        let exprs = vec![expression(
+            "list",
+            None,
+            [],
+            [for_expression("item", list([1, 2, 3]), [body])],
+        )];
+        let mut nodes = make_it_so(&exprs);
+        let state = TestState::new();
+        let context = Context::root(&state);
+        let mut nodes = LayoutNodes::new(&mut nodes, Constraints::new(80, 25), &context);
+
+        nodes
+            .next(|mut node| {
+                let constraints = Constraints::new(80, 25);
+                let size = node.measure(constraints)?;
+                assert_eq!(size, Size::new(5, 3));
+                assert_eq!(node.size, Size::ZERO);
+
+                // Measuring again gives the same size
+                assert_eq!(node.measure(constraints)?, size);
+
+                assert_eq!(node.layout(constraints)?, size);
+                assert_eq!(node.size, size);
+                Ok(())
+            })
+            .unwrap();
+    }
+}
@@ -3,7 +3,7 @@ use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
 
 use anathema_render::{ScreenPos, Size};
 use anathema_values::{
-    impl_dyn_value, Context, DynValue, Immediate, NodeId, Value, ValueExpr, ValueRef,
+    impl_dyn_value, Context, DynValue, Immediate, NodeId, Num, Owned, Value, ValueExpr, ValueRef,
 };
 pub use layoutnodes::{LayoutNode, LayoutNodes};
 
@@ -133,16 +133,16 @@ impl TryFrom<ValueRef<'_>> for Align {
     type Error = ();
 
     fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
-        let wrap = match value {
-            ValueRef::Str("top") => Self::Top,
-            ValueRef::Str("top-right") => Self::TopRight,
-            ValueRef::Str("right") => Self::Right,
-            ValueRef::Str("bottom-right") => Self::BottomRight,
-            ValueRef::Str("bottom") => Self::Bottom,
-            ValueRef::Str("bottom-left") => Self::BottomLeft,
-            ValueRef::Str("left") => Self::Left,
-            ValueRef::Str("top-left") => Self::TopLeft,
-            ValueRef::Str("centre" | "center") => Self::Centre,
+        let wrap = match value.as_str() {
+            Some("top") => Self::Top,
+            Some("top-right") => Self::TopRight,
+            Some("right") => Self::Right,
+            Some("bottom-right") => Self::BottomRight,
+            Some("bottom") => Self::Bottom,
+            Some("bottom-left") => Self::BottomLeft,
+            Some("left") => Self::Left,
+            Some("top-left") => Self::TopLeft,
+            Some("centre" | "center") => Self::Centre,
             _ => Self::Top,
         };
         Ok(wrap)
@@ -165,9 +165,9 @@ impl TryFrom<ValueRef<'_>> for Display {
     type Error = ();
 
     fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
-        let wrap = match value {
-            ValueRef::Str("hide") => Self::Hide,
-            ValueRef::Str("exclude") => Self::Exclude,
+        let wrap = match value.as_str() {
+            Some("hide") => Self::Hide,
+            Some("exclude") => Self::Exclude,
             _ => Self::Show,
         };
         Ok(wrap)
@@ -176,6 +176,146 @@ impl TryFrom<ValueRef<'_>> for Display {
 
 impl_dyn_value!(Display);
 
+/// Determine what happens to children painted outside of the widget.
+///
+/// Clipping composes: the clipping region of a widget is the intersection of
+/// its own region and the clipping region of its ancestors.
+/// This means a child with `Visible` overflow inside a parent with `Hidden` overflow
+/// is still clipped by the parent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// Children can paint outside of the widget, this is the default for most widgets
+    #[default]
+    Visible,
+    /// Children are clipped to the widget
+    Hidden,
+    /// Children are clipped to the widget, and widgets that support scrolling
+    /// (e.g the `Viewport`) will offset their children.
+    Scroll,
+}
+
+impl Overflow {
+    /// Returns true if the children should be clipped
+    pub fn clips(&self) -> bool {
+        matches!(self, Self::Hidden | Self::Scroll)
+    }
+}
+
+impl TryFrom<ValueRef<'_>> for Overflow {
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
+        match value.as_str() {
+            Some("visible") => Ok(Self::Visible),
+            Some("hidden" | "clip") => Ok(Self::Hidden),
+            Some("scroll") => Ok(Self::Scroll),
+            _ => Err(()),
+        }
+    }
+}
+
+impl_dyn_value!(Overflow);
+
+/// A width or a height, resolved against the space available to the widget
+/// (the max width / height of the constraints) during layout.
+///
+/// In a template:
+/// * a whole number is a number of cells: `width: 10`
+/// * a decimal number from `0.0` to `1.0` is a fraction of the available space: `width: 0.5`.
+///   A larger decimal number is a number of cells, rounded to the nearest cell (`2.0` is two cells)
+/// * a percentage of the available space: `width: "50%"`
+/// * `"auto"` is the size of the child
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Dimension {
+    /// A number of cells
+    Cells(usize),
+    /// A percentage of the available space, where `100.0` is all of it
+    Percent(f32),
+    /// The size of the child
+    #[default]
+    Auto,
+}
+
+impl Dimension {
+    /// The number of cells, given the available space.
+    ///
+    /// Percentages are rounded to whole cells and clamped to the available space.
+    /// Returns `None` for `Auto`, and for a percentage of unbounded space.
+    ///
+    /// ```
+    /// # use anathema_widget_core::layout::Dimension;
+    /// assert_eq!(Dimension::Cells(5).resolve(3), Some(5));
+    /// assert_eq!(Dimension::Percent(50.0).resolve(9), Some(5));
+    /// assert_eq!(Dimension::Percent(150.0).resolve(9), Some(9));
+    /// assert_eq!(Dimension::Auto.resolve(9), None);
+    /// ```
+    pub fn resolve(&self, available: usize) -> Option<usize> {
+        match self {
+            Self::Cells(cells) => Some(*cells),
+            Self::Percent(_) if available == usize::MAX => None,
+            Self::Percent(percent) => {
+                let cells = (available as f64 * percent.max(0.0) as f64 / 100.0).round();
+                Some((cells as usize).min(available))
+            }
+            Self::Auto => None,
+        }
+    }
+}
+
+impl From<usize> for Dimension {
+    fn from(cells: usize) -> Self {
+        Self::Cells(cells)
+    }
+}
+
+impl TryFrom<&str> for Dimension {
+    type Error = ();
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        let value = value.trim();
+        if value == "auto" {
+            return Ok(Self::Auto);
+        }
+
+        if let Some(percent) = value.strip_suffix('%') {
+            return match percent.trim().parse::<f32>() {
+                Ok(percent) if percent >= 0.0 => Ok(Self::Percent(percent)),
+                _ => Err(()),
+            };
+        }
+
+        match value.parse::<Num>() {
+            Ok(num) => Self::try_from(ValueRef::Owned(Owned::Num(num))),
+            Err(_) => Err(()),
+        }
+    }
+}
+
+impl TryFrom<ValueRef<'_>> for Dimension {
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ValueRef::Owned(Owned::Num(Num::Unsigned(cells))) => Ok(Self::Cells(cells as usize)),
+            ValueRef::Owned(Owned::Num(Num::Signed(cells))) if cells >= 0 => {
+                Ok(Self::Cells(cells as usize))
+            }
+            ValueRef::Owned(Owned::Num(Num::Float(fraction)))
+                if (0.0..=1.0).contains(&fraction) =>
+            {
+                Ok(Self::Percent(fraction as f32 * 100.0))
+            }
+            ValueRef::Owned(Owned::Num(Num::Float(cells))) if cells > 1.0 => {
+                Ok(Self::Cells(cells.round() as usize))
+            }
+            ValueRef::Str(s) => Self::try_from(&*s),
+            _ => Err(()),
+        }
+    }
+}
+
+impl_dyn_value!(Dimension);
+
 #[derive(Debug)]
 pub enum HorzEdge {
     /// Position to the left
@@ -205,9 +345,9 @@ impl TryFrom<ValueRef<'_>> for Axis {
     type Error = ();
 
     fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
-        match value {
-            ValueRef::Str("horz" | "horizontal") => Ok(Self::Horizontal),
-            ValueRef::Str("vert" | "vertical") => Ok(Self::Vertical),
+        match value.as_str() {
+            Some("horz" | "horizontal") => Ok(Self::Horizontal),
+            Some("vert" | "vertical") => Ok(Self::Vertical),
             _ => Err(()),
         }
     }
@@ -246,9 +386,9 @@ impl TryFrom<ValueRef<'_>> for Direction {
     type Error = ();
 
     fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
-        match value {
-            ValueRef::Str("fwd" | "forwards" | "forward") => Ok(Self::Forwards),
-            ValueRef::Str("bck" | "backwards" | "backward") => Ok(Self::Backwards),
+        match value.as_str() {
+            Some("fwd" | "forwards" | "forward") => Ok(Self::Forwards),
+            Some("bck" | "backwards" | "backward") => Ok(Self::Backwards),
             _ => Err(()),
         }
     }
@@ -414,7 +554,7 @@ impl TryFrom<LocalPos> for ScreenPos {
 //     - Region -
 // -----------------------------------------------------------------------------
 /// A region in global space
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Region {
     /// The starting position of the region
     pub from: Pos,
@@ -458,6 +598,14 @@ impl Region {
         self.to.x = self.to.x.min(other.to.x);
         self.to.y = self.to.y.min(other.to.y);
     }
+
+    /// The smallest region containing both regions
+    pub fn union(&self, other: &Region) -> Region {
+        Region::new(
+            Pos::new(self.from.x.min(other.from.x), self.from.y.min(other.from.y)),
+            Pos::new(self.to.x.max(other.to.x), self.to.y.max(other.to.y)),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -478,4 +626,66 @@ mod test {
         assert!(a.contains(Pos::ZERO));
         assert!(a.contains(Pos::new(10, 10)));
     }
+
+    #[test]
+    fn region_union() {
+        let a = Region::new(Pos::new(1, 5), Pos::new(3, 6));
+        let b = Region::new(Pos::new(2, 0), Pos::new(8, 2));
+        let expected = Region::new(Pos::new(1, 0), Pos::new(8, 6));
+        assert_eq!(a.union(&b), expected);
+        assert_eq!(b.union(&a), expected);
+    }
+
+    #[test]
+    fn parse_dimension() {
+        let dimension = |value: ValueRef<'_>| Dimension::try_from(value);
+        assert_eq!(
+            dimension(ValueRef::Owned(10u64.into())),
+            Ok(Dimension::Cells(10))
+        );
+        assert_eq!(
+            dimension(ValueRef::Owned(0.25f64.into())),
+            Ok(Dimension::Percent(25.0))
+        );
+        assert_eq!(
+            dimension(ValueRef::Owned(1.0f64.into())),
+            Ok(Dimension::Percent(100.0))
+        );
+        // Only decimal numbers up to one are fractions
+        assert_eq!(
+            dimension(ValueRef::Owned(2.0f64.into())),
+            Ok(Dimension::Cells(2))
+        );
+        assert_eq!(
+            dimension(ValueRef::Owned(2.6f64.into())),
+            Ok(Dimension::Cells(3))
+        );
+        assert_eq!(
+            dimension(ValueRef::Str("50%".into())),
+            Ok(Dimension::Percent(50.0))
+        );
+        assert_eq!(
+            dimension(ValueRef::Str("12".into())),
+            Ok(Dimension::Cells(12))
+        );
+        assert_eq!(dimension(ValueRef::Str("auto".into())), Ok(Dimension::Auto));
+
+        assert!(dimension(ValueRef::Owned((-1i64).into())).is_err());
+        assert!(dimension(ValueRef::Owned((-0.5f64).into())).is_err());
+        assert!(dimension(ValueRef::Owned(f64::NAN.into())).is_err());
+        assert!(dimension(ValueRef::Str("-5%".into())).is_err());
+        assert!(dimension(ValueRef::Str("wide".into())).is_err());
+    }
+
+    #[test]
+    fn resolve_dimension() {
+        // Rounded to whole cells
+        assert_eq!(Dimension::Percent(50.0).resolve(5), Some(3));
+        assert_eq!(Dimension::Percent(33.0).resolve(10), Some(3));
+        assert_eq!(Dimension::Percent(0.0).resolve(10), Some(0));
+
+        // A percentage of unbounded space is the size of the child
+        assert_eq!(Dimension::Percent(50.0).resolve(usize::MAX), None);
+        assert_eq!(Dimension::Cells(4).resolve(usize::MAX), Some(4));
+    }
 }
@@ -1,3 +1,4 @@
+pub mod clipboard;
 pub mod contexts;
 pub mod error;
 mod event;
@@ -15,10 +16,11 @@ pub mod testing;
 pub use anathema_render::Color;
 pub use nodes::{Node, Nodes};
 
-pub use crate::event::{Event, Events, KeyCode, KeyModifiers};
+pub use crate::event::{Event, Events, KeyCode, KeyEventState, KeyModifiers};
 pub use crate::factory::{Factory, FactoryContext, WidgetFactory};
 pub use crate::layout::{
-    Align, Axis, Direction, Display, LayoutNode, LayoutNodes, LocalPos, Pos, Region,
+    Align, Axis, Dimension, Direction, Display, LayoutNode, LayoutNodes, LocalPos, Overflow, Pos,
+    Region,
 };
 pub use crate::style::WidgetStyle;
 pub use crate::views::View;
@@ -1,7 +1,10 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::ops::ControlFlow;
 
 use anathema_values::{
-    Change, Context, Deferred, NextNodeId, NodeId, Path, ScopeStorage, ScopeValue, ValueRef,
+    Change, Context, Deferred, Immediate, NextNodeId, NodeId, Owned, Path, ScopeStorage,
+    ScopeValue, ValueExpr, ValueRef,
 };
 
 use super::Nodes;
@@ -35,6 +38,14 @@ pub struct LoopNode<'e> {
     pub(super) binding: Path,
     pub(super) collection: Collection<'e>,
     pub(super) value_index: usize,
+    sorted_by: Option<&'e ValueExpr>,
+    filter: Option<&'e ValueExpr>,
+    // The indices of the values in the collection, after sorting and filtering.
+    // This is `None` if the loop is neither sorted or filtered.
+    order: Option<Vec<usize>>,
+    // The id of the node wrapping the loop, subscribing to the
+    // values used to sort and filter the collection.
+    parent_id: NodeId,
     node_id: NodeId,
     next_node_id: NextNodeId,
 }
@@ -44,8 +55,11 @@ impl<'e> LoopNode<'e> {
         expressions: &'e [Expression],
         binding: Path,
         collection: Collection<'e>,
-        node_id: NodeId,
+        sorted_by: Option<&'e ValueExpr>,
+        filter: Option<&'e ValueExpr>,
+        parent_id: NodeId,
     ) -> Self {
+        let node_id = parent_id.child(0);
         let next_node_id = NextNodeId::new(node_id.last());
         Self {
             expressions,
@@ -54,6 +68,10 @@ impl<'e> LoopNode<'e> {
             collection,
             value_index: 0,
             current_iteration: 0,
+            sorted_by,
+            filter,
+            order: None,
+            parent_id,
             node_id,
             next_node_id,
         }
@@ -68,6 +86,10 @@ impl<'e> LoopNode<'e> {
     where
         F: FnMut(&mut WidgetContainer<'e>, &mut Nodes<'e>, &Context<'_, 'e>) -> Result<()>,
     {
+        if self.value_index == 0 {
+            self.update_order(scope, context);
+        }
+
         loop {
             scope.value(
                 // TODO: make this into a constant
@@ -120,21 +142,57 @@ impl<'e> LoopNode<'e> {
     }
 
     fn scope_next_value(&mut self, context: &Context<'_, 'e>) -> Option<ScopeValue<'e>> {
-        match self.collection {
-            Collection::Static(expressions) => {
-                let expr = expressions.get(self.value_index)?;
-                let mut resolver = Deferred::new(context.lookup());
-                let val = match expr.eval(&mut resolver) {
-                    ValueRef::Deferred => ScopeValue::Deferred(expr),
-                    value => ScopeValue::Value(value),
+        let index = match &self.order {
+            Some(order) => *order.get(self.value_index)?,
+            None => self.value_index,
+        };
+        collection_value(&self.collection, index, context)
+    }
+
+    // Sort and filter the collection.
+    // Only the indices of the values are sorted, so the values
+    // are never cloned.
+    //
+    // This happens at the start of every iteration of the loop, as the values
+    // used for the sorting and filtering can change without changing the collection.
+    // If the order changes the iterations have to be recreated.
+    fn update_order(&mut self, scope: &mut ScopeStorage<'e>, context: &Context<'_, 'e>) {
+        if self.sorted_by.is_none() && self.filter.is_none() {
+            return;
+        }
+
+        let mut values = vec![];
+        let mut index = 0;
+        while let Some(value) = collection_value(&self.collection, index, context) {
+            scope.insert(self.binding.clone(), value);
+            let scope = context.new_scope(scope);
+            let context = context.with_scope(&scope);
+            let mut resolver = Immediate::new(context.lookup(), &self.parent_id);
+
+            let keep = self
+                .filter
+                .is_none_or(|filter| filter.eval(&mut resolver).is_true());
+
+            if keep {
+                let key = match self.sorted_by {
+                    Some(key) => sort_key(key.eval(&mut resolver)),
+                    None => ValueRef::Empty,
                 };
-                Some(val)
+                values.push((index, key));
             }
-            Collection::State { len, .. } if len == self.value_index => None,
-            Collection::State { expr, .. } => {
-                Some(ScopeValue::DeferredList(self.value_index, expr))
-            }
-            Collection::Empty => None,
+
+            index += 1;
+        }
+
+        if self.sorted_by.is_some() {
+            values.sort_by(|(_, lhs), (_, rhs)| compare_keys(lhs, rhs));
+        }
+
+        let order = values.into_iter().map(|(index, _)| index).collect();
+        if self.order.as_ref() != Some(&order) {
+            self.iterations.clear();
+            self.current_iteration = 0;
+            self.order = Some(order);
         }
     }
 
@@ -162,6 +220,57 @@ impl<'e> LoopNode<'e> {
     }
 }
 
+fn collection_value<'e>(
+    collection: &Collection<'e>,
+    index: usize,
+    context: &Context<'_, 'e>,
+) -> Option<ScopeValue<'e>> {
+    match *collection {
+        Collection::Static(expressions) => {
+            let expr = expressions.get(index)?;
+            let mut resolver = Deferred::new(context.lookup());
+            let val = match expr.eval(&mut resolver) {
+                ValueRef::Deferred => ScopeValue::Deferred(expr),
+                value => ScopeValue::Value(value),
+            };
+            Some(val)
+        }
+        Collection::State { len, .. } if index >= len => None,
+        Collection::State { expr, .. } => Some(ScopeValue::DeferredList(index, expr)),
+        Collection::Empty => None,
+    }
+}
+
+// The key only lives for the duration of the sort,
+// but it can't borrow from the scope of a single value.
+fn sort_key(value: ValueRef<'_>) -> ValueRef<'static> {
+    match value {
+        ValueRef::Str(s) => ValueRef::Str(Cow::Owned(s.into_owned())),
+        ValueRef::Owned(owned) => ValueRef::Owned(owned),
+        _ => ValueRef::Empty,
+    }
+}
+
+// A total order of the keys, as required by the sort.
+// Keys of different types are grouped by type: numbers, NaN, strings, bools, chars,
+// and everything else (which compares as equal).
+fn compare_keys(lhs: &ValueRef<'_>, rhs: &ValueRef<'_>) -> Ordering {
+    fn rank(value: &ValueRef<'_>) -> u8 {
+        match value {
+            ValueRef::Owned(Owned::Num(num)) if num.partial_cmp(num).is_none() => 1,
+            ValueRef::Owned(Owned::Num(_)) => 0,
+            ValueRef::Str(_) => 2,
+            ValueRef::Owned(Owned::Bool(_)) => 3,
+            ValueRef::Owned(Owned::Char(_)) => 4,
+            _ => 5,
+        }
+    }
+
+    rank(lhs)
+        .cmp(&rank(rhs))
+        .then_with(|| lhs.partial_cmp(rhs).unwrap_or(Ordering::Equal))
+}
+
 #[cfg(test)]
 mod test {
     // use anathema_render::Size;
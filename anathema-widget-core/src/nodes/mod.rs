@@ -1,7 +1,9 @@
 use std::fmt;
 use std::iter::once;
 use std::ops::ControlFlow;
+use std::time::Duration;
 
+use anathema_render::Size;
 use anathema_values::{
     Change, Context, Deferred, Immediate, NextNodeId, NodeId, ScopeStorage, Value, ValueRef,
 };
@@ -9,10 +11,11 @@ use anathema_values::{
 pub(crate) use self::controlflow::IfElse;
 pub(crate) use self::loops::LoopNode;
 use self::query::Query;
+use crate::contexts::RuntimeContext;
 use crate::error::Result;
 use crate::expressions::{Collection, Expression, ViewState};
-use crate::views::{AnyView, Views};
-use crate::{Event, WidgetContainer};
+use crate::views::{AnyView, RegisteredViews, Views};
+use crate::{Event, Region, WidgetContainer};
 
 mod controlflow;
 mod loops;
@@ -143,9 +146,16 @@ impl<'e> Node<'e> {
                 // we need to resub to the state
                 if let Collection::State { expr, len } = &mut loop_node.collection {
                     let mut immediate = Immediate::new(context.lookup(), &self.node_id);
-                    if let ValueRef::List(list) = expr.eval(&mut immediate) {
-                        list.subscribe(self.node_id.clone());
-                        *len = list.len();
+                    match expr.eval(&mut immediate) {
+                        ValueRef::List(list) => {
+                            list.subscribe(self.node_id.clone());
+                            *len = list.len();
+                        }
+                        ValueRef::Map(map) => {
+                            map.state_subscribe(self.node_id.clone());
+                            *len = map.state_len().unwrap_or(0);
+                        }
+                        _ => (),
                     }
                 }
 
@@ -153,6 +163,7 @@ impl<'e> Node<'e> {
                     Change::InsertIndex(_index) => loop_node.smush(),
                     Change::RemoveIndex(_index) => loop_node.smush(),
                     Change::Push => loop_node.smush(),
+                    Change::InsertKey(_) | Change::RemoveKey(_) => loop_node.smush(),
                     _ => (),
                 }
             }
@@ -179,6 +190,7 @@ pub struct Single<'e> {
 
 pub struct View<'e> {
     pub(crate) view: Box<dyn AnyView>,
+    pub(crate) id: usize,
     pub(crate) nodes: Nodes<'e>,
     pub(crate) state: ViewState<'e>,
     pub tabindex: Value<u32>,
@@ -194,14 +206,22 @@ impl fmt::Debug for View<'_> {
 }
 
 impl View<'_> {
-    pub fn on_event(&mut self, event: Event) {
-        self.view.on_any_event(event, &mut self.nodes);
+    pub fn on_event(&mut self, event: Event, ctx: &mut RuntimeContext<'_>) {
+        self.view.on_any_event(event, &mut self.nodes, ctx);
     }
 
     pub fn tick(&mut self) {
         self.view.tick_any();
     }
 
+    pub fn poll_state(&mut self) {
+        self.view.poll_any_state();
+    }
+
+    pub fn animate(&mut self, delta: Duration) {
+        self.view.animate_any(delta);
+    }
+
     pub fn focus(&mut self) {
         self.view.focus_any();
     }
@@ -209,6 +229,10 @@ impl View<'_> {
     pub fn blur(&mut self) {
         self.view.blur_any();
     }
+
+    pub fn resize(&mut self, size: Size) {
+        self.view.resize_any(size);
+    }
 }
 
 #[derive(Debug)]
@@ -243,6 +267,41 @@ impl<'expr> Nodes<'expr> {
         }
     }
 
+    /// Call the closure with the widget with the given node id,
+    /// if the node is a widget.
+    pub fn with_widget<F, T>(&mut self, node_id: &NodeId, f: F) -> Option<T>
+    where
+        F: FnOnce(&mut WidgetContainer<'expr>) -> T,
+    {
+        match self.query().get(node_id) {
+            Some(Node {
+                kind: NodeKind::Single(Single { widget, .. }),
+                ..
+            }) => Some(f(widget)),
+            _ => None,
+        }
+    }
+
+    /// The node ids of the widgets that accept focus, in the order they appear in the tree.
+    pub fn focusable(&mut self) -> Vec<NodeId> {
+        let mut node_ids = vec![];
+        focusable(self, &mut node_ids);
+        node_ids
+    }
+
+    /// The region covered by the widget with the given node id.
+    /// Returns `None` if the node is not a widget (a loop, a view or control flow),
+    /// or if the widget has no size.
+    pub fn region(&mut self, node_id: &NodeId) -> Option<Region> {
+        match self.query().get(node_id) {
+            Some(Node {
+                kind: NodeKind::Single(Single { widget, .. }),
+                ..
+            }) if widget.size.width > 0 && widget.size.height > 0 => Some(widget.region()),
+            _ => None,
+        }
+    }
+
     fn new_node(&mut self, context: &Context<'_, 'expr>) -> Option<Result<()>> {
         let expr = self.expressions.get(self.expr_index)?;
         self.expr_index += 1;
@@ -314,6 +373,36 @@ impl<'expr> Nodes<'expr> {
         count_widgets(self.inner.iter())
     }
 
+    /// Consume the nodes and return the views to the view registry,
+    /// so the same view instances (and their state) are used
+    /// when new nodes are created, e.g when reloading the templates.
+    ///
+    /// Views created from a prototype are dropped, as the prototype
+    /// creates a new view every time.
+    pub fn recycle_views(self) {
+        for node in self.inner {
+            match node.kind {
+                NodeKind::Single(single) => single.children.recycle_views(),
+                NodeKind::Loop(loop_node) => loop_node
+                    .iterations
+                    .into_iter()
+                    .for_each(|iteration| iteration.body.recycle_views()),
+                NodeKind::ControlFlow(if_else) => {
+                    if_else.if_node.body.recycle_views();
+                    if_else
+                        .elses
+                        .into_iter()
+                        .for_each(|e| e.body.recycle_views());
+                }
+                NodeKind::View(view) => {
+                    Views::remove(&node.node_id);
+                    RegisteredViews::restore(view.id, view.view);
+                    view.nodes.recycle_views();
+                }
+            }
+        }
+    }
+
     /// Reset the widget cache.
     /// This should be done per frame
     #[doc(hidden)]
@@ -357,6 +446,31 @@ impl<'expr> Nodes<'expr> {
     }
 }
 
+fn focusable(nodes: &mut Nodes<'_>, node_ids: &mut Vec<NodeId>) {
+    for node in &mut nodes.inner {
+        match &mut node.kind {
+            NodeKind::Single(Single {
+                widget, children, ..
+            }) => {
+                if widget.accepts_focus() {
+                    node_ids.push(node.node_id.clone());
+                }
+                focusable(children, node_ids);
+            }
+            NodeKind::Loop(loop_node) => loop_node
+                .iterations
+                .iter_mut()
+                .for_each(|iteration| focusable(&mut iteration.body, node_ids)),
+            NodeKind::ControlFlow(if_else) => {
+                if let Some(body) = if_else.body_mut() {
+                    focusable(body, node_ids);
+                }
+            }
+            NodeKind::View(View { nodes, .. }) => focusable(nodes, node_ids),
+        }
+    }
+}
+
 fn count_widgets<'a>(nodes: impl Iterator<Item = &'a Node<'a>>) -> usize {
     nodes
         .map(|node| match &node.kind {
@@ -425,12 +539,36 @@ fn update(nodes: &mut [Node<'_>], node_id: &[usize], change: &Change, context: &
 #[cfg(test)]
 mod test {
     use anathema_render::Size;
-    use anathema_values::testing::{ident, list};
+    use anathema_values::testing::{greater_than, ident, list, neg, unum};
     use anathema_values::ValueExpr;
 
+    use crate::expressions::Expression;
     use crate::testing::expressions::{expression, for_expression, if_expression};
     use crate::testing::nodes::*;
 
+    fn sorted_loop(
+        collection: Box<ValueExpr>,
+        sorted_by: Option<Box<ValueExpr>>,
+        filter: Option<Box<ValueExpr>>,
+    ) -> Vec<Expression> {
+        let body = expression("test", Some(*ident("item")), [], []);
+        let Expression::Loop(mut loop_expr) = for_expression("item", collection, [body]) else {
+            unreachable!()
+        };
+        loop_expr.sorted_by = sorted_by.map(|expr| *expr);
+        loop_expr.filter = filter.map(|expr| *expr);
+        vec![Expression::Loop(loop_expr)]
+    }
+
+    fn loop_values(runtime: &mut TestRuntime<'_>) -> Vec<String> {
+        runtime.layout().unwrap();
+        runtime
+            .nodes
+            .iter_mut()
+            .map(|(widget, _)| widget.to_ref::<TestWidget>().0.value_ref().unwrap().clone())
+            .collect()
+    }
+
     #[test]
     fn generate_a_single_widget() {
         let test = expression("test", None, [], []).test();
@@ -461,6 +599,50 @@ mod test {
         assert_eq!(runtime.nodes.count(), 3);
     }
 
+    #[test]
+    fn for_loop_over_map() {
+        // The values of the map, in insertion order
+        let exprs = sorted_loop(ident("ordered_map"), None, None);
+        let mut runtime = test_runtime(&exprs);
+        assert_eq!(loop_values(&mut runtime), vec!["3", "1", "2"]);
+    }
+
+    #[test]
+    fn for_loop_sorted_by() {
+        let exprs = sorted_loop(list(["c", "a", "b"]), Some(ident("item")), None);
+        let mut runtime = test_runtime(&exprs);
+        assert_eq!(loop_values(&mut runtime), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn for_loop_sort_is_stable() {
+        let exprs = sorted_loop(list([2, 1, 3]), Some(unum(0)), None);
+        let mut runtime = test_runtime(&exprs);
+        assert_eq!(loop_values(&mut runtime), vec!["2", "1", "3"]);
+    }
+
+    #[test]
+    fn for_loop_where() {
+        let exprs = sorted_loop(
+            ident("generic_list"),
+            None,
+            Some(greater_than(ident("item"), unum(1))),
+        );
+        let mut runtime = test_runtime(&exprs);
+        assert_eq!(loop_values(&mut runtime), vec!["2", "3"]);
+    }
+
+    #[test]
+    fn for_loop_sorted_by_where() {
+        let exprs = sorted_loop(
+            ident("generic_list"),
+            Some(neg(ident("item"))),
+            Some(greater_than(ident("item"), unum(1))),
+        );
+        let mut runtime = test_runtime(&exprs);
+        assert_eq!(loop_values(&mut runtime), vec!["3", "2"]);
+    }
+
     fn test_if_else(is_true: bool, else_cond: Option<bool>, expected: &str) {
         let is_true = is_true.into();
         let is_else = else_cond.map(|val| val.into());
@@ -1,4 +1,4 @@
-use anathema_render::{Attributes, Color, Style as RenderStyle};
+use anathema_render::{Attributes, Color, Link, Style as RenderStyle};
 use anathema_values::{Context, NodeId, Value};
 
 #[derive(Debug)]
@@ -12,6 +12,10 @@ pub struct WidgetStyle {
     pub(crate) crossed_out: Value<bool>,
     pub(crate) overlined: Value<bool>,
     pub(crate) inverse: Value<bool>,
+    pub(crate) hyperlink: Value<String>,
+    // The link is only created when the hyperlink resolves,
+    // rather than every time the style is painted
+    pub(crate) link: Option<Link>,
 }
 
 impl WidgetStyle {
@@ -50,6 +54,7 @@ impl WidgetStyle {
             fg: self.fg.value_ref().cloned(),
             bg: self.bg.value_ref().cloned(),
             attributes,
+            link: self.link,
         }
     }
 
@@ -63,5 +68,7 @@ impl WidgetStyle {
         self.crossed_out.resolve(context, node_id);
         self.overlined.resolve(context, node_id);
         self.inverse.resolve(context, node_id);
+        self.hyperlink.resolve(context, node_id);
+        self.link = self.hyperlink.value_ref().map(|url| Link::new(url));
     }
 }
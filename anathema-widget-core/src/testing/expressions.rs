@@ -29,6 +29,8 @@ pub fn for_expression(
         body: body.into(),
         binding: binding.into(),
         collection: *collection,
+        sorted_by: None,
+        filter: None,
     })
 }
 
@@ -7,6 +7,7 @@ use crate::error::Result;
 use crate::expressions::Expression;
 use crate::layout::{Constraints, Layout};
 use crate::nodes::{make_it_so, Node};
+use crate::testing::expression;
 use crate::{AnyWidget, Factory, FactoryContext, LayoutNodes, Nodes, Widget, WidgetFactory};
 
 // -----------------------------------------------------------------------------
@@ -146,6 +147,35 @@ pub fn test_runtime(exprs: &[Expression]) -> TestRuntime<'_> {
     }
 }
 
+/// Lay out a single `test` widget, displaying `text`, using `layout`.
+pub fn test_layout(layout: &mut impl Layout, text: &str, constraints: Constraints) -> Result<Size> {
+    test_layout_many(layout, &[text], constraints).map(|(size, _)| size)
+}
+
+/// Lay out a `test` widget for each of the `texts`, using `layout`.
+/// Returns the size of the layout and the size of each widget.
+pub fn test_layout_many(
+    layout: &mut impl Layout,
+    texts: &[&str],
+    constraints: Constraints,
+) -> Result<(Size, Vec<Size>)> {
+    let exprs = texts
+        .iter()
+        .map(|text| expression("test", Some((*text).into()), [], []))
+        .collect::<Vec<_>>();
+    let mut runtime = test_runtime(&exprs);
+    let context = Context::root(&runtime.state);
+    let mut nodes = LayoutNodes::new(&mut runtime.nodes, constraints, &context);
+    let size = layout.layout(&mut nodes)?;
+
+    let sizes = runtime
+        .nodes
+        .iter_mut()
+        .map(|(widget, _)| widget.size)
+        .collect();
+    Ok((size, sizes))
+}
+
 // pub struct TestNodes<'e> {
 //     pub nodes: Nodes<'e>,
 //     state: TestState,
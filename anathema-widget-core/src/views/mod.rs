@@ -1,12 +1,15 @@
 use std::cell::RefCell;
 use std::fmt::Debug;
 use std::sync::OnceLock;
+use std::time::Duration;
 
+use anathema_render::Size;
 use anathema_values::hashmap::HashMap;
 use anathema_values::{NodeId, State};
 use kempt::Map;
 use parking_lot::Mutex;
 
+use crate::contexts::RuntimeContext;
 use crate::error::{Error, Result};
 use crate::{Event, Nodes};
 
@@ -20,7 +23,7 @@ enum ViewFactory {
 static REGISTERED_VIEWS: OnceLock<Mutex<HashMap<usize, ViewFactory>>> = OnceLock::new();
 
 thread_local! {
-    static VIEWS: RefCell<Map<NodeId, Option<u32>>> = RefCell::new(Map::new());
+    static VIEWS: RefCell<Map<NodeId, Option<u32>>> = const { RefCell::new(Map::new()) };
 }
 
 pub struct RegisteredViews;
@@ -45,6 +48,15 @@ impl RegisteredViews {
             .insert(key, view);
     }
 
+    // Return a view that was previously consumed, so it can be used again.
+    // Prototypes are not restored, as a new instance is created every time.
+    pub(crate) fn restore(id: usize, view: Box<dyn AnyView>) {
+        let mut views = REGISTERED_VIEWS.get_or_init(Default::default).lock();
+        if let Some(ViewFactory::View(slot @ None)) = views.get_mut(&id) {
+            *slot = Some(view);
+        }
+    }
+
     pub fn get(id: usize) -> Result<Box<dyn AnyView>> {
         let mut views = REGISTERED_VIEWS.get_or_init(Default::default).lock();
         let view = views.get_mut(&id);
@@ -91,6 +103,10 @@ impl Views {
         VIEWS.with_borrow_mut(|views| views.insert(node_id, tabindex));
     }
 
+    pub(crate) fn remove(node_id: &NodeId) {
+        VIEWS.with_borrow_mut(|views| views.remove(node_id));
+    }
+
     pub(crate) fn update(node_id: &NodeId, tabindex: Option<u32>) {
         VIEWS.with_borrow_mut(|views| {
             if let Some(old_index) = views.get_mut(node_id) {
@@ -117,6 +133,19 @@ pub trait View {
     /// `nodes` represents all the nodes inside the view.
     fn on_event(&mut self, _event: Event, _nodes: &mut Nodes<'_>) {}
 
+    /// Called once a view receives an event, the same as [`View::on_event`],
+    /// with access to the runtime, e.g the clipboard (see [`RuntimeContext::clipboard`]).
+    ///
+    /// By default this calls [`View::on_event`].
+    fn on_event_with_context(
+        &mut self,
+        event: Event,
+        nodes: &mut Nodes<'_>,
+        _ctx: &mut RuntimeContext<'_>,
+    ) {
+        self.on_event(event, nodes);
+    }
+
     /// Internal state will always take precedence over external state.
     /// It is not possible to shadow internal state.
     /// This is required to pass internal state to the templates.
@@ -125,9 +154,24 @@ pub trait View {
         &()
     }
 
+    /// Mutable access to the same state as [`View::state`].
+    ///
+    /// The runtime uses this to poll any [`ChannelCollection`](anathema_values::ChannelCollection)
+    /// in the state at the start of every frame.
+    fn state_mut(&mut self) -> Option<&mut dyn State> {
+        None
+    }
+
     /// This function is called every frame
     fn tick(&mut self) {}
 
+    /// This function is called every frame, before [`View::tick`], with the time
+    /// since the previous frame.
+    ///
+    /// Use this to advance animations, e.g a [`Tween`](anathema_values::Tween),
+    /// and copy the current value into the state.
+    fn animate(&mut self, _delta: Duration) {}
+
     /// This function is called once the view receives focus.
     /// This requires that the view is either the root view, which means it
     /// will receive this call exactly once,
@@ -136,38 +180,60 @@ pub trait View {
 
     /// This is called when the tab index changes and this view loses focus.
     fn blur(&mut self) {}
+
+    /// This function is called on every view when the terminal is resized,
+    /// before the next layout.
+    /// Consecutive resize events within the same frame are coalesced, so this is only
+    /// called with the final size.
+    fn resize(&mut self, _size: Size) {}
 }
 
 impl View for () {}
 
 pub trait AnyView: Send {
-    fn on_any_event(&mut self, ev: Event, nodes: &mut Nodes<'_>);
+    fn on_any_event(&mut self, ev: Event, nodes: &mut Nodes<'_>, ctx: &mut RuntimeContext<'_>);
 
     fn get_any_state(&self) -> &dyn State;
 
+    fn poll_any_state(&mut self);
+
     fn tick_any(&mut self);
 
+    fn animate_any(&mut self, delta: Duration);
+
     fn focus_any(&mut self);
 
     fn blur_any(&mut self);
+
+    fn resize_any(&mut self, size: Size);
 }
 
 impl<T> AnyView for T
 where
     T: View + Send,
 {
-    fn on_any_event(&mut self, event: Event, nodes: &mut Nodes<'_>) {
-        self.on_event(event, nodes);
+    fn on_any_event(&mut self, event: Event, nodes: &mut Nodes<'_>, ctx: &mut RuntimeContext<'_>) {
+        self.on_event_with_context(event, nodes, ctx);
     }
 
     fn get_any_state(&self) -> &dyn State {
         self.state()
     }
 
+    fn poll_any_state(&mut self) {
+        if let Some(state) = self.state_mut() {
+            state.state_poll();
+        }
+    }
+
     fn tick_any(&mut self) {
         self.tick();
     }
 
+    fn animate_any(&mut self, delta: Duration) {
+        self.animate(delta);
+    }
+
     fn blur_any(&mut self) {
         self.blur();
     }
@@ -175,4 +241,8 @@ where
     fn focus_any(&mut self) {
         self.focus();
     }
+
+    fn resize_any(&mut self, size: Size) {
+        self.resize(size);
+    }
 }
@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 use std::ops::{Deref, DerefMut};
 
-use anathema_render::{Color, ScreenPos, Size, Style};
+use anathema_render::{Color, Gradient, ScreenPos, Size, Style};
 use anathema_values::{Attributes, Context, NodeId, Value};
 
 use super::{AnyWidget, Widget};
@@ -10,7 +10,7 @@ use crate::error::Result;
 use crate::expressions::Expression;
 use crate::layout::Constraints;
 use crate::nodes::Nodes;
-use crate::{Display, LayoutNodes, LocalPos, Pos, Region};
+use crate::{Display, Event, LayoutNodes, LocalPos, Overflow, Pos, Region};
 
 /// The `WidgetContainer` has to go through three steps before it can be displayed:
 /// * [`layout`](Self::layout)
@@ -19,7 +19,9 @@ use crate::{Display, LayoutNodes, LocalPos, Pos, Region};
 #[derive(Debug)]
 pub struct WidgetContainer<'e> {
     pub(crate) background: Value<Color>,
+    pub(crate) gradient: Value<Gradient>,
     pub(crate) display: Value<Display>,
+    pub(crate) overflow: Value<Overflow>,
     pub(crate) inner: Box<dyn AnyWidget>,
     pub pos: Pos,
     pub size: Size,
@@ -89,16 +91,42 @@ impl WidgetContainer<'_> {
         Some(res)
     }
 
+    /// The region covered by the widget, in global coordinates.
+    /// Like all regions this is inclusive.
     pub fn region(&self) -> Region {
         Region::new(
             self.pos,
             Pos::new(
-                self.pos.x + self.size.width as i32,
-                self.pos.y + self.size.height as i32,
+                self.pos.x + self.size.width as i32 - 1,
+                self.pos.y + self.size.height as i32 - 1,
             ),
         )
     }
 
+    /// The overflow policy of the widget.
+    /// If the `overflow` attribute is not set, the widget decides.
+    pub fn overflow(&self) -> Overflow {
+        self.overflow.value_or_else(|| self.inner.overflow())
+    }
+
+    /// A widget accepts focus if the widget itself accepts focus and it's displayed.
+    pub fn accepts_focus(&self) -> bool {
+        matches!(self.display.value_or_default(), Display::Show) && self.inner.accepts_focus()
+    }
+
+    pub fn focus(&mut self) {
+        self.inner.focus();
+    }
+
+    pub fn blur(&mut self) {
+        self.inner.blur();
+    }
+
+    /// Pass an event to the widget. Returns `true` if the widget handled the event.
+    pub fn on_event(&mut self, event: Event) -> bool {
+        self.inner.on_event(event)
+    }
+
     pub fn layout<'e>(
         &mut self,
         children: &mut Nodes<'e>,
@@ -122,6 +150,27 @@ impl WidgetContainer<'_> {
         Ok(self.size)
     }
 
+    /// Compute the size of the widget for the given constraints.
+    /// The size of the widget is kept, but the children are laid out.
+    /// See [`LayoutNode::measure`](crate::LayoutNode::measure).
+    pub fn measure<'e>(
+        &mut self,
+        children: &mut Nodes<'e>,
+        constraints: Constraints,
+        data: &Context<'_, 'e>,
+    ) -> Result<Size> {
+        let size = self.size;
+
+        // The children are evaluated from the first child on every layout,
+        // so a measured widget is laid out the same way as one that wasn't.
+        children.reset_cache();
+        let measured = self.layout(children, constraints, data);
+        children.reset_cache();
+
+        self.size = size;
+        measured
+    }
+
     pub fn position(&mut self, children: &mut Nodes<'_>, pos: Pos) {
         self.pos = pos;
 
@@ -143,10 +192,32 @@ impl WidgetContainer<'_> {
 
         let pos = Pos::new(self.pos.x, self.pos.y);
         ctx.update(self.size, pos);
-        self.inner.paint(children, ctx);
+
+        match self.overflow().clips() {
+            false => self.inner.paint(children, ctx),
+            true => {
+                // The region is already constrained by any existing clipping region
+                let region = ctx.create_region();
+                let mut ctx = ctx.to_unsized();
+                ctx.set_region(&region);
+                self.inner.paint(children, ctx.into_sized(self.size, pos));
+            }
+        }
     }
 
+    // A gradient takes precedence over a solid background colour
     fn paint_background(&self, ctx: &mut PaintCtx<'_, WithSize>) -> Option<()> {
+        if let Some(gradient) = self.gradient.value_ref() {
+            let mut style = Style::new();
+            for y in 0..self.size.height {
+                for x in 0..self.size.width {
+                    style.set_bg(gradient.sample_cell(x, y, self.size));
+                    ctx.put(' ', style, LocalPos::new(x, y));
+                }
+            }
+            return Some(());
+        }
+
         let color = self.background.value_ref()?;
         let width = self.size.width;
 
@@ -164,7 +235,9 @@ impl WidgetContainer<'_> {
 
     pub fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
         self.background.resolve(context, node_id);
+        self.gradient.resolve(context, node_id);
         self.display.resolve(context, node_id);
+        self.overflow.resolve(context, node_id);
         self.inner.update(context, node_id);
     }
 }
@@ -9,7 +9,7 @@ pub use self::container::WidgetContainer;
 use super::contexts::{PaintCtx, PositionCtx, WithSize};
 use crate::error::Result;
 use crate::nodes::Nodes;
-use crate::LayoutNodes;
+use crate::{Event, LayoutNodes, Overflow};
 
 mod container;
 
@@ -47,6 +47,33 @@ pub trait Widget {
 
     /// Called when a value the widget subscribes to has changed.
     fn update(&mut self, _context: &Context<'_, '_>, _node_id: &NodeId) {}
+
+    /// The overflow policy used when the `overflow` attribute is not set.
+    fn overflow(&self) -> Overflow {
+        Overflow::Visible
+    }
+
+    // -----------------------------------------------------------------------------
+    //     - Focus -
+    // -----------------------------------------------------------------------------
+    /// Widgets that accept focus can be focused with tab / shift+tab,
+    /// and receive the key events while focused.
+    fn accepts_focus(&self) -> bool {
+        false
+    }
+
+    /// Called when the widget receives focus.
+    fn focus(&mut self) {}
+
+    /// Called when the widget loses focus.
+    fn blur(&mut self) {}
+
+    /// Called with key events while the widget has focus.
+    ///
+    /// Return `true` if the event was handled, otherwise the event is passed on to the view.
+    fn on_event(&mut self, _event: Event) -> bool {
+        false
+    }
 }
 
 impl Widget for Box<dyn Widget> {
@@ -69,6 +96,26 @@ impl Widget for Box<dyn Widget> {
     fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
         self.as_mut().update(context, node_id)
     }
+
+    fn overflow(&self) -> Overflow {
+        self.as_ref().overflow()
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.as_ref().accepts_focus()
+    }
+
+    fn focus(&mut self) {
+        self.as_mut().focus()
+    }
+
+    fn blur(&mut self) {
+        self.as_mut().blur()
+    }
+
+    fn on_event(&mut self, event: Event) -> bool {
+        self.as_mut().on_event(event)
+    }
 }
 
 pub trait AnyWidget: Debug {
@@ -89,6 +136,16 @@ pub trait AnyWidget: Debug {
     );
 
     fn update_any(&mut self, context: &Context<'_, '_>, node_id: &NodeId);
+
+    fn overflow_any(&self) -> Overflow;
+
+    fn accepts_focus_any(&self) -> bool;
+
+    fn focus_any(&mut self);
+
+    fn blur_any(&mut self);
+
+    fn on_any_event(&mut self, event: Event) -> bool;
 }
 
 impl Widget for Box<dyn AnyWidget> {
@@ -111,6 +168,26 @@ impl Widget for Box<dyn AnyWidget> {
     fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
         self.deref_mut().update_any(context, node_id)
     }
+
+    fn overflow(&self) -> Overflow {
+        self.deref().overflow_any()
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.deref().accepts_focus_any()
+    }
+
+    fn focus(&mut self) {
+        self.deref_mut().focus_any()
+    }
+
+    fn blur(&mut self) {
+        self.deref_mut().blur_any()
+    }
+
+    fn on_event(&mut self, event: Event) -> bool {
+        self.deref_mut().on_any_event(event)
+    }
 }
 
 impl<T: Debug + Widget + 'static> AnyWidget for T {
@@ -145,4 +222,24 @@ impl<T: Debug + Widget + 'static> AnyWidget for T {
     fn update_any(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
         self.update(context, node_id)
     }
+
+    fn overflow_any(&self) -> Overflow {
+        self.overflow()
+    }
+
+    fn accepts_focus_any(&self) -> bool {
+        self.accepts_focus()
+    }
+
+    fn focus_any(&mut self) {
+        self.focus()
+    }
+
+    fn blur_any(&mut self) {
+        self.blur()
+    }
+
+    fn on_any_event(&mut self, event: Event) -> bool {
+        self.on_event(event)
+    }
 }
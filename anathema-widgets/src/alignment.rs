@@ -2,17 +2,19 @@ use anathema_render::Size;
 use anathema_values::{Context, NodeId, Value};
 use anathema_widget_core::contexts::PositionCtx;
 use anathema_widget_core::error::Result;
-use anathema_widget_core::layout::{Align, Layout};
+use anathema_widget_core::layout::Align;
 use anathema_widget_core::nodes::Nodes;
 use anathema_widget_core::{AnyWidget, FactoryContext, LayoutNodes, Pos, Widget, WidgetFactory};
 
-use crate::layout::single::Single;
-
 /// Then `Alignment` widget "inflates" the parent to its maximum constraints
 /// See [`Align`](crate::layout::Align) for more information.
 ///
 /// If the alignment has no children it will have a size of zero.
 ///
+/// The child is laid out with loose constraints (no minimum width / height).
+/// A child that is larger than the alignment is aligned to the start
+/// rather than being drawn outside of the alignment.
+///
 /// ```text
 /// alignment [align: "centre"]
 ///     border [background: "red"]
@@ -35,7 +37,16 @@ impl Widget for Alignment {
     }
 
     fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
-        let size = Single.layout(nodes)?;
+        let mut constraints = nodes.constraints;
+        constraints.min_width = 0;
+        constraints.min_height = 0;
+
+        let mut size = Size::ZERO;
+        nodes.next(|mut node| {
+            size = node.layout(constraints)?;
+            Ok(())
+        })?;
+
         if size == Size::ZERO {
             Ok(Size::ZERO)
         } else {
@@ -53,30 +64,36 @@ impl Widget for Alignment {
 
     fn position(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx) {
         if let Some((child, children)) = children.first_mut() {
-            let width = ctx.inner_size.width as i32;
-            let height = ctx.inner_size.height as i32;
-            let child_width = child.size.width as i32;
-            let child_height = child.size.height as i32;
-
-            let child_offset = match self.alignment.value_or_default() {
-                Align::TopLeft => Pos::ZERO,
-                Align::Top => Pos::new(width / 2 - child_width / 2, 0),
-                Align::TopRight => Pos::new(width - child_width, 0),
-                Align::Right => Pos::new(width - child_width, height / 2 - child_height / 2),
-                Align::BottomRight => Pos::new(width - child_width, height - child_height),
-                Align::Bottom => Pos::new(width / 2 - child_width / 2, height - child_height),
-                Align::BottomLeft => Pos::new(0, height - child_height),
-                Align::Left => Pos::new(0, height / 2 - child_height / 2),
-                Align::Centre => {
-                    Pos::new(width / 2 - child_width / 2, height / 2 - child_height / 2)
-                }
-            };
-
+            let align = self.alignment.value_or_default();
+            let child_offset = child_offset(align, ctx.inner_size, child.size);
             child.position(children, ctx.pos + child_offset);
         }
     }
 }
 
+// The offset of the child within the alignment.
+// If the child doesn't fit on an axis it's aligned to the start of that axis.
+fn child_offset(align: Align, size: Size, child_size: Size) -> Pos {
+    let width = size.width as i32;
+    let height = size.height as i32;
+    let child_width = child_size.width as i32;
+    let child_height = child_size.height as i32;
+
+    let offset = match align {
+        Align::TopLeft => Pos::ZERO,
+        Align::Top => Pos::new(width / 2 - child_width / 2, 0),
+        Align::TopRight => Pos::new(width - child_width, 0),
+        Align::Right => Pos::new(width - child_width, height / 2 - child_height / 2),
+        Align::BottomRight => Pos::new(width - child_width, height - child_height),
+        Align::Bottom => Pos::new(width / 2 - child_width / 2, height - child_height),
+        Align::BottomLeft => Pos::new(0, height - child_height),
+        Align::Left => Pos::new(0, height / 2 - child_height / 2),
+        Align::Centre => Pos::new(width / 2 - child_width / 2, height / 2 - child_height / 2),
+    };
+
+    Pos::new(offset.x.max(0), offset.y.max(0))
+}
+
 pub(crate) struct AlignmentFactory;
 
 impl WidgetFactory for AlignmentFactory {
@@ -107,6 +124,26 @@ mod test {
         test_widget(alignment, expected);
     }
 
+    #[test]
+    fn oversized_child_aligns_to_start() {
+        let size = Size::new(10, 4);
+        let child = Size::new(12, 2);
+        assert_eq!(child_offset(Align::Right, size, child), Pos::new(0, 1));
+        assert_eq!(child_offset(Align::Centre, size, child), Pos::new(0, 1));
+
+        let child = Size::new(4, 6);
+        assert_eq!(
+            child_offset(Align::BottomRight, size, child),
+            Pos::new(6, 0)
+        );
+        assert_eq!(child_offset(Align::Bottom, size, child), Pos::new(3, 0));
+
+        let child = Size::new(20, 20);
+        for align in [Align::TopRight, Align::BottomLeft, Align::Centre] {
+            assert_eq!(child_offset(align, size, child), Pos::ZERO);
+        }
+    }
+
     #[test]
     fn align_top_left() {
         align_widget(
@@ -8,11 +8,13 @@ use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
 use anathema_widget_core::error::Result;
 use anathema_widget_core::layout::Layout;
 use anathema_widget_core::{
-    AnyWidget, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget, WidgetFactory, WidgetStyle,
+    AnyWidget, Dimension, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget, WidgetFactory,
+    WidgetStyle,
 };
 use unicode_width::UnicodeWidthChar;
 
 use crate::layout::border::BorderLayout;
+use crate::layout::padding::Padding;
 
 // -----------------------------------------------------------------------------
 //     - Indices -
@@ -66,7 +68,7 @@ impl DynValue for Sides {
         let value = expr.eval(&mut resolver);
 
         let inner = match value {
-            ValueRef::Str(s) => s.into(),
+            ValueRef::Str(s) => (&*s).into(),
             ValueRef::Expressions(Expressions(values)) => {
                 let mut sides = Sides::EMPTY;
 
@@ -75,7 +77,7 @@ impl DynValue for Sides {
                     .map(|expr| expr.eval(&mut Immediate::new(context.lookup(), node_id)))
                     .for_each(|val| {
                         if let ValueRef::Str(s) = val {
-                            sides |= s.into();
+                            sides |= (&*s).into();
                         }
                     });
 
@@ -99,7 +101,7 @@ impl DynValue for Sides {
             let value = expr.eval(&mut resolver);
 
             *inner = match value {
-                ValueRef::Str(s) => s.into(),
+                ValueRef::Str(s) => (&*s).into(),
                 ValueRef::Expressions(Expressions(values)) => {
                     let mut sides = Sides::EMPTY;
 
@@ -108,7 +110,7 @@ impl DynValue for Sides {
                         .map(|expr| expr.eval(&mut Immediate::new(context.lookup(), node_id)))
                         .for_each(|val| {
                             if let ValueRef::Str(s) = val {
-                                sides |= s.into();
+                                sides |= (&*s).into();
                             }
                         });
 
@@ -208,10 +210,10 @@ impl TryFrom<ValueRef<'_>> for BorderStyle {
     type Error = ();
 
     fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
-        Ok(match value {
-            ValueRef::Str("thin") => Self::Thin,
-            ValueRef::Str("thick") => Self::Thick,
-            ValueRef::Str(raw) => Self::Custom(raw.to_string()),
+        Ok(match value.as_str() {
+            Some("thin") => Self::Thin,
+            Some("thick") => Self::Thick,
+            Some(raw) => Self::Custom(raw.to_string()),
             _ => Self::default(),
         })
     }
@@ -265,14 +267,16 @@ pub struct Border {
     /// `edges[2]` etc.
     pub edges: [char; 8],
     /// The width of the border. This will make the constraints tight for the width.
-    pub width: Value<usize>,
+    /// See [`Dimension`] for percentages of the available width.
+    pub width: Value<Dimension>,
     /// The height of the border. This will make the constraints tight for the height.
-    pub height: Value<usize>,
-    /// The minimum width of the border. This will force the minimum constrained width to expand to
-    /// this value.
+    /// See [`Dimension`] for percentages of the available height.
+    pub height: Value<Dimension>,
+    /// The minimum width of the child. This will force the minimum constrained width to expand to
+    /// this value plus the border.
     pub min_width: Value<usize>,
-    /// The minimum height of the border. This will force the minimum constrained height to expand to
-    /// this value.
+    /// The minimum height of the child. This will force the minimum constrained height to expand to
+    /// this value plus the border.
     pub min_height: Value<usize>,
     /// The style of the border.
     pub style: WidgetStyle,
@@ -282,67 +286,47 @@ impl Border {
     /// The name of the element
     pub const KIND: &'static str = "Border";
 
-    fn border_size(&self) -> Size {
-        // Get the size of the border (thickness).
+    fn edge_sizes(&self) -> Padding {
+        // Get the thickness of each edge of the border.
         // This is NOT including the child.
-        let mut border_width = 0;
         let sides = self.sides.value_or(Sides::ALL);
-
-        if sides.contains(Sides::LEFT) {
-            let mut width = self.edges[BORDER_EDGE_LEFT].width().unwrap_or(0);
-
-            if sides.contains(Sides::TOP | Sides::BOTTOM) {
-                let corner = self.edges[BORDER_EDGE_TOP_LEFT].width().unwrap_or(0);
-                width = width.max(corner);
-
-                let corner = self.edges[BORDER_EDGE_BOTTOM_LEFT].width().unwrap_or(0);
-                width = width.max(corner);
-            }
-            border_width += width;
-        }
-
-        if sides.contains(Sides::RIGHT) {
-            let mut width = self.edges[BORDER_EDGE_RIGHT].width().unwrap_or(0);
-
-            if sides.contains(Sides::TOP | Sides::BOTTOM) {
-                let corner = self.edges[BORDER_EDGE_TOP_RIGHT].width().unwrap_or(0);
-                width = width.max(corner);
-
-                let corner = self.edges[BORDER_EDGE_BOTTOM_RIGHT].width().unwrap_or(0);
-                width = width.max(corner);
-            }
-            border_width += width;
+        let width = |edge: usize| self.edges[edge].width().unwrap_or(0);
+
+        // Corners are only drawn when there are connecting sides
+        let mut left = width(BORDER_EDGE_LEFT);
+        let mut right = width(BORDER_EDGE_RIGHT);
+        if sides.contains(Sides::TOP | Sides::BOTTOM) {
+            left = left
+                .max(width(BORDER_EDGE_TOP_LEFT))
+                .max(width(BORDER_EDGE_BOTTOM_LEFT));
+            right = right
+                .max(width(BORDER_EDGE_TOP_RIGHT))
+                .max(width(BORDER_EDGE_BOTTOM_RIGHT));
         }
 
-        // Set the height of the border it self (thickness)
-        let mut border_height = 0;
-        if sides.contains(Sides::TOP) {
-            let mut height = 1;
-
-            if sides.contains(Sides::LEFT | Sides::RIGHT) {
-                let corner = self.edges[BORDER_EDGE_TOP_LEFT].width().unwrap_or(0);
-                height = height.max(corner);
-
-                let corner = self.edges[BORDER_EDGE_TOP_RIGHT].width().unwrap_or(0);
-                height = height.max(corner);
-            }
-            border_height += height;
+        let mut top = 1;
+        let mut bottom = 1;
+        if sides.contains(Sides::LEFT | Sides::RIGHT) {
+            top = top
+                .max(width(BORDER_EDGE_TOP_LEFT))
+                .max(width(BORDER_EDGE_TOP_RIGHT));
+            bottom = bottom
+                .max(width(BORDER_EDGE_BOTTOM_LEFT))
+                .max(width(BORDER_EDGE_BOTTOM_RIGHT));
         }
 
-        if sides.contains(Sides::BOTTOM) {
-            let mut height = 1;
-
-            if sides.contains(Sides::LEFT | Sides::RIGHT) {
-                let corner = self.edges[BORDER_EDGE_BOTTOM_LEFT].width().unwrap_or(0);
-                height = height.max(corner);
+        Padding::new(top, right, bottom, left)
+    }
 
-                let corner = self.edges[BORDER_EDGE_BOTTOM_RIGHT].width().unwrap_or(0);
-                height = height.max(corner);
-            }
-            border_height += height;
+    fn border_layout(&self) -> BorderLayout {
+        BorderLayout {
+            min_height: self.min_height.value(),
+            min_width: self.min_width.value(),
+            height: self.height.value(),
+            width: self.width.value(),
+            edges: self.edge_sizes(),
+            sides: self.sides.value_or(Sides::ALL),
         }
-
-        Size::new(border_width, border_height)
     }
 }
 
@@ -362,14 +346,7 @@ impl Widget for Border {
     }
 
     fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
-        let mut layout = BorderLayout {
-            min_height: self.min_height.value(),
-            min_width: self.min_width.value(),
-            height: self.height.value(),
-            width: self.width.value(),
-            border_size: self.border_size(),
-        };
-        layout.layout(nodes)
+        self.border_layout().layout(nodes)
     }
 
     fn position(&mut self, children: &mut Nodes<'_>, mut ctx: PositionCtx) {
@@ -378,14 +355,7 @@ impl Widget for Border {
             None => return,
         };
 
-        if self.sides.value_or_default().contains(Sides::TOP) {
-            ctx.pos.y += 1;
-        }
-
-        if self.sides.value_or_default().contains(Sides::LEFT) {
-            ctx.pos.x += self.edges[BORDER_EDGE_LEFT].width().unwrap_or(0) as i32;
-        }
-
+        ctx.pos += self.border_layout().inset().offset();
         child.position(children, ctx.pos);
     }
 
@@ -561,6 +531,29 @@ mod test {
         );
     }
 
+    #[test]
+    fn percent_width() {
+        let attribs = [
+            ("width".to_string(), "50%".into()),
+            ("height".to_string(), 3.into()),
+        ];
+        test_widget(
+            expression("border", None, attribs, []),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══════╗
+            ║┌────────┐          ║
+            ║│        │          ║
+            ║└────────┘          ║
+            ║                    ║
+            ║                    ║
+            ║                    ║
+            ╚════════════════════╝
+            "#,
+            ),
+        );
+    }
+
     #[test]
     fn thick_border() {
         test_widget(
@@ -769,6 +762,44 @@ mod test {
         );
     }
 
+    #[test]
+    fn top_bottom_sized_by_child() {
+        test_widget(
+            border(
+                BorderStyle::Thin,
+                Sides::TOP | Sides::BOTTOM,
+                None,
+                None,
+                Some("hello world"),
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [════╗
+            ║───────────       ║
+            ║hello world       ║
+            ║───────────       ║
+            ║                  ║
+            ╚══════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn left_gutter_sized_by_child() {
+        test_widget(
+            border(BorderStyle::Thin, Sides::LEFT, None, None, Some("hello")),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [════╗
+            ║│hello            ║
+            ║                  ║
+            ╚══════════════════╝
+            "#,
+            ),
+        );
+    }
+
     #[test]
     fn fixed_size() {
         test_widget(
@@ -1,92 +1,213 @@
 use anathema_render::Size;
 use anathema_widget_core::error::{Error, Result};
 use anathema_widget_core::layout::{Constraints, Layout};
-use anathema_widget_core::LayoutNodes;
+use anathema_widget_core::{Dimension, LayoutNodes};
+
+use crate::layout::padding::Padding;
+use crate::Sides;
 
 pub struct BorderLayout {
+    /// The min width of the child. The border is added on top of this.
     pub min_width: Option<usize>,
+    /// The min height of the child. The border is added on top of this.
     pub min_height: Option<usize>,
-    pub width: Option<usize>,
-    pub height: Option<usize>,
-    pub border_size: Size,
+    /// The width including the border, resolved against the max width of the constraints
+    pub width: Option<Dimension>,
+    /// The height including the border, resolved against the max height of the constraints
+    pub height: Option<Dimension>,
+    /// The thickness of each edge of the border:
+    /// the width of the left and right edges, and the height of the top and bottom edges.
+    pub edges: Padding,
+    /// Only the enabled sides are subtracted from the space available to the child.
+    pub sides: Sides,
+}
+
+impl BorderLayout {
+    /// The thickness of the enabled sides of the border
+    pub fn inset(&self) -> Padding {
+        let side = |side: Sides, size: usize| match self.sides.contains(side) {
+            true => size,
+            false => 0,
+        };
+
+        Padding::new(
+            side(Sides::TOP, self.edges.top),
+            side(Sides::RIGHT, self.edges.right),
+            side(Sides::BOTTOM, self.edges.bottom),
+            side(Sides::LEFT, self.edges.left),
+        )
+    }
 }
 
 impl Layout for BorderLayout {
     fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
-        // If there is a min width / height, make sure the minimum constraints
-        // are matching these
-        let mut constraints = nodes.constraints;
-
-        if let Some(min_width) = self.min_width {
-            constraints.min_width = constraints.min_width.max(min_width);
-        }
-
-        if let Some(min_height) = self.min_height {
-            constraints.min_height = constraints.min_height.max(min_height);
-        }
-
-        // If there is a width / height then make the constraints tight
-        // around the size. This will modify the size to fit within the
-        // constraints first.
-        if let Some(width) = self.width {
-            constraints.make_width_tight(width);
-        }
-
-        if let Some(height) = self.height {
-            constraints.make_height_tight(height);
-        }
+        let border_size = self.inset().size();
+        let width = self
+            .width
+            .and_then(|width| width.resolve(nodes.constraints.max_width));
+        let height = self
+            .height
+            .and_then(|height| height.resolve(nodes.constraints.max_height));
+
+        // The min width / height (plus the border) raise the minimum constraints,
+        // and a width / height makes the constraints tight around the size.
+        // Both are limited by the available space.
+        let own = Constraints {
+            min_width: width
+                .or(self
+                    .min_width
+                    .map(|min| min.saturating_add(border_size.width)))
+                .unwrap_or(0),
+            min_height: height
+                .or(self
+                    .min_height
+                    .map(|min| min.saturating_add(border_size.height)))
+                .unwrap_or(0),
+            max_width: width.unwrap_or(usize::MAX),
+            max_height: height.unwrap_or(usize::MAX),
+        };
+        let constraints = nodes.constraints.intersect(&own);
 
         if constraints == Constraints::ZERO {
             return Ok(Size::ZERO);
         }
 
-        let border_size = self.border_size;
-
         let mut size = Size::ZERO;
 
         nodes.next(|mut node| {
             // Shrink the constraint for the child to fit inside the border
-            let mut constraints = constraints;
-            constraints.max_width = match constraints.max_width.checked_sub(border_size.width) {
-                Some(w) => w,
-                None => return Err(Error::InsufficientSpaceAvailble),
-            };
-
-            constraints.max_height = match constraints.max_height.checked_sub(border_size.height) {
-                Some(h) => h,
-                None => return Err(Error::InsufficientSpaceAvailble),
-            };
-
-            if constraints.min_width > constraints.max_width {
-                constraints.min_width = constraints.max_width;
-            }
-
-            if constraints.min_height > constraints.max_height {
-                constraints.min_height = constraints.max_height;
-            }
+            let mut inner_constraints = constraints.shrink_checked(border_size)?;
+            inner_constraints.min_width = constraints.min_width.saturating_sub(border_size.width);
+            inner_constraints.min_height =
+                constraints.min_height.saturating_sub(border_size.height);
+            inner_constraints.normalize();
 
-            if constraints.max_width == 0 || constraints.max_height == 0 {
+            if inner_constraints.max_width == 0 || inner_constraints.max_height == 0 {
                 return Err(Error::InsufficientSpaceAvailble);
             }
 
-            let inner_size = node.layout(constraints)?;
+            size = node.layout(inner_constraints)? + border_size;
+            Ok(())
+        })?;
 
-            size = inner_size + border_size;
+        // The min size already includes the border
+        Ok(constraints.clamp_size(size))
+    }
+}
 
-            if let Some(min_width) = self.min_width {
-                size.width = size.width.max(min_width);
-            }
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::testing::nodes::test_layout;
 
-            if let Some(min_height) = self.min_height {
-                size.height = size.height.max(min_height);
-            }
+    use super::*;
 
-            Ok(())
-        })?;
+    fn layout(mut border: BorderLayout, text: &str) -> Result<Size> {
+        test_layout(&mut border, text, Constraints::new(20, 10))
+    }
+
+    fn border_layout() -> BorderLayout {
+        BorderLayout {
+            min_width: None,
+            min_height: None,
+            width: None,
+            height: None,
+            edges: Padding::all(1),
+            sides: Sides::ALL,
+        }
+    }
+
+    #[test]
+    fn min_size_excludes_border() {
+        let border = BorderLayout {
+            min_width: Some(10),
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "").unwrap(), Size::new(12, 3));
+
+        let border = BorderLayout {
+            min_width: Some(10),
+            min_height: Some(3),
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "hello").unwrap(), Size::new(12, 5));
+    }
 
-        size.width = size.width.max(constraints.min_width);
-        size.height = size.height.max(constraints.min_height);
+    #[test]
+    fn child_larger_than_min_size() {
+        let border = BorderLayout {
+            min_width: Some(2),
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "hello").unwrap(), Size::new(7, 3));
+    }
+
+    #[test]
+    fn min_size_limited_by_constraints() {
+        let border = BorderLayout {
+            min_width: Some(30),
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "").unwrap(), Size::new(20, 3));
+    }
+
+    #[test]
+    fn size_includes_border() {
+        let border = BorderLayout {
+            width: Some(Dimension::Cells(10)),
+            min_width: Some(10),
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "").unwrap(), Size::new(10, 3));
+    }
+
+    #[test]
+    fn percent_of_available_space() {
+        // The constraints are 20 x 10
+        let border = BorderLayout {
+            width: Some(Dimension::Percent(50.0)),
+            height: Some(Dimension::Percent(25.0)),
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "").unwrap(), Size::new(10, 3));
+
+        // Clamped to the available space
+        let border = BorderLayout {
+            width: Some(Dimension::Percent(200.0)),
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "").unwrap(), Size::new(20, 3));
+    }
+
+    #[test]
+    fn auto_is_child_size() {
+        let border = BorderLayout {
+            width: Some(Dimension::Auto),
+            height: Some(Dimension::Auto),
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "hello").unwrap(), Size::new(7, 3));
+    }
 
-        Ok(size)
+    #[test]
+    fn uneven_edges() {
+        let border = BorderLayout {
+            edges: Padding::new(1, 1, 1, 2),
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "hello").unwrap(), Size::new(8, 3));
+
+        let border = BorderLayout {
+            edges: Padding::new(1, 1, 1, 2),
+            sides: Sides::LEFT,
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "hello").unwrap(), Size::new(7, 1));
+
+        let border = BorderLayout {
+            edges: Padding::new(1, 1, 1, 2),
+            sides: Sides::RIGHT,
+            ..border_layout()
+        };
+        assert_eq!(layout(border, "hello").unwrap(), Size::new(6, 1));
     }
 }
@@ -1,33 +1,369 @@
-use display::Size;
+use anathema_render::Size;
+use anathema_widget_core::error::{Error, Result};
+use anathema_widget_core::layout::{Constraints, Layout};
+use anathema_widget_core::{Dimension, LayoutNodes, Pos};
 
-use crate::ctx::{LayoutCtx, PositionCtx};
-use crate::spacer::Spacer;
-use crate::WidgetContainer;
+/// The size of a single column or row in a [`GridLayout`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Track {
+    /// A fixed number of cells
+    Fixed(usize),
+    /// A percentage of the available space, sized like a `Fixed` track.
+    ///
+    /// If the space is unbounded the track is sized like an `Auto` track.
+    Percent(f32),
+    /// A share of the space left once the fixed and auto tracks are sized.
+    /// A track of `Fraction(2)` is twice the size of a track of `Fraction(1)`.
+    ///
+    /// If the space is unbounded the track is sized like an `Auto` track.
+    Fraction(u16),
+    /// The size of the largest child in the track
+    Auto,
+}
+
+impl Track {
+    // Percentages are resolved into fixed tracks before the layout
+    fn resolve(self, available: usize) -> Self {
+        match self {
+            Self::Percent(percent) => match Dimension::Percent(percent).resolve(available) {
+                Some(cells) => Self::Fixed(cells),
+                None => Self::Auto,
+            },
+            track => track,
+        }
+    }
+}
+
+impl From<Dimension> for Track {
+    fn from(dimension: Dimension) -> Self {
+        match dimension {
+            Dimension::Cells(cells) => Self::Fixed(cells),
+            Dimension::Percent(percent) => Self::Percent(percent),
+            Dimension::Auto => Self::Auto,
+        }
+    }
+}
+
+/// Lay out the children in a grid, from left to right, top to bottom.
+///
+/// Fixed tracks are subtracted from the constraints first, then the auto tracks
+/// are sized by their children, and whatever space is left is distributed between
+/// the fraction tracks.
+///
+/// Children that don't fit in the grid (more children than cells) are given a zero size.
+#[derive(Debug)]
+pub struct GridLayout {
+    pub columns: Vec<Track>,
+    pub rows: Vec<Track>,
+    column_widths: Vec<usize>,
+    row_heights: Vec<usize>,
+}
+
+impl GridLayout {
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Self {
+            columns,
+            rows,
+            column_widths: vec![],
+            row_heights: vec![],
+        }
+    }
+
+    /// The width of each column, from the last layout
+    pub fn column_widths(&self) -> &[usize] {
+        &self.column_widths
+    }
+
+    /// The height of each row, from the last layout
+    pub fn row_heights(&self) -> &[usize] {
+        &self.row_heights
+    }
+
+    /// The position of the cell of the child at `index`, relative to the grid,
+    /// from the last layout.
+    /// Returns `None` if the child doesn't fit in the grid.
+    pub fn cell_pos(&self, index: usize) -> Option<Pos> {
+        let (column, row) = cell(index, self.column_widths.len(), self.row_heights.len())?;
+        let x = self.column_widths[..column].iter().sum::<usize>();
+        let y = self.row_heights[..row].iter().sum::<usize>();
+        Some(Pos::new(x as i32, y as i32))
+    }
+}
+
+impl Layout for GridLayout {
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        let column_tracks = resolve_percent(&self.columns, constraints.max_width);
+        let row_tracks = resolve_percent(&self.rows, constraints.max_height);
+        let columns = column_tracks.len();
+        let rows = row_tracks.len();
+
+        let free_width = free_space(&column_tracks, constraints.max_width)?;
+        let free_height = free_space(&row_tracks, constraints.max_height)?;
+
+        // Measure the children in the auto (and unbounded fraction) tracks
+        let mut content_widths = vec![0; columns];
+        let mut content_heights = vec![0; rows];
+        let mut index = 0;
+        nodes.for_each(|mut node| {
+            let Some((column, row)) = cell(index, columns, rows) else {
+                node.size = Size::ZERO;
+                index += 1;
+                return Ok(());
+            };
+            index += 1;
 
-pub fn layout(widgets: &mut [WidgetContainer], ctx: LayoutCtx) -> Size {
-    let _max_height = ctx.constraints.max_height;
+            let max_width = track_max(column_tracks[column], free_width);
+            let max_height = track_max(row_tracks[row], free_height);
+            let size = node.measure(Constraints::new(max_width, max_height))?;
+            content_widths[column] = content_widths[column].max(size.width);
+            content_heights[row] = content_heights[row].max(size.height);
+            Ok(())
+        })?;
 
-    let mut height = 0;
-    let mut width = 0;
+        self.column_widths = resolve_tracks(&column_tracks, free_width, &content_widths);
+        self.row_heights = resolve_tracks(&row_tracks, free_height, &content_heights);
 
-    for widget in widgets.iter_mut() {
-        // Ignore spacers
-        if widget.kind() == Spacer::KIND {
-            continue;
+        // Lay out every child in its cell
+        for (index, mut node) in nodes.iter_mut().enumerate() {
+            match cell(index, columns, rows) {
+                Some((column, row)) => {
+                    let width = self.column_widths[column];
+                    let height = self.row_heights[row];
+                    node.layout(Constraints::new(width, height))?;
+                }
+                None => node.size = Size::ZERO,
+            }
         }
 
-        let size = widget.layout(ctx.constraints, ctx.force_layout);
+        let width = self.column_widths.iter().sum::<usize>();
+        let height = self.row_heights.iter().sum::<usize>();
 
-        width = width.max(size.width);
-        height = height.max(size.height);
+        Ok(Size::new(
+            width.max(constraints.min_width),
+            height.max(constraints.min_height),
+        ))
     }
+}
 
-    Size::new(width, height)
+// The column and row of the child at `index`, or `None` if there are more
+// children than cells.
+fn cell(index: usize, columns: usize, rows: usize) -> Option<(usize, usize)> {
+    match index < columns * rows {
+        true => Some((index % columns, index / columns)),
+        false => None,
+    }
 }
 
-pub fn position(widgets: &mut [WidgetContainer], ctx: PositionCtx) {
-    for widget in widgets {
-        widget.position(ctx.pos);
+fn resolve_percent(tracks: &[Track], available: usize) -> Vec<Track> {
+    tracks
+        .iter()
+        .map(|track| track.resolve(available))
+        .collect()
+}
+
+// The space left once the fixed tracks are subtracted.
+// Unbounded space stays unbounded.
+fn free_space(tracks: &[Track], available: usize) -> Result<usize> {
+    let fixed = tracks
+        .iter()
+        .map(|track| match track {
+            Track::Fixed(size) => *size,
+            _ => 0,
+        })
+        .sum::<usize>();
+
+    match available {
+        usize::MAX => Ok(usize::MAX),
+        _ if !tracks.is_empty() && available == 0 => Err(Error::InsufficientSpaceAvailble),
+        _ => available
+            .checked_sub(fixed)
+            .ok_or(Error::InsufficientSpaceAvailble),
+    }
+}
+
+// The max size of a child in a track while measuring
+fn track_max(track: Track, free: usize) -> usize {
+    match track {
+        Track::Fixed(size) => size,
+        Track::Percent(_) | Track::Fraction(_) | Track::Auto => free,
+    }
+}
+
+// Resolve the size of each track.
+//
+// `free` is the space left after the fixed tracks, and `content` is the size
+// of the largest child in each track.
+fn resolve_tracks(tracks: &[Track], mut free: usize, content: &[usize]) -> Vec<usize> {
+    let unbounded = free == usize::MAX;
+    let mut sizes = vec![0; tracks.len()];
+
+    for (i, track) in tracks.iter().enumerate() {
+        match track {
+            Track::Fixed(size) => sizes[i] = *size,
+            Track::Fraction(_) if !unbounded => {}
+            Track::Percent(_) | Track::Fraction(_) | Track::Auto => {
+                sizes[i] = content[i].min(free);
+                if !unbounded {
+                    free -= sizes[i];
+                }
+            }
+        }
+    }
+
+    if unbounded {
+        return sizes;
+    }
+
+    let fractions = tracks
+        .iter()
+        .map(|track| match track {
+            Track::Fraction(fr) => *fr as usize,
+            _ => 0,
+        })
+        .sum::<usize>();
+
+    if fractions == 0 {
+        return sizes;
+    }
+
+    // Distribute the space proportionally, and hand out what is lost
+    // to rounding to the first fraction tracks.
+    let mut remainder = free;
+    for (i, track) in tracks.iter().enumerate() {
+        if let Track::Fraction(fr) = track {
+            sizes[i] = free * *fr as usize / fractions;
+            remainder -= sizes[i];
+        }
     }
+
+    for (i, track) in tracks.iter().enumerate() {
+        if remainder == 0 {
+            break;
+        }
+
+        if let Track::Fraction(fr) = track {
+            if *fr > 0 {
+                sizes[i] += 1;
+                remainder -= 1;
+            }
+        }
+    }
+
+    sizes
 }
 
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::testing::nodes::test_layout_many;
+
+    use super::*;
+
+    #[test]
+    fn fixed_tracks_are_subtracted() {
+        let tracks = [Track::Fixed(2), Track::Fraction(1), Track::Fixed(3)];
+        let free = free_space(&tracks, 10).unwrap();
+        assert_eq!(free, 5);
+        assert_eq!(resolve_tracks(&tracks, free, &[0, 0, 0]), vec![2, 5, 3]);
+    }
+
+    #[test]
+    fn distribute_fractions() {
+        let tracks = [Track::Fraction(1), Track::Fraction(2), Track::Fraction(1)];
+        assert_eq!(resolve_tracks(&tracks, 8, &[0, 0, 0]), vec![2, 4, 2]);
+
+        // The remainder goes to the first tracks
+        let tracks = [Track::Fraction(1), Track::Fraction(1), Track::Fraction(1)];
+        assert_eq!(resolve_tracks(&tracks, 11, &[0, 0, 0]), vec![4, 4, 3]);
+    }
+
+    #[test]
+    fn auto_tracks_before_fractions() {
+        let tracks = [Track::Auto, Track::Fraction(1), Track::Auto];
+        assert_eq!(resolve_tracks(&tracks, 10, &[3, 9, 2]), vec![3, 5, 2]);
+
+        // Auto tracks can't grow past the available space
+        let tracks = [Track::Auto, Track::Auto];
+        assert_eq!(resolve_tracks(&tracks, 4, &[3, 3]), vec![3, 1]);
+    }
+
+    #[test]
+    fn unbounded_fractions_are_auto() {
+        let tracks = [Track::Fixed(1), Track::Fraction(1), Track::Auto];
+        let free = free_space(&tracks, usize::MAX).unwrap();
+        assert_eq!(resolve_tracks(&tracks, free, &[0, 4, 2]), vec![1, 4, 2]);
+    }
+
+    #[test]
+    fn percent_tracks_are_fixed() {
+        let tracks = resolve_percent(&[Track::Percent(25.0), Track::Fraction(1)], 10);
+        assert_eq!(tracks, vec![Track::Fixed(3), Track::Fraction(1)]);
+        let free = free_space(&tracks, 10).unwrap();
+        assert_eq!(resolve_tracks(&tracks, free, &[0, 0]), vec![3, 7]);
+
+        // Unbounded percentages are auto
+        let tracks = resolve_percent(&[Track::Percent(25.0)], usize::MAX);
+        assert_eq!(tracks, vec![Track::Auto]);
+    }
+
+    #[test]
+    fn under_constrained_grid() {
+        let tracks = [Track::Fixed(5), Track::Fixed(6)];
+        assert!(matches!(
+            free_space(&tracks, 10),
+            Err(Error::InsufficientSpaceAvailble)
+        ));
+        assert!(matches!(
+            free_space(&[Track::Auto], 0),
+            Err(Error::InsufficientSpaceAvailble)
+        ));
+    }
+
+    #[test]
+    fn overflow_children_have_no_cell() {
+        assert_eq!(cell(0, 2, 2), Some((0, 0)));
+        assert_eq!(cell(3, 2, 2), Some((1, 1)));
+        assert_eq!(cell(4, 2, 2), None);
+        assert_eq!(cell(0, 0, 2), None);
+    }
+
+    #[test]
+    fn layout_children() {
+        let mut grid = GridLayout::new(
+            vec![Track::Fixed(3), Track::Fraction(1), Track::Auto],
+            vec![Track::Auto, Track::Fixed(2)],
+        );
+        let texts = ["a", "bb", "cccc", "dd", "e", "ff", "overflow"];
+        let (size, children) =
+            test_layout_many(&mut grid, &texts, Constraints::new(20, 10)).unwrap();
+
+        // The auto column fits the widest child ("cccc"),
+        // and the fraction column gets the rest
+        assert_eq!(grid.column_widths(), &[3, 13, 4]);
+        assert_eq!(grid.row_heights(), &[1, 2]);
+        assert_eq!(size, Size::new(20, 3));
+        assert_eq!(grid.cell_pos(5), Some(Pos::new(16, 1)));
+
+        let expected = [
+            Size::new(1, 1),
+            Size::new(2, 1),
+            Size::new(4, 1),
+            Size::new(2, 1),
+            Size::new(1, 1),
+            Size::new(2, 1),
+            // There are more children than cells
+            Size::ZERO,
+        ];
+        assert_eq!(children, expected);
+    }
+
+    #[test]
+    fn cell_positions() {
+        let mut grid = GridLayout::new(vec![Track::Fixed(2); 2], vec![Track::Fixed(1); 2]);
+        grid.column_widths = vec![2, 3];
+        grid.row_heights = vec![1, 4];
+        assert_eq!(grid.cell_pos(0), Some(Pos::ZERO));
+        assert_eq!(grid.cell_pos(3), Some(Pos::new(2, 1)));
+        assert_eq!(grid.cell_pos(4), None);
+    }
+}
@@ -1,7 +1,9 @@
 pub mod border;
 pub mod expand;
+pub mod grid;
 pub mod horizontal;
 pub mod many;
+pub mod padding;
 pub mod single;
 pub mod spacers;
 pub mod stacked;
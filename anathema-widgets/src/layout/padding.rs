@@ -1,22 +1,124 @@
-use crate::Padding;
-
-pub fn layout(ctx: LayoutCtx, padding: Padding, child: Option<&mut WidgetContainer>) -> Size {
-    let constraints = Constraints::new(
-        ctx.constraints
-            .max_width
-            .saturating_sub(padding.right + padding.left),
-        ctx.constraints
-            .max_height
-            .saturating_sub(padding.top + padding.bottom),
-    );
-
-    match child {
-        Some(child) => {
-            let mut size = child.layout(LayoutCtx::new(constraints, ctx.force_layout));
-            size.width += padding.right + padding.left;
-            size.height += padding.top + padding.bottom;
-            size
+use anathema_render::Size;
+use anathema_widget_core::error::Result;
+use anathema_widget_core::layout::Layout;
+use anathema_widget_core::{LayoutNodes, Pos};
+
+/// Space around a child, in cells.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Padding {
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub left: usize,
+}
+
+impl Padding {
+    /// No padding
+    pub const ZERO: Self = Self::all(0);
+
+    /// Create a new padding, in the same order as CSS: top, right, bottom, left
+    pub const fn new(top: usize, right: usize, bottom: usize, left: usize) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
         }
-        None => Size::new(padding.left + padding.right, padding.top + padding.bottom),
+    }
+
+    /// The same padding on every side
+    pub const fn all(padding: usize) -> Self {
+        Self::new(padding, padding, padding, padding)
+    }
+
+    /// `horizontal` padding on the left and right, and `vertical` padding
+    /// on the top and bottom.
+    pub const fn symmetric(horizontal: usize, vertical: usize) -> Self {
+        Self::new(vertical, horizontal, vertical, horizontal)
+    }
+
+    /// The total horizontal and vertical padding
+    pub const fn size(&self) -> Size {
+        Size::new(
+            self.left.saturating_add(self.right),
+            self.top.saturating_add(self.bottom),
+        )
+    }
+
+    /// The position of the child, relative to the padding
+    pub const fn offset(&self) -> Pos {
+        Pos::new(self.left as i32, self.top as i32)
+    }
+}
+
+/// Inset a single child by the padding.
+///
+/// The size is the size of the child plus the padding, or just the padding
+/// if there is no child.
+pub struct PaddingLayout(pub Padding);
+
+impl Layout for PaddingLayout {
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        let padding = self.0.size();
+
+        let mut inner_constraints = constraints.shrink_checked(padding)?;
+        inner_constraints.min_width = constraints.min_width.saturating_sub(padding.width);
+        inner_constraints.min_height = constraints.min_height.saturating_sub(padding.height);
+        inner_constraints.normalize();
+
+        let mut size = padding;
+
+        nodes.next(|mut node| {
+            size = node.layout(inner_constraints)? + padding;
+            Ok(())
+        })?;
+
+        size.width = size.width.max(constraints.min_width);
+        size.height = size.height.max(constraints.min_height);
+
+        Ok(size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::error::Error;
+    use anathema_widget_core::layout::Constraints;
+    use anathema_widget_core::testing::nodes::test_layout;
+
+    use super::*;
+
+    fn layout(padding: Padding, constraints: Constraints) -> Result<Size> {
+        test_layout(&mut PaddingLayout(padding), "abc", constraints)
+    }
+
+    #[test]
+    fn constructors() {
+        assert_eq!(Padding::all(1), Padding::new(1, 1, 1, 1));
+        assert_eq!(Padding::symmetric(2, 1), Padding::new(1, 2, 1, 2));
+        assert_eq!(Padding::new(1, 2, 3, 4).size(), Size::new(6, 4));
+        assert_eq!(Padding::new(1, 2, 3, 4).offset(), Pos::new(4, 1));
+    }
+
+    #[test]
+    fn pad_child() {
+        let size = layout(Padding::symmetric(2, 1), Constraints::new(10, 5)).unwrap();
+        assert_eq!(size, Size::new(7, 3));
+    }
+
+    #[test]
+    fn pad_child_with_min_size() {
+        let mut constraints = Constraints::new(10, 5);
+        constraints.min_width = 9;
+        constraints.min_height = 4;
+        let size = layout(Padding::all(1), constraints).unwrap();
+        assert_eq!(size, Size::new(9, 4));
+    }
+
+    #[test]
+    fn padding_exceeds_constraints() {
+        let result = layout(Padding::symmetric(6, 0), Constraints::new(10, 5));
+        assert!(matches!(result, Err(Error::InsufficientSpaceAvailble)));
     }
 }
@@ -27,20 +27,44 @@ pub fn layout(nodes: &mut LayoutNodes<'_, '_, '_>, axis: Axis) -> Result<Size> {
         return Ok(final_size);
     }
 
-    let mut constraints = nodes.constraints;
-    match axis {
-        Axis::Horizontal => {
-            constraints.max_width /= count;
-            constraints.min_width = constraints.max_width;
-        }
-        Axis::Vertical => {
-            constraints.max_height /= count;
-            constraints.min_height = constraints.max_height;
-        }
+    let constraints = nodes.constraints;
+    let leftover = match axis {
+        Axis::Horizontal => constraints.max_width,
+        Axis::Vertical => constraints.max_height,
     };
-    nodes.set_constraints(constraints);
+
+    // Nothing left to absorb, so collapse the spacers
+    if leftover == 0 {
+        for mut spacer in nodes.filter(|widget| widget.kind() == Spacer::KIND) {
+            spacer.size = Size::ZERO;
+        }
+        return Ok(final_size);
+    }
+
+    // Split the leftover space evenly, giving what is lost
+    // to rounding to the first spacers
+    let share = leftover / count;
+    let mut remainder = leftover % count;
 
     for mut spacer in nodes.filter(|widget| widget.kind() == Spacer::KIND) {
+        let mut spacer_size = share;
+        if remainder > 0 {
+            spacer_size += 1;
+            remainder -= 1;
+        }
+
+        let mut constraints = constraints;
+        match axis {
+            Axis::Horizontal => {
+                constraints.max_width = spacer_size;
+                constraints.min_width = spacer_size;
+            }
+            Axis::Vertical => {
+                constraints.max_height = spacer_size;
+                constraints.min_height = spacer_size;
+            }
+        };
+
         let size = spacer.layout(constraints)?;
 
         match axis {
@@ -232,8 +232,9 @@ impl TextLayout {
         &self.lines
     }
 
-    pub fn reset(&mut self, max_size: Size, squash: bool) {
+    pub fn reset(&mut self, max_size: Size, squash: bool, wrap: Wrap) {
         self.max_size = max_size;
+        self.wrap = wrap;
         self.lines.clear();
         self.current_width = 0;
         self.slice_index = 0;
@@ -258,7 +259,7 @@ impl TextLayout {
                 self.current_width = self.tree.left.width();
 
                 self.lines.push(line);
-                if self.lines.len() == self.max_size.height {
+                if self.is_full() {
                     return ProcessOutput::InsufficientSpaceAvailble;
                 }
 
@@ -274,6 +275,10 @@ impl TextLayout {
                     let line = self.tree.drain(Drain::All);
                     self.lines.push(line);
                     self.tree.focus = Focus::Left;
+                    self.current_width = 0;
+                    if self.is_full() {
+                        return ProcessOutput::InsufficientSpaceAvailble;
+                    }
                 }
                 _ if word_break(c) => {
                     self.tree.set_middle(
@@ -297,7 +302,7 @@ impl TextLayout {
             if width + self.current_width > self.max_size.width {
                 let line = self.tree.drain(Drain::Left);
                 self.lines.push(line);
-                if self.lines.len() == self.max_size.height {
+                if self.is_full() {
                     return ProcessOutput::InsufficientSpaceAvailble;
                 }
                 self.current_width = 0;
@@ -320,6 +325,11 @@ impl TextLayout {
         ProcessOutput::Done
     }
 
+    // No more lines can be added without exceeding the max height
+    fn is_full(&self) -> bool {
+        self.lines.len() >= self.max_size.height
+    }
+
     pub fn size(&self) -> Size {
         Size {
             height: self.lines.len(),
@@ -335,6 +345,10 @@ impl TextLayout {
     }
 
     pub fn process(&mut self, s: &str) -> ProcessOutput {
+        if self.is_full() {
+            return ProcessOutput::InsufficientSpaceAvailble;
+        }
+
         match self.wrap {
             Wrap::Normal => self.process_word_wrap(s),
             Wrap::WordBreak => self.process_word_break(s),
@@ -353,6 +367,7 @@ pub enum ProcessOutput {
 pub enum Wrap {
     /// Normal word wrapping. This will break text on hyphen and whitespace.
     /// Trailing whitespace is consumed if it would cause a line break.
+    /// Words longer than the line are broken at the last character that fits.
     #[default]
     Normal,
     /// Insert a newline in the middle of any text
@@ -368,9 +383,9 @@ impl TryFrom<ValueRef<'_>> for Wrap {
     type Error = ();
 
     fn try_from(value: ValueRef<'_>) -> Result<Self, Self::Error> {
-        let wrap = match value {
-            ValueRef::Str("overflow") => Self::Overflow,
-            ValueRef::Str("break") => Self::WordBreak,
+        let wrap = match value.as_str() {
+            Some("overflow" | "nowrap" | "none") => Self::Overflow,
+            Some("break" | "char") => Self::WordBreak,
             _ => Self::Normal,
         };
         Ok(wrap)
@@ -409,9 +424,9 @@ impl TryFrom<ValueRef<'_>> for TextAlignment {
     type Error = ();
 
     fn try_from(value: ValueRef<'_>) -> Result<Self, Self::Error> {
-        let wrap = match value {
-            ValueRef::Str("center" | "centre") => Self::Centre,
-            ValueRef::Str("right") => Self::Right,
+        let wrap = match value.as_str() {
+            Some("center" | "centre") => Self::Centre,
+            Some("right") => Self::Right,
             _ => Self::Left,
         };
         Ok(wrap)
@@ -7,7 +7,9 @@ mod alignment;
 mod border;
 mod expand;
 mod hstack;
+mod padding;
 mod position;
+mod scroll;
 mod spacer;
 mod stack;
 mod text;
@@ -25,7 +27,9 @@ pub use crate::alignment::Alignment;
 pub use crate::border::{Border, BorderStyle, Sides};
 pub use crate::expand::Expand;
 pub use crate::hstack::HStack;
+pub use crate::padding::Padding;
 pub use crate::position::Position;
+pub use crate::scroll::ScrollAcceleration;
 pub use crate::spacer::Spacer;
 pub use crate::text::{Text, TextSpan};
 pub use crate::viewport::Viewport;
@@ -40,6 +44,7 @@ mod factories {
     pub(super) use crate::border::BorderFactory;
     pub(super) use crate::expand::ExpandFactory;
     pub(super) use crate::hstack::HStackFactory;
+    pub(super) use crate::padding::PaddingFactory;
     pub(super) use crate::position::PositionFactory;
     pub(super) use crate::spacer::SpacerFactory;
     pub(super) use crate::text::{SpanFactory, TextFactory};
@@ -55,6 +60,7 @@ pub fn register_default_widgets() -> Result<()> {
         Factory::register("border".to_string(), factories::BorderFactory),
         Factory::register("expand".to_string(), factories::ExpandFactory),
         Factory::register("hstack".to_string(), factories::HStackFactory),
+        Factory::register("padding".to_string(), factories::PaddingFactory),
         Factory::register("position".to_string(), factories::PositionFactory),
         Factory::register("spacer".to_string(), factories::SpacerFactory),
         Factory::register("span".to_string(), factories::SpanFactory),
@@ -0,0 +1,128 @@
+use anathema_render::Size;
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::PositionCtx;
+use anathema_widget_core::error::Result;
+use anathema_widget_core::layout::Layout;
+use anathema_widget_core::{AnyWidget, FactoryContext, LayoutNodes, Nodes, Widget, WidgetFactory};
+
+use crate::layout::padding::{self, PaddingLayout};
+
+/// Space around a single child, without drawing anything.
+///
+/// `padding` sets every side, and `top`, `right`, `bottom` and `left`
+/// override a single side.
+///
+/// ```text
+/// padding [padding: 1, left: 2]
+///     text "Keep your distance"
+/// ```
+#[derive(Debug)]
+pub struct Padding {
+    /// Padding above the child
+    pub top: Value<usize>,
+    /// Padding to the right of the child
+    pub right: Value<usize>,
+    /// Padding below the child
+    pub bottom: Value<usize>,
+    /// Padding to the left of the child
+    pub left: Value<usize>,
+}
+
+impl Padding {
+    /// Widget name.
+    pub const KIND: &'static str = "Padding";
+
+    fn padding(&self) -> padding::Padding {
+        padding::Padding::new(
+            self.top.value_or_default(),
+            self.right.value_or_default(),
+            self.bottom.value_or_default(),
+            self.left.value_or_default(),
+        )
+    }
+}
+
+impl Widget for Padding {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.top.resolve(context, node_id);
+        self.right.resolve(context, node_id);
+        self.bottom.resolve(context, node_id);
+        self.left.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        PaddingLayout(self.padding()).layout(nodes)
+    }
+
+    fn position(&mut self, children: &mut Nodes<'_>, mut ctx: PositionCtx) {
+        if let Some((child, children)) = children.first_mut() {
+            ctx.pos += self.padding().offset();
+            child.position(children, ctx.pos);
+        }
+    }
+}
+
+pub(crate) struct PaddingFactory;
+
+impl WidgetFactory for PaddingFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let side = |name| match ctx.get(name) {
+            Value::Empty => ctx.get("padding"),
+            val => val,
+        };
+
+        let widget = Padding {
+            top: side("top"),
+            right: side("right"),
+            bottom: side("bottom"),
+            left: side("left"),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use crate::testing::test_widget;
+
+    #[test]
+    fn padding() {
+        let border = expression(
+            "border",
+            None,
+            [],
+            [expression(
+                "padding",
+                None,
+                [
+                    ("padding".to_string(), 1.into()),
+                    ("left".to_string(), 2.into()),
+                ],
+                [expression("text", Some("tea".into()), [], [])],
+            )],
+        );
+
+        test_widget(
+            border,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║┌──────┐       ║
+            ║│      │       ║
+            ║│  tea │       ║
+            ║│      │       ║
+            ║└──────┘       ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+}
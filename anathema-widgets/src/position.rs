@@ -253,4 +253,59 @@ mod test {
             ),
         );
     }
+
+    // The text is laid out inside the border (three cells wide), but positioned
+    // two cells to the right, so the last cell of the text ends up outside of the border.
+    fn overflow(overflow: &str, expected: &str) {
+        let expr = expression(
+            "border",
+            None,
+            [
+                ("width".to_string(), 5.into()),
+                ("height".to_string(), 3.into()),
+                ("overflow".to_string(), overflow.into()),
+            ],
+            [expression(
+                "position",
+                None,
+                [
+                    ("left".to_string(), 2.into()),
+                    ("top".to_string(), 0.into()),
+                ],
+                [expression("text", Some("hello".into()), [], [])],
+            )],
+        );
+
+        test_widget(expr, FakeTerm::from_str(expected));
+    }
+
+    #[test]
+    fn overflow_visible() {
+        overflow(
+            "visible",
+            r#"
+            ╔═] Fake term [═╗
+            ║┌───┐          ║
+            ║│  h│l         ║
+            ║└───┘          ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+        );
+    }
+
+    #[test]
+    fn overflow_hidden() {
+        overflow(
+            "hidden",
+            r#"
+            ╔═] Fake term [═╗
+            ║┌───┐          ║
+            ║│  h│          ║
+            ║└───┘          ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+        );
+    }
 }
@@ -0,0 +1,237 @@
+use std::time::{Duration, Instant};
+
+use anathema_widget_core::{Event, KeyCode};
+
+#[derive(Debug, Copy, Clone)]
+struct Held {
+    key: KeyCode,
+    since: Instant,
+    last: Instant,
+}
+
+/// Keyboard scrolling with acceleration, used to drive the `offset` of a [`Viewport`](crate::Viewport).
+///
+/// Holding a scroll key scrolls further per event the longer the key is held,
+/// starting at `min_step` and reaching `max_step` after `ramp`.
+/// Releasing the key (or pressing any other key) resets the acceleration.
+///
+/// Mouse wheel events scroll by `min_step`, without any acceleration
+/// (this requires [`Runtime::enable_mouse`](../anathema_runtime/struct.Runtime.html#structfield.enable_mouse)).
+///
+/// Most terminals send a repeated key press while a key is held, so presses of the same key
+/// that arrive within `repeat_timeout` of each other are treated as a held key.
+///
+/// ```
+/// # use anathema_widgets::ScrollAcceleration;
+/// # use anathema_widget_core::Event;
+/// # fn on_event(event: Event) {
+/// # let mut offset = 0; let content_len = 100; let visible_len = 10;
+/// let mut scroll = ScrollAcceleration::new();
+/// if let Some(new_offset) = scroll.scroll(event, offset, content_len, visible_len) {
+///     offset = new_offset;
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ScrollAcceleration {
+    /// The number of lines to scroll when the key is first pressed (default: 1)
+    pub min_step: usize,
+    /// The max number of lines to scroll per event (default: 10)
+    pub max_step: usize,
+    /// How long a key has to be held to reach `max_step` (default: one second)
+    pub ramp: Duration,
+    /// Key presses further apart than this are not considered a held key (default: 150ms)
+    pub repeat_timeout: Duration,
+    /// Key to scroll backwards, decreasing the offset (default: `Up`)
+    pub back: KeyCode,
+    /// Key to scroll forwards, increasing the offset (default: `Down`)
+    pub forward: KeyCode,
+    held: Option<Held>,
+}
+
+impl ScrollAcceleration {
+    pub fn new() -> Self {
+        Self {
+            min_step: 1,
+            max_step: 10,
+            ramp: Duration::from_secs(1),
+            repeat_timeout: Duration::from_millis(150),
+            back: KeyCode::Up,
+            forward: KeyCode::Down,
+            held: None,
+        }
+    }
+
+    /// Scroll by the number of lines given by [`ScrollAcceleration::step`],
+    /// and return the new offset.
+    /// The offset is clamped so the content never scrolls past its bounds.
+    ///
+    /// Returns `None` if the event isn't a scroll event.
+    pub fn scroll(
+        &mut self,
+        event: Event,
+        offset: i32,
+        content_len: usize,
+        visible_len: usize,
+    ) -> Option<i32> {
+        let step = self.step(event, Instant::now())?;
+        let max_offset = content_len.saturating_sub(visible_len) as i32;
+        Some((offset + step).clamp(0, max_offset.max(0)))
+    }
+
+    /// The number of lines to scroll for a given event, negative for backwards.
+    ///
+    /// Returns `None` if the event isn't a scroll event.
+    pub fn step(&mut self, event: Event, now: Instant) -> Option<i32> {
+        let (key, repeat) = match event {
+            Event::KeyPress(key, ..) => (key, false),
+            Event::KeyRepeat(key, ..) => (key, true),
+            Event::MouseScrollUp(..) | Event::MouseScrollDown(..) => {
+                self.held = None;
+                let step = self.min_step as i32;
+                return match event {
+                    Event::MouseScrollUp(..) => Some(-step),
+                    _ => Some(step),
+                };
+            }
+            Event::KeyRelease(key, ..) => {
+                if self.held.is_some_and(|held| held.key == key) {
+                    self.held = None;
+                }
+                return None;
+            }
+            _ => return None,
+        };
+
+        let direction = match key {
+            key if key == self.back => -1,
+            key if key == self.forward => 1,
+            _ => {
+                self.held = None;
+                return None;
+            }
+        };
+
+        let held = match self.held {
+            Some(held)
+                if held.key == key
+                    && (repeat || now.duration_since(held.last) <= self.repeat_timeout) =>
+            {
+                Held { last: now, ..held }
+            }
+            _ => Held {
+                key,
+                since: now,
+                last: now,
+            },
+        };
+        self.held = Some(held);
+
+        let progress = match self.ramp.is_zero() {
+            true => 1.0,
+            false => {
+                (now.duration_since(held.since).as_secs_f64() / self.ramp.as_secs_f64()).min(1.0)
+            }
+        };
+
+        let max_step = self.max_step.max(self.min_step);
+        let step = self.min_step + ((max_step - self.min_step) as f64 * progress) as usize;
+        Some(step as i32 * direction)
+    }
+}
+
+impl Default for ScrollAcceleration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::{KeyEventState, KeyModifiers};
+
+    use super::*;
+
+    fn press(key: KeyCode) -> Event {
+        Event::KeyPress(key, KeyModifiers::NONE, KeyEventState::NONE)
+    }
+
+    fn release(key: KeyCode) -> Event {
+        Event::KeyRelease(key, KeyModifiers::NONE, KeyEventState::NONE)
+    }
+
+    #[test]
+    fn accelerate_while_held() {
+        let mut scroll = ScrollAcceleration::new();
+        let start = Instant::now();
+        let steps = (0..=10)
+            .map(|i| {
+                let now = start + Duration::from_millis(i * 100);
+                scroll.step(press(KeyCode::Down), now).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(steps, vec![1, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+
+        // Max step
+        let now = start + Duration::from_millis(1100);
+        assert_eq!(scroll.step(press(KeyCode::Down), now), Some(10));
+    }
+
+    #[test]
+    fn reset_on_release() {
+        let mut scroll = ScrollAcceleration::new();
+        let start = Instant::now();
+        scroll.step(press(KeyCode::Up), start);
+        let now = start + Duration::from_millis(100);
+        assert_eq!(scroll.step(press(KeyCode::Up), now), Some(-1));
+        let now = start + Duration::from_millis(200);
+        assert_eq!(scroll.step(press(KeyCode::Up), now), Some(-2));
+
+        scroll.step(release(KeyCode::Up), now);
+        assert_eq!(scroll.step(press(KeyCode::Up), now), Some(-1));
+    }
+
+    #[test]
+    fn reset_after_timeout() {
+        let mut scroll = ScrollAcceleration::new();
+        let start = Instant::now();
+        for i in 0..5 {
+            scroll.step(press(KeyCode::Down), start + Duration::from_millis(i * 100));
+        }
+
+        let now = start + Duration::from_secs(2);
+        assert_eq!(scroll.step(press(KeyCode::Down), now), Some(1));
+    }
+
+    #[test]
+    fn mouse_wheel() {
+        let mut scroll = ScrollAcceleration::new();
+        scroll.min_step = 3;
+        let now = Instant::now();
+        let up = Event::MouseScrollUp(0, 0, KeyModifiers::NONE);
+        let down = Event::MouseScrollDown(0, 0, KeyModifiers::NONE);
+        assert_eq!(scroll.step(down, now), Some(3));
+        assert_eq!(scroll.step(down, now + Duration::from_millis(500)), Some(3));
+        assert_eq!(scroll.step(up, now), Some(-3));
+    }
+
+    #[test]
+    fn clamp_to_content() {
+        let mut scroll = ScrollAcceleration::new();
+        scroll.min_step = 5;
+
+        // Short list: ten lines of content, eight visible
+        let offset = scroll.scroll(press(KeyCode::Down), 0, 10, 8);
+        assert_eq!(offset, Some(2));
+
+        let offset = scroll.scroll(press(KeyCode::Up), 2, 10, 8);
+        assert_eq!(offset, Some(0));
+
+        // Content fits inside the viewport
+        let offset = scroll.scroll(press(KeyCode::Down), 0, 3, 8);
+        assert_eq!(offset, Some(0));
+
+        assert!(scroll.scroll(press(KeyCode::Enter), 0, 10, 8).is_none());
+    }
+}
@@ -12,6 +12,9 @@ use crate::layout::spacers::SpacerLayout;
 /// [`HStack`](crate::HStack) and [`VStack`](crate::VStack), and flows in the
 /// direction of the stack.
 ///
+/// The space left once the other children are laid out is split evenly between the spacers.
+/// If there is no space left the spacer collapses to zero size.
+///
 /// In an `HStack` the spacer will always expand to have the same height as the child with the most
 /// height.
 ///
@@ -151,4 +154,58 @@ mod test {
             ),
         );
     }
+
+    #[test]
+    fn split_leftover_evenly() {
+        let expr = expression(
+            "hstack",
+            None,
+            [],
+            [
+                expression("text", Some("ab".into()), [], []),
+                expression("spacer", None, [], []),
+                expression("text", Some("c".into()), [], []),
+                expression("spacer", None, [], []),
+                expression("text", Some("d".into()), [], []),
+            ],
+        );
+
+        test_widget(
+            expr,
+            FakeTerm::from_str(
+                r#"
+                ╔═] Fake term [═╗
+                ║ab      c     d║
+                ║               ║
+                ╚═══════════════╝
+                "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn collapse_without_leftover() {
+        let expr = expression(
+            "hstack",
+            None,
+            [],
+            [
+                expression("text", Some("0123456789abcde".into()), [], []),
+                expression("spacer", None, [], []),
+                expression("text", Some("x".into()), [], []),
+            ],
+        );
+
+        test_widget(
+            expr,
+            FakeTerm::from_str(
+                r#"
+                ╔═] Fake term [═╗
+                ║0123456789abcde║
+                ║               ║
+                ╚═══════════════╝
+                "#,
+            ),
+        );
+    }
 }
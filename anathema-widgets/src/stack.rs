@@ -3,7 +3,7 @@ use anathema_values::{Context, NodeId, Value};
 use anathema_widget_core::contexts::PositionCtx;
 use anathema_widget_core::error::Result;
 use anathema_widget_core::layout::{Direction, Layout};
-use anathema_widget_core::{Axis, LayoutNodes, Nodes};
+use anathema_widget_core::{Axis, Dimension, LayoutNodes, Nodes};
 
 use crate::layout::horizontal::Horizontal;
 use crate::layout::vertical::Vertical;
@@ -36,10 +36,12 @@ use crate::layout::vertical::Vertical;
 /// ```
 #[derive(Debug)]
 pub struct Stack {
-    /// If a width is provided then the layout constraints will be tight for width
-    pub width: Value<usize>,
-    /// If a height is provided then the layout constraints will be tight for height
-    pub height: Value<usize>,
+    /// If a width is provided then the layout constraints will be tight for width.
+    /// See [`Dimension`] for percentages of the available width.
+    pub width: Value<Dimension>,
+    /// If a height is provided then the layout constraints will be tight for height.
+    /// See [`Dimension`] for percentages of the available height.
+    pub height: Value<Dimension>,
     /// The minimum width. This will force the minimum constrained width to expand to
     /// this value.
     pub min_width: Value<usize>,
@@ -51,7 +53,7 @@ pub struct Stack {
 
 impl Stack {
     /// Creates a new instance of a `VStack`
-    pub fn new(width: Value<usize>, height: Value<usize>, axis: Axis) -> Self {
+    pub fn new(width: Value<Dimension>, height: Value<Dimension>, axis: Axis) -> Self {
         Self {
             width,
             height,
@@ -71,12 +73,22 @@ impl Stack {
     }
 
     pub(crate) fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
-        if let Some(width) = self.width.value() {
+        let max_width = nodes.constraints.max_width;
+        if let Some(width) = self
+            .width
+            .value()
+            .and_then(|width| width.resolve(max_width))
+        {
             nodes.constraints.max_width = nodes.constraints.max_width.min(width);
             nodes.constraints.min_width = nodes.constraints.max_width.min(width);
         }
 
-        if let Some(height) = self.height.value() {
+        let max_height = nodes.constraints.max_height;
+        if let Some(height) = self
+            .height
+            .value()
+            .and_then(|height| height.resolve(max_height))
+        {
             nodes.constraints.max_height = nodes.constraints.max_height.min(height);
             nodes.constraints.min_height = nodes.constraints.max_height.min(height);
         }
@@ -18,8 +18,12 @@ use crate::layout::text::{Line, ProcessOutput, TextAlignment, TextLayout, Wrap};
 /// * foreground
 /// * text-align
 /// * wrap
+/// * hyperlink
 /// ```
 ///
+/// A `hyperlink` (e.g `text [hyperlink: "https://example.com"]: "click me"`) is written
+/// as an OSC 8 sequence if the terminal supports it, otherwise the text is drawn as is.
+///
 /// Note: Spans, unlike other widgets, does not require a widget id
 ///
 /// A `Text` widget will be as wide as its text.
@@ -99,6 +103,7 @@ impl Widget for Text {
         self.layout.reset(
             Size::new(constraints.max_width, constraints.max_height),
             self.squash.value_or(true),
+            self.word_wrap.value_or_default(),
         );
 
         self.layout.process(self.text.str());
@@ -271,6 +276,80 @@ mod test {
         );
     }
 
+    #[test]
+    fn word_wrap_long_word() {
+        test_widget(
+            expression(
+                "text",
+                Some("hi thisisaverylongword ok".into()),
+                [("wrap".into(), ValueExpr::from("word"))],
+                [],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══╗
+            ║hi              ║
+            ║thisisaverylongw║
+            ║ord ok          ║
+            ╚════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn char_wrap_long_word() {
+        // The same text as `word_wrap_long_word`, only breaking on chars
+        test_widget(
+            expression(
+                "text",
+                Some("hi thisisaverylongword ok".into()),
+                [("wrap".into(), ValueExpr::from("char"))],
+                [],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══╗
+            ║hi thisisaverylo║
+            ║ngword ok       ║
+            ║                ║
+            ╚════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn word_wrap_max_height() {
+        test_widget(
+            expression(
+                "border",
+                None,
+                [
+                    ("width".into(), ValueExpr::from(7)),
+                    ("height".into(), ValueExpr::from(4)),
+                ],
+                [expression(
+                    "text",
+                    Some("one two three four five six".into()),
+                    [],
+                    [],
+                )],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══╗
+            ║┌─────┐         ║
+            ║│one  │         ║
+            ║│two  │         ║
+            ║└─────┘         ║
+            ║                ║
+            ╚════════════════╝
+            "#,
+            ),
+        );
+    }
+
     #[test]
     fn break_word_wrap() {
         test_widget(
@@ -1,19 +1,28 @@
 use anathema_render::Size;
 use anathema_values::{Context, NodeId, Value};
-use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::contexts::PositionCtx;
 use anathema_widget_core::error::Result;
 use anathema_widget_core::layout::{Axis, Direction, Layout};
-use anathema_widget_core::{AnyWidget, FactoryContext, LayoutNodes, Nodes, Widget, WidgetFactory};
+use anathema_widget_core::{
+    AnyWidget, FactoryContext, LayoutNodes, Nodes, Overflow, Widget, WidgetFactory,
+};
 
 use crate::layout::many::Many;
+use crate::{Expand, Spacer};
 
 /// A viewport where the children can be rendered with an offset.
+///
+/// The children are clipped to the viewport (`Overflow::Scroll`),
+/// unless the `overflow` attribute says otherwise.
 #[derive(Debug)]
 pub struct Viewport {
     /// Line / cell offset
     pub offset: Value<i32>,
     /// Clamp the horizontal / vertical space, meaning the edge of the content can not surpass the edge of the
     /// visible space.
+    ///
+    /// Note that this has to lay out all the children, not just the visible ones,
+    /// to know the size of the content.
     pub clamp: Value<bool>,
     /// Layout direction.
     /// `Direction::Forward` is the default, and keeps the scroll position on the first child.
@@ -21,6 +30,10 @@ pub struct Viewport {
     pub direction: Value<Direction>,
     /// Vertical or horizontal
     pub axis: Value<Axis>,
+    // The size of the content and the visible space along the axis,
+    // from the last time the viewport was positioned.
+    content_len: usize,
+    visible_len: usize,
 }
 
 impl Viewport {
@@ -33,6 +46,56 @@ impl Viewport {
 
         offset
     }
+
+    /// The largest offset that keeps the edge of the content
+    /// from passing the edge of the visible space.
+    ///
+    /// This is only known once the viewport has been positioned,
+    /// and is used to clamp the offset when scrolling (see [`ScrollAcceleration`](crate::ScrollAcceleration)).
+    pub fn max_offset(&self) -> i32 {
+        self.content_len.saturating_sub(self.visible_len) as i32
+    }
+
+    // Lay out every child, as the size of the content is needed to clamp the offset.
+    // The size of the viewport is the size of the content, constrained to the available space.
+    fn layout_all(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let axis = self.axis.value_or(Axis::Vertical);
+        let max = nodes.constraints;
+        let mut constraints = max;
+        match axis {
+            Axis::Horizontal => constraints.unbound_width(),
+            Axis::Vertical => constraints.unbound_height(),
+        }
+
+        let mut content = Size::ZERO;
+        nodes.for_each(|mut node| {
+            if [Spacer::KIND, Expand::KIND].contains(&node.kind()) {
+                return Ok(());
+            }
+
+            let size = node.layout(constraints)?;
+            match axis {
+                Axis::Horizontal => {
+                    content.width += size.width;
+                    content.height = content.height.max(size.height);
+                }
+                Axis::Vertical => {
+                    content.width = content.width.max(size.width);
+                    content.height += size.height;
+                }
+            }
+            Ok(())
+        })?;
+
+        Ok(Size::new(
+            content
+                .width
+                .clamp(max.min_width, max.max_width.max(max.min_width)),
+            content
+                .height
+                .clamp(max.min_height, max.max_height.max(max.min_height)),
+        ))
+    }
 }
 
 impl Widget for Viewport {
@@ -41,6 +104,10 @@ impl Widget for Viewport {
     }
 
     fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        if self.clamp.value_or_default() {
+            return self.layout_all(nodes);
+        }
+
         let mut many = Many::new(
             self.direction.value_or_default(),
             self.axis.value_or(Axis::Vertical),
@@ -64,32 +131,15 @@ impl Widget for Viewport {
         let mut pos = ctx.pos;
         let mut offset = self.offset();
 
+        let sizes = children.iter_mut().map(|(w, _)| w.size);
+        (self.content_len, self.visible_len) = match axis {
+            Axis::Horizontal => (sizes.map(|s| s.width).sum(), ctx.inner_size.width),
+            Axis::Vertical => (sizes.map(|s| s.height).sum(), ctx.inner_size.height),
+        };
+
         // If the value is clamped, update the offset
         if self.clamp.value_or_default() {
-            match axis {
-                Axis::Horizontal => {
-                    let total = children
-                        .iter_mut()
-                        .map(|(w, _)| w.size.width)
-                        .sum::<usize>();
-
-                    let h = ctx.inner_size.width as i32 + offset;
-                    if h > total as i32 {
-                        offset -= h - total as i32;
-                    }
-                }
-                Axis::Vertical => {
-                    let total = children
-                        .iter_mut()
-                        .map(|(w, _)| w.size.height)
-                        .sum::<usize>();
-
-                    let v = ctx.inner_size.height as i32 + offset;
-                    if v > total as i32 {
-                        offset -= v - total as i32;
-                    }
-                }
-            };
+            offset = offset.min(self.max_offset());
         }
 
         if let Direction::Backwards = direction {
@@ -131,13 +181,8 @@ impl Widget for Viewport {
         }
     }
 
-    fn paint(&mut self, children: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
-        let region = ctx.create_region();
-        for (widget, children) in children.iter_mut() {
-            let mut ctx = ctx.to_unsized();
-            ctx.set_region(&region);
-            widget.paint(children, ctx);
-        }
+    fn overflow(&self) -> Overflow {
+        Overflow::Scroll
     }
 }
 
@@ -150,6 +195,8 @@ impl WidgetFactory for ViewportFactory {
             axis: ctx.get("axis"),
             offset: ctx.get("offset"),
             clamp: ctx.get("clamp"),
+            content_len: 0,
+            visible_len: 0,
         };
 
         Ok(Box::new(widget))
@@ -249,6 +296,31 @@ mod test {
         );
     }
 
+    #[test]
+    fn clamp_to_end_of_content() {
+        let viewport = expression(
+            "viewport",
+            None,
+            [("clamp".into(), true.into()), ("offset".into(), 100.into())],
+            children(10),
+        );
+        test_widget(
+            viewport,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║┌─┐            ║
+            ║│8│            ║
+            ║└─┘            ║
+            ║┌─┐            ║
+            ║│9│            ║
+            ║└─┘            ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
     // #[test]
     // fn vertical_viewport_reversed() {
     //     let viewport = expression("viewport", None, [("direction".into(), "backward".into())], children(10));
@@ -3,7 +3,7 @@ use anathema_values::{Context, NodeId, Value};
 use anathema_widget_core::contexts::PositionCtx;
 use anathema_widget_core::error::Result;
 use anathema_widget_core::{
-    AnyWidget, Axis, FactoryContext, LayoutNodes, Nodes, Widget, WidgetFactory,
+    AnyWidget, Axis, Dimension, FactoryContext, LayoutNodes, Nodes, Widget, WidgetFactory,
 };
 
 use crate::stack::Stack;
@@ -39,7 +39,7 @@ pub struct VStack(Stack);
 
 impl VStack {
     /// Creates a new instance of a `VStack`
-    pub fn new(width: Value<usize>, height: Value<usize>) -> Self {
+    pub fn new(width: Value<Dimension>, height: Value<Dimension>) -> Self {
         Self(Stack::new(width, height, Axis::Vertical))
     }
 }
@@ -3,7 +3,9 @@ use anathema_values::{Context, NodeId, Value};
 use anathema_widget_core::contexts::PositionCtx;
 use anathema_widget_core::error::Result;
 use anathema_widget_core::layout::Layout;
-use anathema_widget_core::{AnyWidget, FactoryContext, LayoutNodes, Nodes, Widget, WidgetFactory};
+use anathema_widget_core::{
+    AnyWidget, Dimension, FactoryContext, LayoutNodes, Nodes, Widget, WidgetFactory,
+};
 
 use crate::layout::stacked::Stacked;
 
@@ -42,10 +44,10 @@ use crate::layout::stacked::Stacked;
 /// `ZStack`.
 #[derive(Debug)]
 pub struct ZStack {
-    /// Width
-    pub width: Value<usize>,
-    /// Height
-    pub height: Value<usize>,
+    /// Width, see [`Dimension`]
+    pub width: Value<Dimension>,
+    /// Height, see [`Dimension`]
+    pub height: Value<Dimension>,
     /// The minimum width of the border. This will force the minimum constrained width to expand to
     /// this value.
     pub min_width: Value<usize>,
@@ -56,7 +58,7 @@ pub struct ZStack {
 
 impl ZStack {
     /// Create a new instance of a `ZStack`
-    pub fn new(width: Value<usize>, height: Value<usize>) -> Self {
+    pub fn new(width: Value<Dimension>, height: Value<Dimension>) -> Self {
         Self {
             width,
             height,
@@ -85,10 +87,20 @@ impl Widget for ZStack {
         if let Some(min_height) = self.min_height.value() {
             nodes.constraints.min_height = nodes.constraints.min_height.max(min_height);
         }
-        if let Some(width) = self.width.value() {
+        let max_width = nodes.constraints.max_width;
+        if let Some(width) = self
+            .width
+            .value()
+            .and_then(|width| width.resolve(max_width))
+        {
             nodes.constraints.make_width_tight(width);
         }
-        if let Some(height) = self.height.value() {
+        let max_height = nodes.constraints.max_height;
+        if let Some(height) = self
+            .height
+            .value()
+            .and_then(|height| height.resolve(max_height))
+        {
             nodes.constraints.make_height_tight(height);
         }
 
@@ -1,10 +1,15 @@
 // -----------------------------------------------------------------------------
 //   - Example: input -
 //   * Add items to a collection based on user input
+//   * Copy the input with ctrl+c and paste with ctrl+v
+//     (run with `--features clipboard` to use the system clipboard)
+//   * Quit with escape
 // -----------------------------------------------------------------------------
 use std::fs::read_to_string;
 
-use anathema::core::{Event, KeyCode, Nodes, View};
+use anathema::core::clipboard::paste;
+use anathema::core::contexts::RuntimeContext;
+use anathema::core::{Event, KeyCode, KeyModifiers, Nodes, View};
 use anathema::runtime::Runtime;
 use anathema::values::{List, State, StateValue};
 use anathema::vm::Templates;
@@ -20,17 +25,35 @@ struct RootView {
 }
 
 impl View for RootView {
-    fn on_event(&mut self, event: Event, _nodes: &mut Nodes<'_>) {
-        if let Event::KeyPress(code, ..) = event {
-            match code {
+    fn on_event_with_context(
+        &mut self,
+        event: Event,
+        _nodes: &mut Nodes<'_>,
+        ctx: &mut RuntimeContext<'_>,
+    ) {
+        match event {
+            Event::CtrlC => {
+                if let Some(clipboard) = ctx.clipboard() {
+                    clipboard.set_text(&self.state.input);
+                }
+            }
+            Event::KeyPress(KeyCode::Char('v'), KeyModifiers::CONTROL, _) => {
+                if let Some(text) = ctx.clipboard().and_then(|clipboard| clipboard.get_text()) {
+                    let cursor = self.state.input.chars().count();
+                    paste(&mut self.state.input, cursor, &text, None);
+                }
+            }
+            Event::KeyPress(code, ..) => match code {
                 KeyCode::Char(c) => self.state.input.push(c),
                 KeyCode::Backspace => drop(self.state.input.pop()),
                 KeyCode::Enter => {
                     let input = self.state.input.drain(..).collect();
                     self.state.output.push_back(input);
                 }
+                KeyCode::Esc => ctx.quit(),
                 _ => {}
-            }
+            },
+            _ => {}
         }
     }
 
@@ -56,6 +79,8 @@ fn main() {
     // Step three: setup runtime
     let mut runtime = Runtime::new(&templates).unwrap();
     runtime.enable_tabindex = false;
+    // Ctrl+c is used to copy the input
+    runtime.enable_ctrlc = false;
 
     // Disable the alt screen if the application panics
     // and you want to see the panic message.